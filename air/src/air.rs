@@ -1,8 +1,13 @@
+use alloc::vec;
+use alloc::vec::Vec;
 use core::ops::{Add, Mul, Sub};
 
 use p3_field::{ExtensionField, Field, FieldAlgebra, FieldExtensionAlgebra};
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+
+use crate::Interaction;
 
 /// An AIR (algebraic intermediate representation).
 pub trait BaseAir<F>: Sync {
@@ -12,6 +17,86 @@ pub trait BaseAir<F>: Sync {
     fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
         None
     }
+
+    /// The number of main trace columns over the extension field used by the prover, which follow
+    /// the `width()` base field columns. Constraints access them through `ExtensionMainBuilder`.
+    fn extension_width(&self) -> usize {
+        0
+    }
+
+    /// The number of public values over the extension field, such as challenges from an outer
+    /// protocol. Constraints access them through `AirBuilderWithExtensionPublicValues`.
+    fn num_extension_public_values(&self) -> usize {
+        0
+    }
+
+    /// The offsets, relative to the current row, of the rows which constraints can refer to.
+    ///
+    /// Row `k` of the main (and preprocessed) matrix seen by `Air::eval` is the row at offset
+    /// `row_offsets()[k]`. The offsets must be strictly increasing and start at zero. The default
+    /// window consists of the current row and the next.
+    fn row_offsets(&self) -> Vec<usize> {
+        vec![0, 1]
+    }
+
+    /// The selectors of fixed rows, or of periodic subsets of rows, which constraints can refer to
+    /// through `AirBuilder::row_selector`, beyond those of the first and last rows.
+    fn row_selectors(&self) -> Vec<RowSelector> {
+        vec![]
+    }
+
+    /// Columns which repeat with a power of two period, such as round constants, each given by its
+    /// values over one period. They are not committed: the prover and verifier evaluate them from
+    /// these values. Constraints access them through `PeriodicAirBuilder`.
+    fn periodic_columns(&self) -> Vec<Vec<F>> {
+        vec![]
+    }
+
+    /// Interactions in which this AIR sends tuples on a bus.
+    fn sends(&self) -> Vec<Interaction<F>>
+    where
+        F: Field,
+    {
+        vec![]
+    }
+
+    /// Interactions in which this AIR receives tuples from a bus.
+    fn receives(&self) -> Vec<Interaction<F>>
+    where
+        F: Field,
+    {
+        vec![]
+    }
+}
+
+/// A set of rows of a trace, on which the selector returned by `AirBuilder::row_selector` is
+/// nonzero.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum RowSelector {
+    /// The row with the given index.
+    Row(usize),
+    /// The rows whose index is `offset` modulo `period`, which must be a power of two.
+    Periodic { period: usize, offset: usize },
+}
+
+impl RowSelector {
+    /// Whether this selector is defined on a trace of the given height.
+    pub const fn is_valid_for_height(&self, height: usize) -> bool {
+        match *self {
+            Self::Row(row) => row < height,
+            Self::Periodic { period, offset } => {
+                period.is_power_of_two() && offset < period && period <= height
+            }
+        }
+    }
+
+    /// Whether this selector is nonzero on row `row`.
+    pub const fn contains(&self, row: usize) -> bool {
+        match *self {
+            Self::Row(r) => row == r,
+            Self::Periodic { period, offset } => row % period == offset,
+        }
+    }
 }
 
 ///  An AIR with 0 or more public values.
@@ -63,6 +148,24 @@ pub trait AirBuilder: Sized {
     }
     fn is_transition_window(&self, size: usize) -> Self::Expr;
 
+    /// A selector which is nonzero exactly on the rows of `selector`, which the AIR must declare in
+    /// `BaseAir::row_selectors`.
+    ///
+    /// The default panics, for builders which are never given an AIR with row selectors.
+    fn row_selector(&self, _selector: RowSelector) -> Self::Expr {
+        panic!("row selectors not supported by this builder")
+    }
+
+    /// A selector which is nonzero exactly on row `row`.
+    fn is_row(&self, row: usize) -> Self::Expr {
+        self.row_selector(RowSelector::Row(row))
+    }
+
+    /// A selector which is nonzero exactly on the rows whose index is `offset` modulo `period`.
+    fn is_periodic_row(&self, period: usize, offset: usize) -> Self::Expr {
+        self.row_selector(RowSelector::Periodic { period, offset })
+    }
+
     /// Returns a sub-builder whose constraints are enforced only when `condition` is nonzero.
     fn when<I: Into<Self::Expr>>(&mut self, condition: I) -> FilteredAirBuilder<'_, Self> {
         FilteredAirBuilder {
@@ -90,6 +193,17 @@ pub trait AirBuilder: Sized {
         self.when(self.is_last_row())
     }
 
+    /// Returns a sub-builder whose constraints are enforced only on row `row`.
+    fn when_row(&mut self, row: usize) -> FilteredAirBuilder<'_, Self> {
+        self.when(self.is_row(row))
+    }
+
+    /// Returns a sub-builder whose constraints are enforced only on the rows whose index is
+    /// `offset` modulo `period`.
+    fn when_periodic_row(&mut self, period: usize, offset: usize) -> FilteredAirBuilder<'_, Self> {
+        self.when(self.is_periodic_row(period, offset))
+    }
+
     /// Returns a sub-builder whose constraints are enforced on all rows except the last.
     fn when_transition(&mut self) -> FilteredAirBuilder<'_, Self> {
         self.when(self.is_transition())
@@ -100,23 +214,30 @@ pub trait AirBuilder: Sized {
         self.when(self.is_transition_window(size))
     }
 
+    /// Assert that `x` is zero. Through `#[track_caller]`, which the other `assert_*` methods
+    /// share, implementations can tell where each constraint was asserted.
+    #[track_caller]
     fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I);
 
+    #[track_caller]
     fn assert_one<I: Into<Self::Expr>>(&mut self, x: I) {
         self.assert_zero(x.into() - Self::Expr::ONE);
     }
 
+    #[track_caller]
     fn assert_eq<I1: Into<Self::Expr>, I2: Into<Self::Expr>>(&mut self, x: I1, y: I2) {
         self.assert_zero(x.into() - y.into());
     }
 
     /// Assert that `x` is a boolean, i.e. either 0 or 1.
+    #[track_caller]
     fn assert_bool<I: Into<Self::Expr>>(&mut self, x: I) {
         let x = x.into();
         self.assert_zero(x.clone() * (x - Self::Expr::ONE));
     }
 
     /// Assert that `x` is ternary, i.e. either 0, 1 or 2.
+    #[track_caller]
     fn assert_tern<I: Into<Self::Expr>>(&mut self, x: I) {
         let x = x.into();
         self.assert_zero(x.clone() * (x.clone() - Self::Expr::ONE) * (x - Self::Expr::TWO));
@@ -129,6 +250,13 @@ pub trait AirBuilderWithPublicValues: AirBuilder {
     fn public_values(&self) -> &[Self::PublicVar];
 }
 
+/// A builder which can read the values of the AIR's periodic columns on the current row.
+pub trait PeriodicAirBuilder: AirBuilder {
+    type PeriodicVar: Into<Self::Expr> + Copy;
+
+    fn periodic_values(&self) -> &[Self::PeriodicVar];
+}
+
 pub trait PairBuilder: AirBuilder {
     fn preprocessed(&self) -> Self::M;
 }
@@ -140,10 +268,12 @@ pub trait ExtensionBuilder: AirBuilder {
 
     type VarEF: Into<Self::ExprEF> + Copy + Send + Sync;
 
+    #[track_caller]
     fn assert_zero_ext<I>(&mut self, x: I)
     where
         I: Into<Self::ExprEF>;
 
+    #[track_caller]
     fn assert_eq_ext<I1, I2>(&mut self, x: I1, y: I2)
     where
         I1: Into<Self::ExprEF>,
@@ -152,6 +282,7 @@ pub trait ExtensionBuilder: AirBuilder {
         self.assert_zero_ext(x.into() - y.into());
     }
 
+    #[track_caller]
     fn assert_one_ext<I>(&mut self, x: I)
     where
         I: Into<Self::ExprEF>,
@@ -160,6 +291,23 @@ pub trait ExtensionBuilder: AirBuilder {
     }
 }
 
+/// A builder for AIRs with public values over the extension field, in addition to their base field
+/// public values.
+pub trait AirBuilderWithExtensionPublicValues: ExtensionBuilder {
+    type PublicVarEF: Into<Self::ExprEF> + Copy;
+
+    fn extension_public_values(&self) -> &[Self::PublicVarEF];
+}
+
+/// A builder for AIRs whose main trace has columns over the extension field, in addition to its
+/// base field columns.
+pub trait ExtensionMainBuilder: ExtensionBuilder {
+    type MEF: Matrix<Self::VarEF>;
+
+    /// The extension field columns of the main trace, over the same window of rows as `main`.
+    fn main_ext(&self) -> Self::MEF;
+}
+
 pub trait PermutationAirBuilder: ExtensionBuilder {
     type MP: Matrix<Self::VarEF>;
 
@@ -204,16 +352,30 @@ impl<'a, AB: AirBuilder> AirBuilder for FilteredAirBuilder<'a, AB> {
         self.inner.is_transition_window(size)
     }
 
+    fn row_selector(&self, selector: RowSelector) -> Self::Expr {
+        self.inner.row_selector(selector)
+    }
+
+    #[track_caller]
     fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
         self.inner.assert_zero(self.condition() * x.into());
     }
 }
 
+impl<'a, AB: PeriodicAirBuilder> PeriodicAirBuilder for FilteredAirBuilder<'a, AB> {
+    type PeriodicVar = AB::PeriodicVar;
+
+    fn periodic_values(&self) -> &[Self::PeriodicVar] {
+        self.inner.periodic_values()
+    }
+}
+
 impl<'a, AB: ExtensionBuilder> ExtensionBuilder for FilteredAirBuilder<'a, AB> {
     type EF = AB::EF;
     type ExprEF = AB::ExprEF;
     type VarEF = AB::VarEF;
 
+    #[track_caller]
     fn assert_zero_ext<I>(&mut self, x: I)
     where
         I: Into<Self::ExprEF>,
@@ -222,6 +384,24 @@ impl<'a, AB: ExtensionBuilder> ExtensionBuilder for FilteredAirBuilder<'a, AB> {
     }
 }
 
+impl<'a, AB: AirBuilderWithExtensionPublicValues> AirBuilderWithExtensionPublicValues
+    for FilteredAirBuilder<'a, AB>
+{
+    type PublicVarEF = AB::PublicVarEF;
+
+    fn extension_public_values(&self) -> &[Self::PublicVarEF] {
+        self.inner.extension_public_values()
+    }
+}
+
+impl<'a, AB: ExtensionMainBuilder> ExtensionMainBuilder for FilteredAirBuilder<'a, AB> {
+    type MEF = AB::MEF;
+
+    fn main_ext(&self) -> Self::MEF {
+        self.inner.main_ext()
+    }
+}
+
 impl<'a, AB: PermutationAirBuilder> PermutationAirBuilder for FilteredAirBuilder<'a, AB> {
     type MP = AB::MP;
 
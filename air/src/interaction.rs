@@ -0,0 +1,33 @@
+use alloc::vec::Vec;
+
+use p3_field::Field;
+
+use crate::VirtualPairCol;
+
+/// An interaction with a bus, in which a tuple of values is sent or received some number of times.
+///
+/// Interactions are checked with a LogUp argument: across all rows (and all tables sharing the
+/// bus), the multiset of tuples sent on each bus must equal the multiset of tuples received.
+#[derive(Clone, Debug)]
+pub struct Interaction<F: Field> {
+    /// The tuple of values, each an affine combination of preprocessed and main columns.
+    pub fields: Vec<VirtualPairCol<F>>,
+    /// The multiplicity with which the tuple is sent or received on each row.
+    pub count: VirtualPairCol<F>,
+    /// The bus on which the interaction takes place.
+    pub argument_index: usize,
+}
+
+impl<F: Field> Interaction<F> {
+    pub const fn new(
+        fields: Vec<VirtualPairCol<F>>,
+        count: VirtualPairCol<F>,
+        argument_index: usize,
+    ) -> Self {
+        Self {
+            fields,
+            count,
+            argument_index,
+        }
+    }
+}
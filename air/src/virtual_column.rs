@@ -107,6 +107,16 @@ impl<F: Field> VirtualPairCol<F> {
         Self::new_main(vec![(a_col, F::ONE), (b_col, F::NEG_ONE)], F::ZERO)
     }
 
+    /// The columns of this affine combination, with their weights.
+    pub fn column_weights(&self) -> &[(PairCol, F)] {
+        &self.column_weights
+    }
+
+    /// The constant term of this affine combination.
+    pub const fn constant_term(&self) -> F {
+        self.constant
+    }
+
     pub fn apply<Expr, Var>(&self, preprocessed: &[Var], main: &[Var]) -> Expr
     where
         F: Into<Expr>,
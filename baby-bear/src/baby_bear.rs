@@ -224,6 +224,12 @@ mod tests {
         let m2_serialized = serde_json::to_string(&m2).unwrap();
         let m2_deserialized: F = serde_json::from_str(&m2_serialized).unwrap();
         assert_eq!(m2, m2_deserialized);
+
+        // Values outside the field are rejected.
+        for value in [F::ORDER_U32, u32::MAX] {
+            let value_serialized = serde_json::to_string(&value).unwrap();
+            assert!(serde_json::from_str::<F>(&value_serialized).is_err());
+        }
     }
 
     test_field!(crate::BabyBear);
@@ -53,12 +53,7 @@ fn main() -> Result<(), impl Debug> {
     let inputs = (0..NUM_HASHES).map(|_| random()).collect::<Vec<_>>();
     let trace = generate_trace_rows::<Val>(inputs);
 
-    let fri_config = FriConfig {
-        log_blowup: 1,
-        num_queries: 100,
-        proof_of_work_bits: 16,
-        mmcs: challenge_mmcs,
-    };
+    let fri_config = FriConfig::new(1, 100, 16, challenge_mmcs);
     type Dft = RecursiveDft<Val>;
     let dft = Dft::new(trace.height() << fri_config.log_blowup);
 
@@ -11,6 +11,7 @@ use p3_util::{log2_ceil_usize, log2_strict_usize};
 use tracing::instrument;
 
 use crate::point::Point;
+use crate::{circle_basis, CircleEvaluations};
 
 /// A twin-coset of the circle group on F. It has a power-of-two size and an arbitrary shift.
 ///
@@ -80,6 +81,56 @@ impl<F: ComplexExtendable> CircleDomain<F> {
         }
     }
 
+    /// The point of row `row` of a trace over this domain, in the order in which `next_point`
+    /// steps through it.
+    fn row_point(&self, row: usize) -> Point<F> {
+        assert!(
+            self.is_standard(),
+            "rows are only ordered for standard position twin cosets"
+        );
+        self.shift + Point::generator(self.log_n) * row
+    }
+
+    /// The log of `period`, along with the coefficients in `circle_basis` of the polynomial over
+    /// the standard position twin coset of that size which takes `values`, in row order.
+    ///
+    /// Doubling `log_n - log_period` times maps row `i` of this domain onto row `i % period` of
+    /// that coset, so composing the two gives the periodic column of `values` on this domain.
+    fn periodic_column_coeffs(&self, values: &[F]) -> (usize, RowMajorMatrix<F>) {
+        assert!(
+            self.is_standard(),
+            "rows are only ordered for standard position twin cosets"
+        );
+        assert!(
+            values.len().is_power_of_two() && values.len() <= self.size(),
+            "the period must be a power of two no larger than the domain"
+        );
+        let log_period = log2_strict_usize(values.len());
+        let coeffs = if log_period == 0 {
+            RowMajorMatrix::new_col(values.to_vec())
+        } else {
+            CircleEvaluations::from_natural_order(
+                Self::standard(log_period),
+                RowMajorMatrix::new_col(values.to_vec()),
+            )
+            .interpolate()
+        };
+        (log_period, coeffs)
+    }
+
+    fn eval_periodic_column<EF: ExtensionField<F>>(
+        &self,
+        log_period: usize,
+        coeffs: &RowMajorMatrix<F>,
+        at: Point<EF>,
+    ) -> EF {
+        if log_period == 0 {
+            return coeffs.values[0].into();
+        }
+        let at = (log_period..self.log_n).fold(at, |at, _| at.double());
+        coeffs.columnwise_dot_product(&circle_basis(at, log_period))[0]
+    }
+
     pub(crate) fn zeroifier<EF: ExtensionField<F>>(&self, at: Point<EF>) -> EF {
         at.v_n(self.log_n) - self.shift.v_n(self.log_n)
     }
@@ -215,6 +266,67 @@ impl<F: ComplexExtendable> PolynomialSpace for CircleDomain<F> {
             inv_zeroifier: sels.iter().map(|s| s.inv_zeroifier).collect(),
         }
     }
+
+    fn selector_at_row<Ext: ExtensionField<Self::Val>>(&self, row: usize, point: Ext) -> Ext {
+        self.s_p(self.row_point(row), Point::from_projective_line(point))
+    }
+
+    fn selector_at_row_on_coset(&self, row: usize, coset: Self) -> Vec<Self::Val> {
+        coset
+            .points()
+            .map(|p| self.selector_at_row(row, p.to_projective_line().unwrap()))
+            .collect()
+    }
+
+    fn periodic_selector<Ext: ExtensionField<Self::Val>>(
+        &self,
+        period: usize,
+        offset: usize,
+        point: Ext,
+    ) -> Ext {
+        assert!(
+            period.is_power_of_two() && period <= self.size(),
+            "the period must be a power of two no larger than the domain"
+        );
+        if period == self.size() {
+            return self.selector_at_row(offset, point);
+        }
+        // The selected rows are a coset of the subgroup of size m = n / period, which this
+        // translation maps onto the standard position twin coset of size m, the roots of v_m.
+        let log_m = self.log_n - log2_strict_usize(period);
+        let at = Point::from_projective_line(point);
+        let translated = at - self.row_point(offset) + Point::generator(log_m + 1);
+        self.zeroifier(at) / translated.v_n(log_m)
+    }
+
+    fn periodic_selector_on_coset(
+        &self,
+        period: usize,
+        offset: usize,
+        coset: Self,
+    ) -> Vec<Self::Val> {
+        coset
+            .points()
+            .map(|p| self.periodic_selector(period, offset, p.to_projective_line().unwrap()))
+            .collect()
+    }
+
+    fn periodic_column_at_point<Ext: ExtensionField<Self::Val>>(
+        &self,
+        values: &[Self::Val],
+        point: Ext,
+    ) -> Ext {
+        let (log_period, coeffs) = self.periodic_column_coeffs(values);
+        self.eval_periodic_column(log_period, &coeffs, Point::from_projective_line(point))
+    }
+
+    fn periodic_column_on_coset(&self, values: &[Self::Val], coset: Self) -> Vec<Self::Val> {
+        let (log_period, coeffs) = self.periodic_column_coeffs(values);
+        coset
+            .points()
+            .map(|p| self.eval_periodic_column(log_period, &coeffs, p))
+            .collect()
+    }
 }
 
 // 0 1 2 .. len-1 len len len-1 .. 1 0 0 1 ..
@@ -378,6 +490,41 @@ mod tests {
         assert_ne!(&is_transition[..n - 1], &vec![F::ZERO; n - 1]);
         assert_eq!(is_transition[n - 1], F::ZERO);
 
+        // Nonzero exactly on the given row, or rows of the given period and offset
+        for row in [0, 5, n - 1] {
+            let selector = coset_to_d(&d.selector_at_row_on_coset(row, coset));
+            for (i, value) in selector.into_iter().enumerate() {
+                assert_eq!(value == F::ZERO, i != row);
+            }
+        }
+        for (period, offset) in [(1, 0), (2, 1), (8, 3), (n, 7)] {
+            let selector = d.periodic_selector_on_coset(period, offset, coset);
+            let mut pt = coset.first_point();
+            for &value in &selector {
+                assert_eq!(value, d.periodic_selector(period, offset, pt));
+                pt = coset.next_point(pt).unwrap();
+            }
+            for (i, value) in coset_to_d(&selector).into_iter().enumerate() {
+                assert_eq!(value == F::ZERO, i % period != offset);
+            }
+        }
+
+        // A periodic column takes its values, repeated, on the domain
+        for period in [1, 2, 8, n] {
+            let values = (0..period)
+                .map(|i| F::from_canonical_usize(3 * i + 1))
+                .collect_vec();
+            let column = d.periodic_column_on_coset(&values, coset);
+            let mut pt = coset.first_point();
+            for &value in &column {
+                assert_eq!(value, d.periodic_column_at_point(&values, pt));
+                pt = coset.next_point(pt).unwrap();
+            }
+            for (i, value) in coset_to_d(&column).into_iter().enumerate() {
+                assert_eq!(value, values[i % period]);
+            }
+        }
+
         // Zeroifier coefficients look like [0.. (n times), 1, 0.. (n-1 times)]
         let z_coeffs = CircleEvaluations::from_natural_order(
             coset,
@@ -6,7 +6,7 @@ use itertools::Itertools;
 use p3_commit::Mmcs;
 use p3_field::extension::ComplexExtendable;
 use p3_field::{batch_multiplicative_inverse, ExtensionField};
-use p3_fri::FriGenericConfig;
+use p3_fri::{fold_matrix_in_pairs, fold_row_in_pairs, FriGenericConfig};
 use p3_matrix::Matrix;
 use p3_util::{log2_strict_usize, reverse_bits_len};
 
@@ -40,11 +40,19 @@ impl<F: ComplexExtendable, EF: ExtensionField<F>, InputProof, InputError: Debug>
         beta: EF,
         evals: impl Iterator<Item = EF>,
     ) -> EF {
-        fold_x_row(index, log_folded_height, beta, evals)
+        fold_row_in_pairs(
+            index,
+            log_folded_height,
+            beta,
+            evals,
+            |index, log_folded_height, beta, lo, hi| {
+                fold_x_row::<F, EF>(index, log_folded_height, beta, [lo, hi].into_iter())
+            },
+        )
     }
 
     fn fold_matrix<M: Matrix<EF>>(&self, beta: EF, m: M) -> Vec<EF> {
-        fold_x(beta, m)
+        fold_matrix_in_pairs(beta, m, |beta, m| fold_x::<F, EF>(beta, m))
     }
 }
 
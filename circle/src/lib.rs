@@ -13,8 +13,8 @@ mod ordering;
 mod pcs;
 mod point;
 mod proof;
-mod prover;
-mod verifier;
+pub mod prover;
+pub mod verifier;
 
 pub use cfft::*;
 pub use domain::*;
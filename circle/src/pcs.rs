@@ -1,15 +1,16 @@
-use alloc::collections::BTreeMap;
+use alloc::collections::{BTreeMap, BTreeSet};
 use alloc::vec;
 use alloc::vec::Vec;
 use core::marker::PhantomData;
 
 use itertools::{izip, Itertools};
 use p3_challenger::{CanObserve, FieldChallenger, GrindingChallenger};
-use p3_commit::{Mmcs, OpenedValues, Pcs, PolynomialSpace};
+use p3_commit::{Mmcs, MultiMmcs, OpenedValues, Pcs, PolynomialSpace, ProverObserver};
 use p3_field::extension::ComplexExtendable;
 use p3_field::{ExtensionField, Field};
 use p3_fri::verifier::FriError;
-use p3_fri::FriConfig;
+pub use p3_fri::BatchOpening;
+use p3_fri::{valid_batch_openings, FriConfig};
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::{Dimensions, Matrix};
 use p3_maybe_rayon::prelude::*;
@@ -21,7 +22,7 @@ use crate::deep_quotient::{deep_quotient_reduce_row, extract_lambda};
 use crate::domain::CircleDomain;
 use crate::folding::{fold_y, fold_y_row, CircleFriConfig, CircleFriGenericConfig};
 use crate::point::Point;
-use crate::prover::prove;
+use crate::prover::prove_observed;
 use crate::verifier::verify;
 use crate::{cfft_permute_index, CfftPermutable, CircleEvaluations, CircleFriProof};
 
@@ -32,20 +33,13 @@ pub struct CirclePcs<Val: Field, InputMmcs, FriMmcs> {
     pub _phantom: PhantomData<Val>,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
-#[serde(bound = "")]
-pub struct BatchOpening<Val: Field, InputMmcs: Mmcs<Val>> {
-    pub(crate) opened_values: Vec<Vec<Val>>,
-    pub(crate) opening_proof: <InputMmcs as Mmcs<Val>>::Proof,
-}
-
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(bound = "")]
 pub struct CircleInputProof<
     Val: Field,
     Challenge: Field,
     InputMmcs: Mmcs<Val>,
-    FriMmcs: Mmcs<Challenge>,
+    FriMmcs: MultiMmcs<Challenge>,
 > {
     input_openings: Vec<BatchOpening<Val, InputMmcs>>,
     first_layer_siblings: Vec<Challenge>,
@@ -67,7 +61,7 @@ pub struct CirclePcsProof<
     Val: Field,
     Challenge: Field,
     InputMmcs: Mmcs<Val>,
-    FriMmcs: Mmcs<Challenge>,
+    FriMmcs: MultiMmcs<Challenge>,
     Witness,
 > {
     first_layer_commitment: FriMmcs::Commitment,
@@ -86,7 +80,7 @@ where
     Val: ComplexExtendable,
     Challenge: ExtensionField<Val>,
     InputMmcs: Mmcs<Val>,
-    FriMmcs: Mmcs<Challenge>,
+    FriMmcs: MultiMmcs<Challenge>,
     Challenger: FieldChallenger<Val> + GrindingChallenger + CanObserve<FriMmcs::Commitment>,
     <InputMmcs as Mmcs<Val>>::ProverData<RowMajorMatrix<Val>>: Clone,
 {
@@ -100,6 +94,12 @@ where
         CircleDomain::standard(log2_strict_usize(degree))
     }
 
+    fn log_max_domain_size(&self) -> usize {
+        // A standard position domain of size `2^log_n` is a coset of the subgroup of size
+        // `2^(log_n + 1)`.
+        Val::CIRCLE_TWO_ADICITY - 1
+    }
+
     fn commit(
         &self,
         evaluations: Vec<(Self::Domain, RowMajorMatrix<Val>)>,
@@ -154,6 +154,15 @@ where
             >,
         )>,
         challenger: &mut Challenger,
+    ) -> (OpenedValues<Challenge>, Self::Proof) {
+        self.open_observed(rounds, challenger, &mut ())
+    }
+
+    fn open_observed(
+        &self,
+        rounds: Vec<(&Self::ProverData, Vec<Vec<Challenge>>)>,
+        challenger: &mut Challenger,
+        observer: &mut impl ProverObserver,
     ) -> (OpenedValues<Challenge>, Self::Proof) {
         // Batch combination challenge
         let alpha: Challenge = challenger.sample_ext_element();
@@ -266,7 +275,7 @@ where
         let g: CircleFriConfig<Val, Challenge, InputMmcs, FriMmcs> =
             CircleFriGenericConfig(PhantomData);
 
-        let fri_proof = prove(&g, &self.fri_config, fri_input, challenger, |index| {
+        let open_input = |index| {
             // CircleFriFolder asks for an extra query index bit, so we use that here to index
             // the first layer fold.
 
@@ -302,7 +311,15 @@ where
                 first_layer_siblings,
                 first_layer_proof,
             }
-        });
+        };
+        let fri_proof = prove_observed(
+            &g,
+            &self.fri_config,
+            fri_input,
+            challenger,
+            open_input,
+            observer,
+        );
 
         (
             values,
@@ -335,14 +352,37 @@ where
         proof: &Self::Proof,
         challenger: &mut Challenger,
     ) -> Result<(), Self::Error> {
+        // The first layer folds each distinct height, and FRI then folds the folded codewords, so
+        // the domains determine the shape of the proof. Check it, but for the commit phase rounds,
+        // which FRI checks, before using the proof.
+        let log_heights = rounds
+            .iter()
+            .flat_map(|(_, mats)| mats)
+            .map(|(domain, _)| domain.log_n + self.fri_config.log_blowup)
+            .collect::<BTreeSet<_>>();
+        let valid_shape = log_heights
+            .first()
+            .is_some_and(|&log_height| log_height > 0)
+            && log_heights
+                .last()
+                .is_some_and(|&log_height| log_height < Val::CIRCLE_TWO_ADICITY)
+            && proof.lambdas.len() == log_heights.len()
+            && proof.fri_proof.query_proofs.iter().all(|qp| {
+                qp.input_proof.first_layer_siblings.len() == log_heights.len()
+                    && valid_batch_openings(&rounds, &qp.input_proof.input_openings)
+            });
+        if !valid_shape {
+            return Err(FriError::InvalidProofShape);
+        }
+
         // Batch combination challenge
         let alpha: Challenge = challenger.sample_ext_element();
         challenger.observe(proof.first_layer_commitment.clone());
         let bivariate_beta: Challenge = challenger.sample_ext_element();
 
-        // +1 to account for first layer
-        let log_global_max_height =
-            proof.fri_proof.commit_phase_commits.len() + self.fri_config.log_blowup + 1;
+        let log_global_max_height = *log_heights.last().unwrap();
+        // FRI folds the codewords after the first layer, descending by height.
+        let log_fri_input_heights = log_heights.iter().rev().map(|&h| h - 1).collect_vec();
 
         let g: CircleFriConfig<Val, Challenge, InputMmcs, FriMmcs> =
             CircleFriGenericConfig(PhantomData);
@@ -350,6 +390,7 @@ where
         verify(
             &g,
             &self.fri_config,
+            &log_fri_input_heights,
             &proof.fri_proof,
             challenger,
             |index, input_proof| {
@@ -373,6 +414,7 @@ where
                         .map(|&height| Dimensions { width: 0, height })
                         .collect_vec();
 
+                    // The shape check above rules out empty batches.
                     let log_batch_max_height =
                         log2_strict_usize(batch_heights.iter().max().copied().unwrap());
 
@@ -384,7 +426,7 @@ where
                             &batch_opening.opened_values,
                             &batch_opening.opening_proof,
                         )
-                        .map_err(InputError::InputMmcsError)?;
+                        .map_err(|err| FriError::InputError(InputError::InputMmcsError(err)))?;
 
                     for (ps_at_x, (mat_domain, mat_points_and_values)) in
                         izip!(&batch_opening.opened_values, mats)
@@ -417,8 +459,6 @@ where
                 let (mut fri_input, fl_dims, fl_leaves): (Vec<_>, Vec<_>, Vec<_>) =
                     izip!(reduced_openings, first_layer_siblings, &proof.lambdas)
                         .map(|((log_height, (_, ro)), &fl_sib, &lambda)| {
-                            assert!(log_height > 0);
-
                             let orig_size = log_height - self.fri_config.log_blowup;
                             let bits_reduced = log_global_max_height - log_height;
                             let orig_idx = cfft_permute_index(index >> bits_reduced, log_height);
@@ -464,7 +504,7 @@ where
                         &fl_leaves,
                         first_layer_proof,
                     )
-                    .map_err(InputError::FirstLayerMmcsError)?;
+                    .map_err(|err| FriError::InputError(InputError::FirstLayerMmcsError(err)))?;
 
                 Ok(fri_input)
             },
@@ -511,12 +551,7 @@ mod tests {
 
         type Challenger = SerializingChallenger32<Val, HashChallenger<u8, ByteHash, 32>>;
 
-        let fri_config = FriConfig {
-            log_blowup: 1,
-            num_queries: 2,
-            proof_of_work_bits: 1,
-            mmcs: challenge_mmcs,
-        };
+        let fri_config = FriConfig::new(1, 2, 1, challenge_mmcs);
 
         type Pcs = CirclePcs<Val, ValMmcs, ChallengeMmcs>;
         let pcs = Pcs {
@@ -3,7 +3,7 @@ use alloc::vec::Vec;
 
 use itertools::{izip, Itertools};
 use p3_challenger::{CanObserve, FieldChallenger, GrindingChallenger};
-use p3_commit::Mmcs;
+use p3_commit::{Mmcs, MultiMmcs};
 use p3_field::{ExtensionField, Field};
 use p3_fri::verifier::FriError;
 use p3_fri::{FriConfig, FriGenericConfig};
@@ -14,41 +14,72 @@ use crate::{CircleCommitPhaseProofStep, CircleFriProof};
 pub fn verify<G, Val, Challenge, M, Challenger>(
     g: &G,
     config: &FriConfig<M>,
+    log_input_heights: &[usize],
     proof: &CircleFriProof<Challenge, M, Challenger::Witness, G::InputProof>,
     challenger: &mut Challenger,
-    open_input: impl Fn(usize, &G::InputProof) -> Result<Vec<(usize, Challenge)>, G::InputError>,
+    open_input: impl Fn(
+        usize,
+        &G::InputProof,
+    ) -> Result<Vec<(usize, Challenge)>, FriError<M::Error, G::InputError>>,
 ) -> Result<(), FriError<M::Error, G::InputError>>
 where
     Val: Field,
     Challenge: ExtensionField<Val>,
-    M: Mmcs<Challenge>,
+    M: MultiMmcs<Challenge>,
     Challenger: FieldChallenger<Val> + GrindingChallenger + CanObserve<M::Commitment>,
     G: FriGenericConfig<Challenge>,
 {
-    let betas: Vec<Challenge> = proof
-        .commit_phase_commits
-        .iter()
-        .map(|comm| {
-            challenger.observe(comm.clone());
-            challenger.sample_ext_element()
+    // Check the shape of the proof before using any of it, so that a malformed proof is rejected
+    // rather than indexed out of bounds or silently truncated.
+    let log_arities = config.log_arities(log_input_heights);
+    let num_rounds = log_arities.len();
+    // Circle FRI folds down to a constant final polynomial.
+    if config.log_final_poly_len != 0
+        || proof.commit_phase_commits.len() != num_rounds
+        || proof.query_proofs.len() != config.num_queries
+        || proof.query_proofs.iter().any(|qp| {
+            qp.commit_phase_openings.len() != num_rounds
+                || izip!(&qp.commit_phase_openings, &log_arities)
+                    .any(|(step, &log_arity)| step.sibling_values.len() != (1 << log_arity) - 1)
         })
-        .collect();
-    challenger.observe_ext_element(proof.final_poly);
-
-    if proof.query_proofs.len() != config.num_queries {
+        || proof.commit_pow_witnesses.len()
+            != if config.commit_proof_of_work_bits > 0 {
+                num_rounds
+            } else {
+                0
+            }
+        || proof.commit_phase_proofs.len() != num_rounds
+    {
         return Err(FriError::InvalidProofShape);
     }
 
+    let mut betas: Vec<Challenge> = Vec::with_capacity(num_rounds);
+    for (round, comm) in proof.commit_phase_commits.iter().enumerate() {
+        challenger.observe(comm.clone());
+        // Check the commit phase PoW, if any, before sampling the round's challenge.
+        if let Some(&witness) = proof.commit_pow_witnesses.get(round) {
+            if !challenger.check_witness(config.commit_proof_of_work_bits, witness) {
+                return Err(FriError::InvalidPowWitness);
+            }
+        }
+        betas.push(challenger.sample_ext_element());
+    }
+    challenger.observe_ext_element(proof.final_poly);
+
     // Check PoW.
     if !challenger.check_witness(config.proof_of_work_bits, proof.pow_witness) {
         return Err(FriError::InvalidPowWitness);
     }
 
-    let log_max_height = proof.commit_phase_commits.len() + config.log_blowup;
+    let log_max_height = log_arities.iter().sum::<usize>() + config.log_blowup;
+
+    // The rows of each commit phase codeword opened by the queries, which are checked against the
+    // round's multi-proof once every query has been folded.
+    let mut opened_rows = vec![(vec![], vec![]); num_rounds];
 
     for qp in &proof.query_proofs {
         let index = challenger.sample_bits(log_max_height + g.extra_query_index_bits());
-        let ro = open_input(index, &qp.input_proof).map_err(FriError::InputError)?;
+        let ro = open_input(index, &qp.input_proof)?;
 
         debug_assert!(
             ro.iter().tuple_windows().all(|((l, _), (r, _))| l > r),
@@ -61,72 +92,84 @@ where
             index >> g.extra_query_index_bits(),
             izip!(
                 &betas,
-                &proof.commit_phase_commits,
-                &qp.commit_phase_openings
+                &log_arities,
+                &qp.commit_phase_openings,
+                &mut opened_rows
             ),
             ro,
             log_max_height,
-        )?;
+        );
 
         if folded_eval != proof.final_poly {
             return Err(FriError::FinalPolyMismatch);
         }
     }
 
+    let mut log_folded_height = log_max_height;
+    for (&log_arity, comm, (index_rows, rows), opening_proof) in izip!(
+        &log_arities,
+        &proof.commit_phase_commits,
+        opened_rows,
+        &proof.commit_phase_proofs
+    ) {
+        log_folded_height -= log_arity;
+        let dims = &[Dimensions {
+            width: 1 << log_arity,
+            height: 1 << log_folded_height,
+        }];
+        config
+            .mmcs
+            .verify_multi_batch(comm, dims, &index_rows, &rows, opening_proof)
+            .map_err(FriError::CommitPhaseMmcsError)?;
+    }
+
     Ok(())
 }
 
-type CommitStep<'a, F, M> = (
+/// A query's step in a commit phase round: the round's challenge and log arity, the query's
+/// opening, and the row indices and rows opened in the round so far, which the query adds its own
+/// to.
+type CommitStep<'a, F> = (
     &'a F,
-    &'a <M as Mmcs<F>>::Commitment,
-    &'a CircleCommitPhaseProofStep<F, M>,
+    &'a usize,
+    &'a CircleCommitPhaseProofStep<F>,
+    &'a mut (Vec<usize>, Vec<Vec<Vec<F>>>),
 );
 
 fn verify_query<'a, G, F, M>(
     g: &G,
     config: &FriConfig<M>,
     mut index: usize,
-    steps: impl Iterator<Item = CommitStep<'a, F, M>>,
+    steps: impl Iterator<Item = CommitStep<'a, F>>,
     reduced_openings: Vec<(usize, F)>,
     log_max_height: usize,
-) -> Result<F, FriError<M::Error, G::InputError>>
+) -> F
 where
     F: Field,
-    M: Mmcs<F> + 'a,
+    M: Mmcs<F>,
     G: FriGenericConfig<F>,
 {
     let mut folded_eval = F::ZERO;
     let mut ro_iter = reduced_openings.into_iter().peekable();
 
-    for (log_folded_height, (&beta, comm, opening)) in izip!((0..log_max_height).rev(), steps) {
-        if let Some((_, ro)) = ro_iter.next_if(|(lh, _)| *lh == log_folded_height + 1) {
+    let mut log_height = log_max_height;
+    for (&beta, &log_arity, opening, (index_rows, rows)) in steps {
+        if let Some((_, ro)) = ro_iter.next_if(|(lh, _)| *lh == log_height) {
             folded_eval += ro;
         }
 
-        let index_sibling = index ^ 1;
-        let index_pair = index >> 1;
+        let index_row = index >> log_arity;
 
-        let mut evals = vec![folded_eval; 2];
-        evals[index_sibling % 2] = opening.sibling_value;
+        let mut evals = opening.sibling_values.clone();
+        evals.insert(index & ((1 << log_arity) - 1), folded_eval);
 
-        let dims = &[Dimensions {
-            width: 2,
-            height: 1 << log_folded_height,
-        }];
-        config
-            .mmcs
-            .verify_batch(
-                comm,
-                dims,
-                index_pair,
-                &[evals.clone()],
-                &opening.opening_proof,
-            )
-            .map_err(FriError::CommitPhaseMmcsError)?;
+        index_rows.push(index_row);
+        rows.push(vec![evals.clone()]);
 
-        index = index_pair;
+        index = index_row;
+        log_height -= log_arity;
 
-        folded_eval = g.fold_row(index, log_folded_height, beta, evals.into_iter());
+        folded_eval = g.fold_row(index, log_height, beta, evals.into_iter());
     }
 
     debug_assert!(index < config.blowup(), "index was {}", index);
@@ -135,5 +178,5 @@ where
         "verifier reduced_openings were not in descending order?"
     );
 
-    Ok(folded_eval)
+    folded_eval
 }
@@ -53,6 +53,43 @@ pub trait PolynomialSpace: Copy {
 
     // Unnormalized
     fn selectors_on_coset(&self, coset: Self) -> LagrangeSelectors<Vec<Self::Val>>;
+
+    /// The selector of row `row` of this domain, which vanishes on every other row, at `point`.
+    // Unnormalized
+    fn selector_at_row<Ext: ExtensionField<Self::Val>>(&self, row: usize, point: Ext) -> Ext;
+
+    // Unnormalized
+    fn selector_at_row_on_coset(&self, row: usize, coset: Self) -> Vec<Self::Val>;
+
+    /// The selector of the rows of this domain whose index is `offset` modulo `period`, which
+    /// vanishes on every other row, at `point`. `period` must be a power of two no larger than
+    /// the domain.
+    // Unnormalized
+    fn periodic_selector<Ext: ExtensionField<Self::Val>>(
+        &self,
+        period: usize,
+        offset: usize,
+        point: Ext,
+    ) -> Ext;
+
+    // Unnormalized
+    fn periodic_selector_on_coset(
+        &self,
+        period: usize,
+        offset: usize,
+        coset: Self,
+    ) -> Vec<Self::Val>;
+
+    /// The value at `point` of the polynomial of degree below the size of this domain which takes
+    /// the value `values[i % values.len()]` on row `i`. The number of values must be a power of two
+    /// no larger than the domain.
+    fn periodic_column_at_point<Ext: ExtensionField<Self::Val>>(
+        &self,
+        values: &[Self::Val],
+        point: Ext,
+    ) -> Ext;
+
+    fn periodic_column_on_coset(&self, values: &[Self::Val], coset: Self) -> Vec<Self::Val>;
 }
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
@@ -67,6 +104,74 @@ impl<Val: TwoAdicField> TwoAdicMultiplicativeCoset<Val> {
     fn gen(&self) -> Val {
         Val::two_adic_generator(self.log_n)
     }
+
+    /// The value `Z_H(x) / denominator(x)` at each point `x` of `coset`, where `Z_H` is the
+    /// vanishing polynomial of this subgroup.
+    fn vanishing_ratio_on_coset(&self, coset: Self, denominator: impl Fn(Val) -> Val) -> Vec<Val> {
+        assert_eq!(self.shift, Val::ONE);
+        assert_ne!(coset.shift, Val::ONE);
+        assert!(coset.log_n >= self.log_n);
+        let rate_bits = coset.log_n - self.log_n;
+
+        let s_pow_n = coset.shift.exp_power_of_2(self.log_n);
+        // evals of Z_H(X) = X^n - 1, which repeat every 2^rate_bits points
+        let evals = Val::two_adic_generator(rate_bits)
+            .powers()
+            .take(1 << rate_bits)
+            .map(|x| s_pow_n * x - Val::ONE)
+            .collect_vec();
+
+        let denoms = cyclic_subgroup_coset_known_order(coset.gen(), coset.shift, 1 << coset.log_n)
+            .map(denominator)
+            .collect_vec();
+        evals
+            .iter()
+            .cycle()
+            .zip(batch_multiplicative_inverse(&denoms))
+            .map(|(&z_h, inv)| z_h * inv)
+            .collect()
+    }
+
+    /// The value of `X^(n / period)` on the rows whose index is `offset` modulo `period`.
+    fn periodic_subgroup_value(&self, period: usize, offset: usize) -> Val {
+        assert!(
+            period.is_power_of_two() && period <= self.size(),
+            "the period must be a power of two no larger than the domain"
+        );
+        Val::two_adic_generator(log2_strict_usize(period)).exp_u64(offset as u64)
+    }
+
+    /// The log of the number of times a period of `period` rows repeats in this domain.
+    fn log_repetitions(&self, period: usize) -> usize {
+        assert!(
+            period.is_power_of_two() && period <= self.size(),
+            "the period must be a power of two no larger than the domain"
+        );
+        self.log_n - log2_strict_usize(period)
+    }
+}
+
+/// The value at `point` of the polynomial of degree below `values.len()`, a power of two, which
+/// takes the value `values[i]` on the `i`th power of the generator of the subgroup of that size.
+fn interpolate_subgroup<F: TwoAdicField, Ext: ExtensionField<F>>(values: &[F], point: Ext) -> Ext {
+    let log_size = log2_strict_usize(values.len());
+    let subgroup = F::two_adic_generator(log_size)
+        .powers()
+        .take(values.len())
+        .collect_vec();
+    let diffs = subgroup.iter().map(|&x| point - x).collect_vec();
+    if let Some(i) = diffs.iter().position(|diff| diff.is_zero()) {
+        return values[i].into();
+    }
+    // The barycentric formula: (X^m - 1) / m * sum_i values[i] x_i / (X - x_i).
+    let sum: Ext = values
+        .iter()
+        .zip(subgroup)
+        .zip(batch_multiplicative_inverse(&diffs))
+        .map(|((&value, x), inv)| inv * (value * x))
+        .sum();
+    sum * (point.exp_power_of_2(log_size) - Ext::ONE)
+        * F::from_canonical_usize(values.len()).inverse()
 }
 
 impl<Val: TwoAdicField> PolynomialSpace for TwoAdicMultiplicativeCoset<Val> {
@@ -146,23 +251,11 @@ impl<Val: TwoAdicField> PolynomialSpace for TwoAdicMultiplicativeCoset<Val> {
         let xs = cyclic_subgroup_coset_known_order(coset.gen(), coset.shift, 1 << coset.log_n)
             .collect_vec();
 
-        let single_point_selector = |i: u64| {
-            let coset_i = self.gen().exp_u64(i);
-            let denoms = xs.iter().map(|&x| x - coset_i).collect_vec();
-            let invs = batch_multiplicative_inverse(&denoms);
-            evals
-                .iter()
-                .cycle()
-                .zip(invs)
-                .map(|(&z_h, inv)| z_h * inv)
-                .collect_vec()
-        };
-
         let subgroup_last = self.gen().inverse();
 
         LagrangeSelectors {
-            is_first_row: single_point_selector(0),
-            is_last_row: single_point_selector((1 << self.log_n) - 1),
+            is_first_row: self.selector_at_row_on_coset(0, coset),
+            is_last_row: self.selector_at_row_on_coset((1 << self.log_n) - 1, coset),
             is_transition: xs.into_iter().map(|x| x - subgroup_last).collect(),
             inv_zeroifier: batch_multiplicative_inverse(&evals)
                 .into_iter()
@@ -171,4 +264,61 @@ impl<Val: TwoAdicField> PolynomialSpace for TwoAdicMultiplicativeCoset<Val> {
                 .collect(),
         }
     }
+
+    fn selector_at_row<Ext: ExtensionField<Val>>(&self, row: usize, point: Ext) -> Ext {
+        let unshifted_point = point * self.shift.inverse();
+        let z_h = unshifted_point.exp_power_of_2(self.log_n) - Ext::ONE;
+        z_h / (unshifted_point - self.gen().exp_u64(row as u64))
+    }
+
+    fn selector_at_row_on_coset(&self, row: usize, coset: Self) -> Vec<Val> {
+        let subgroup_row = self.gen().exp_u64(row as u64);
+        self.vanishing_ratio_on_coset(coset, |x| x - subgroup_row)
+    }
+
+    fn periodic_selector<Ext: ExtensionField<Val>>(
+        &self,
+        period: usize,
+        offset: usize,
+        point: Ext,
+    ) -> Ext {
+        // The rows selected are the roots of X^(n / period) - g^(offset * n / period).
+        let value = self.periodic_subgroup_value(period, offset);
+        let unshifted_point = point * self.shift.inverse();
+        let z_h = unshifted_point.exp_power_of_2(self.log_n) - Ext::ONE;
+        z_h / (unshifted_point.exp_power_of_2(self.log_n - log2_strict_usize(period)) - value)
+    }
+
+    fn periodic_selector_on_coset(&self, period: usize, offset: usize, coset: Self) -> Vec<Val> {
+        let value = self.periodic_subgroup_value(period, offset);
+        let log_subset_size = self.log_n - log2_strict_usize(period);
+        self.vanishing_ratio_on_coset(coset, |x| x.exp_power_of_2(log_subset_size) - value)
+    }
+
+    fn periodic_column_at_point<Ext: ExtensionField<Val>>(
+        &self,
+        values: &[Val],
+        point: Ext,
+    ) -> Ext {
+        // On row i, (X / shift)^(n / period) is the i-th power of the generator of the subgroup
+        // of size period, so the column is an interpolant over that subgroup composed with it.
+        let log_repetitions = self.log_repetitions(values.len());
+        let unshifted_point = point * self.shift.inverse();
+        interpolate_subgroup(values, unshifted_point.exp_power_of_2(log_repetitions))
+    }
+
+    fn periodic_column_on_coset(&self, values: &[Val], coset: Self) -> Vec<Val> {
+        // (X / shift)^(n / period) takes at most `coset.size() * period / n` distinct values on
+        // the coset, which repeat.
+        let log_repetitions = self.log_repetitions(values.len());
+        let num_distinct = (coset.size() >> log_repetitions).max(1);
+        let distinct = cyclic_subgroup_coset_known_order(
+            coset.gen().exp_power_of_2(log_repetitions),
+            (coset.shift * self.shift.inverse()).exp_power_of_2(log_repetitions),
+            num_distinct,
+        )
+        .map(|x| interpolate_subgroup(values, x))
+        .collect_vec();
+        distinct.into_iter().cycle().take(coset.size()).collect()
+    }
 }
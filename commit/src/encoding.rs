@@ -0,0 +1,836 @@
+//! A compact, canonical and versioned binary encoding for proofs.
+//!
+//! The encoding is a serde data format, so it applies to any proof type deriving `Serialize` and
+//! `Deserialize`, such as uni-stark's `Proof`, `FriProof` and `CirclePcsProof`:
+//!
+//! - an encoding starts with the byte `PROOF_ENCODING_VERSION`;
+//! - integers are fixed width and little endian, so a field element, which serializes as its
+//!   canonical integer, takes 4 or 8 bytes;
+//! - sequences, maps, strings and byte arrays are prefixed with their length as a `u32`;
+//! - structs, tuples and arrays are their elements in order, with neither names nor lengths;
+//! - bools and option tags are a byte of 0 or 1, and enum variants are prefixed with their index
+//!   as a `u32`.
+//!
+//! Decoding accepts only the exact encoding of a value, without trailing bytes, so each value has
+//! a single encoding. Floats and chars are not supported.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{self, Display};
+
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
+};
+use serde::ser::{self, Serialize};
+
+/// The version of the encoding produced by `encode_proof`, which is its first byte.
+pub const PROOF_ENCODING_VERSION: u8 = 1;
+
+/// Encode `proof`, or any other serializable value, in the canonical binary encoding.
+///
+/// # Panics
+///
+/// Panics if the value contains a float or a char, or a sequence whose length is unknown upfront.
+pub fn encode_proof<T: Serialize + ?Sized>(proof: &T) -> Vec<u8> {
+    let mut encoder = Encoder::new(None);
+    proof
+        .serialize(&mut encoder)
+        .unwrap_or_else(|err| panic!("{err}"));
+    encoder.output
+}
+
+/// Decode a value encoded by `encode_proof`.
+pub fn decode_proof<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, ProofDecodeError> {
+    let (&version, input) = bytes.split_first().ok_or(ProofDecodeError::UnexpectedEnd)?;
+    if version != PROOF_ENCODING_VERSION {
+        return Err(ProofDecodeError::UnsupportedVersion(version));
+    }
+    let mut decoder = Decoder { input };
+    let value = T::deserialize(&mut decoder)?;
+    if !decoder.input.is_empty() {
+        return Err(ProofDecodeError::TrailingBytes(decoder.input.len()));
+    }
+    Ok(value)
+}
+
+/// The number of bytes each component of `proof` takes in its encoding, in order.
+///
+/// Components are the struct fields and tuple elements of `proof`, named by their path, such as
+/// `opening_proof.query_proofs`, or `opening_proof.1` for the second element of a tuple. Fields
+/// nested more than `max_depth` deep are counted in their ancestor, as are the contents of
+/// sequences, maps, options and enums. The version byte is the component `version`, so the sizes
+/// sum to the length of the encoding.
+///
+/// # Panics
+///
+/// Panics under the same conditions as `encode_proof`.
+pub fn encoded_size_by_component<T: Serialize + ?Sized>(
+    proof: &T,
+    max_depth: usize,
+) -> Vec<(String, usize)> {
+    let mut encoder = Encoder::new(Some(Components {
+        max_depth,
+        path: Vec::new(),
+        suspended: 0,
+        sizes: alloc::vec![("version".to_string(), 1)],
+    }));
+    proof
+        .serialize(&mut encoder)
+        .unwrap_or_else(|err| panic!("{err}"));
+    let mut components = encoder.components.unwrap();
+    let counted: usize = components.sizes.iter().map(|(_, size)| size).sum();
+    if counted < encoder.output.len() {
+        components
+            .sizes
+            .push((String::new(), encoder.output.len() - counted));
+    }
+    components.sizes
+}
+
+/// An error encountered while decoding a proof.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProofDecodeError {
+    /// The input is in a version of the encoding which is not supported.
+    UnsupportedVersion(u8),
+    /// The input ended in the middle of the value.
+    UnexpectedEnd,
+    /// The input continued for this many bytes after the value.
+    TrailingBytes(usize),
+    /// A length prefix exceeded the number of bytes left in the input.
+    InvalidLength(usize),
+    /// A bool or option tag was neither 0 nor 1.
+    InvalidTag(u8),
+    /// A string was not valid UTF-8.
+    InvalidUtf8,
+    /// The value's type uses a feature the encoding does not support.
+    Unsupported(&'static str),
+    /// The value rejected the data decoded for it, such as an integer outside a field.
+    Invalid(String),
+}
+
+impl Display for ProofDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported proof encoding version {version}")
+            }
+            Self::UnexpectedEnd => write!(f, "unexpected end of input"),
+            Self::TrailingBytes(len) => write!(f, "{len} trailing bytes after the proof"),
+            Self::InvalidLength(len) => write!(f, "length {len} exceeds the remaining input"),
+            Self::InvalidTag(tag) => write!(f, "invalid tag {tag}"),
+            Self::InvalidUtf8 => write!(f, "invalid UTF-8 in string"),
+            Self::Unsupported(what) => write!(f, "{what} are not supported"),
+            Self::Invalid(msg) => write!(f, "{msg}"),
+        }
+    }
+}
+
+impl de::StdError for ProofDecodeError {}
+
+impl de::Error for ProofDecodeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self::Invalid(msg.to_string())
+    }
+}
+
+/// The error of a value which cannot be encoded.
+#[derive(Debug)]
+struct EncodeError(String);
+
+impl Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cannot encode proof: {}", self.0)
+    }
+}
+
+impl ser::StdError for EncodeError {}
+
+impl ser::Error for EncodeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+/// The sizes of the components encoded so far, for `encoded_size_by_component`.
+struct Components {
+    max_depth: usize,
+    /// The names of the fields being encoded, outermost first.
+    path: Vec<String>,
+    /// How many sequences, maps, options or enums are being encoded, whose contents are counted
+    /// as a whole.
+    suspended: usize,
+    sizes: Vec<(String, usize)>,
+}
+
+struct Encoder {
+    output: Vec<u8>,
+    components: Option<Components>,
+}
+
+impl Encoder {
+    fn new(components: Option<Components>) -> Self {
+        let output = alloc::vec![PROOF_ENCODING_VERSION];
+        Self { output, components }
+    }
+
+    fn encode_len(&mut self, len: Option<usize>) -> Result<(), EncodeError> {
+        let len = len.ok_or_else(|| EncodeError("sequences of unknown length".to_string()))?;
+        let len = u32::try_from(len).map_err(|_| EncodeError("lengths over u32".to_string()))?;
+        self.output.extend(len.to_le_bytes());
+        Ok(())
+    }
+
+    /// Encode a field or tuple element, counting it as a component if it is shallow enough.
+    fn encode_component<T: Serialize + ?Sized>(
+        &mut self,
+        name: impl FnOnce() -> String,
+        value: &T,
+    ) -> Result<(), EncodeError> {
+        let tracked = self.components.as_ref().is_some_and(|components| {
+            components.suspended == 0 && components.path.len() < components.max_depth
+        });
+        if !tracked {
+            return value.serialize(self);
+        }
+
+        let start = self.output.len();
+        let components = self.components.as_mut().unwrap();
+        let num_sizes = components.sizes.len();
+        components.path.push(name());
+        value.serialize(&mut *self)?;
+        let components = self.components.as_mut().unwrap();
+        // A component whose own fields were not counted is counted as a whole.
+        if components.sizes.len() == num_sizes {
+            let size = self.output.len() - start;
+            components.sizes.push((components.path.join("."), size));
+        }
+        components.path.pop();
+        Ok(())
+    }
+
+    /// Encode a value whose fields are not counted as components.
+    fn encode_opaque<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), EncodeError> {
+        if let Some(components) = self.components.as_mut() {
+            components.suspended += 1;
+        }
+        let result = value.serialize(&mut *self);
+        if let Some(components) = self.components.as_mut() {
+            components.suspended -= 1;
+        }
+        result
+    }
+}
+
+/// Encodes the elements of a compound value, counting the `index` so far.
+struct Compound<'a> {
+    encoder: &'a mut Encoder,
+    index: usize,
+}
+
+impl<'a> Compound<'a> {
+    fn new(encoder: &'a mut Encoder) -> Self {
+        Self { encoder, index: 0 }
+    }
+
+    fn element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), EncodeError> {
+        let index = self.index;
+        self.index += 1;
+        self.encoder.encode_component(|| index.to_string(), value)
+    }
+}
+
+impl<'a> ser::Serializer for &'a mut Encoder {
+    type Ok = ();
+    type Error = EncodeError;
+    type SerializeSeq = Compound<'a>;
+    type SerializeTuple = Compound<'a>;
+    type SerializeTupleStruct = Compound<'a>;
+    type SerializeTupleVariant = Compound<'a>;
+    type SerializeMap = Compound<'a>;
+    type SerializeStruct = Compound<'a>;
+    type SerializeStructVariant = Compound<'a>;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn serialize_bool(self, v: bool) -> Result<(), EncodeError> {
+        self.output.push(v as u8);
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), EncodeError> {
+        self.output.extend(v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), EncodeError> {
+        self.output.extend(v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), EncodeError> {
+        self.output.extend(v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), EncodeError> {
+        self.output.extend(v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), EncodeError> {
+        self.output.push(v);
+        Ok(())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), EncodeError> {
+        self.output.extend(v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), EncodeError> {
+        self.output.extend(v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), EncodeError> {
+        self.output.extend(v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<(), EncodeError> {
+        Err(EncodeError("floats".to_string()))
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<(), EncodeError> {
+        Err(EncodeError("floats".to_string()))
+    }
+
+    fn serialize_char(self, _v: char) -> Result<(), EncodeError> {
+        Err(EncodeError("chars".to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), EncodeError> {
+        self.serialize_bytes(v.as_bytes())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), EncodeError> {
+        self.encode_len(Some(v.len()))?;
+        self.output.extend_from_slice(v);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), EncodeError> {
+        self.output.push(0);
+        Ok(())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), EncodeError> {
+        self.output.push(1);
+        self.encode_opaque(value)
+    }
+
+    fn serialize_unit(self) -> Result<(), EncodeError> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), EncodeError> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<(), EncodeError> {
+        self.serialize_u32(variant_index)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), EncodeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<(), EncodeError> {
+        self.output.extend(variant_index.to_le_bytes());
+        self.encode_opaque(value)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Compound<'a>, EncodeError> {
+        self.encode_len(len)?;
+        Ok(Compound::new(self))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Compound<'a>, EncodeError> {
+        Ok(Compound::new(self))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'a>, EncodeError> {
+        Ok(Compound::new(self))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'a>, EncodeError> {
+        self.output.extend(variant_index.to_le_bytes());
+        Ok(Compound::new(self))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Compound<'a>, EncodeError> {
+        self.encode_len(len)?;
+        Ok(Compound::new(self))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'a>, EncodeError> {
+        Ok(Compound::new(self))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'a>, EncodeError> {
+        self.output.extend(variant_index.to_le_bytes());
+        Ok(Compound::new(self))
+    }
+}
+
+impl ser::SerializeSeq for Compound<'_> {
+    type Ok = ();
+    type Error = EncodeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), EncodeError> {
+        self.encoder.encode_opaque(value)
+    }
+
+    fn end(self) -> Result<(), EncodeError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for Compound<'_> {
+    type Ok = ();
+    type Error = EncodeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), EncodeError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), EncodeError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for Compound<'_> {
+    type Ok = ();
+    type Error = EncodeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), EncodeError> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), EncodeError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleVariant for Compound<'_> {
+    type Ok = ();
+    type Error = EncodeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), EncodeError> {
+        self.encoder.encode_opaque(value)
+    }
+
+    fn end(self) -> Result<(), EncodeError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for Compound<'_> {
+    type Ok = ();
+    type Error = EncodeError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), EncodeError> {
+        self.encoder.encode_opaque(key)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), EncodeError> {
+        self.encoder.encode_opaque(value)
+    }
+
+    fn end(self) -> Result<(), EncodeError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for Compound<'_> {
+    type Ok = ();
+    type Error = EncodeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), EncodeError> {
+        self.encoder.encode_component(|| key.to_string(), value)
+    }
+
+    fn end(self) -> Result<(), EncodeError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for Compound<'_> {
+    type Ok = ();
+    type Error = EncodeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), EncodeError> {
+        self.encoder.encode_opaque(value)
+    }
+
+    fn end(self) -> Result<(), EncodeError> {
+        Ok(())
+    }
+}
+
+struct Decoder<'de> {
+    input: &'de [u8],
+}
+
+impl<'de> Decoder<'de> {
+    fn take(&mut self, len: usize) -> Result<&'de [u8], ProofDecodeError> {
+        if len > self.input.len() {
+            return Err(ProofDecodeError::UnexpectedEnd);
+        }
+        let (taken, rest) = self.input.split_at(len);
+        self.input = rest;
+        Ok(taken)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], ProofDecodeError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn decode_tag(&mut self) -> Result<bool, ProofDecodeError> {
+        match self.take_array::<1>()? {
+            [0] => Ok(false),
+            [1] => Ok(true),
+            [tag] => Err(ProofDecodeError::InvalidTag(tag)),
+        }
+    }
+
+    fn decode_u32(&mut self) -> Result<u32, ProofDecodeError> {
+        self.take_array().map(u32::from_le_bytes)
+    }
+
+    /// Decode a length prefix. Every element takes at least a byte in the proofs this encodes, so
+    /// a length beyond the rest of the input is rejected before anything is allocated for it.
+    fn decode_len(&mut self) -> Result<usize, ProofDecodeError> {
+        let len = self.decode_u32()? as usize;
+        if len > self.input.len() {
+            return Err(ProofDecodeError::InvalidLength(len));
+        }
+        Ok(len)
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut Decoder<'de> {
+    type Error = ProofDecodeError;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, ProofDecodeError> {
+        Err(ProofDecodeError::Unsupported("self-describing types"))
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ProofDecodeError> {
+        visitor.visit_bool(self.decode_tag()?)
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ProofDecodeError> {
+        visitor.visit_i8(i8::from_le_bytes(self.take_array()?))
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ProofDecodeError> {
+        visitor.visit_i16(i16::from_le_bytes(self.take_array()?))
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ProofDecodeError> {
+        visitor.visit_i32(i32::from_le_bytes(self.take_array()?))
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ProofDecodeError> {
+        visitor.visit_i64(i64::from_le_bytes(self.take_array()?))
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ProofDecodeError> {
+        visitor.visit_u8(u8::from_le_bytes(self.take_array()?))
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ProofDecodeError> {
+        visitor.visit_u16(u16::from_le_bytes(self.take_array()?))
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ProofDecodeError> {
+        visitor.visit_u32(self.decode_u32()?)
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ProofDecodeError> {
+        visitor.visit_u64(u64::from_le_bytes(self.take_array()?))
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, ProofDecodeError> {
+        Err(ProofDecodeError::Unsupported("floats"))
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, ProofDecodeError> {
+        Err(ProofDecodeError::Unsupported("floats"))
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, ProofDecodeError> {
+        Err(ProofDecodeError::Unsupported("chars"))
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ProofDecodeError> {
+        let len = self.decode_len()?;
+        let bytes = self.take(len)?;
+        let s = core::str::from_utf8(bytes).map_err(|_| ProofDecodeError::InvalidUtf8)?;
+        visitor.visit_borrowed_str(s)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ProofDecodeError> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ProofDecodeError> {
+        let len = self.decode_len()?;
+        visitor.visit_borrowed_bytes(self.take(len)?)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, ProofDecodeError> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ProofDecodeError> {
+        if self.decode_tag()? {
+            visitor.visit_some(self)
+        } else {
+            visitor.visit_none()
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ProofDecodeError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, ProofDecodeError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, ProofDecodeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ProofDecodeError> {
+        let len = self.decode_len()?;
+        visitor.visit_seq(Elements::new(self, len))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, ProofDecodeError> {
+        visitor.visit_seq(Elements::new(self, len))
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, ProofDecodeError> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ProofDecodeError> {
+        let len = self.decode_len()?;
+        visitor.visit_map(Elements::new(self, len))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ProofDecodeError> {
+        self.deserialize_tuple(fields.len(), visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ProofDecodeError> {
+        visitor.visit_enum(self)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(
+        self,
+        _visitor: V,
+    ) -> Result<V::Value, ProofDecodeError> {
+        Err(ProofDecodeError::Unsupported("identifiers"))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(
+        self,
+        _visitor: V,
+    ) -> Result<V::Value, ProofDecodeError> {
+        Err(ProofDecodeError::Unsupported("ignored values"))
+    }
+}
+
+/// Decodes the `remaining` elements of a sequence, tuple or map.
+struct Elements<'a, 'de> {
+    decoder: &'a mut Decoder<'de>,
+    remaining: usize,
+}
+
+impl<'a, 'de> Elements<'a, 'de> {
+    fn new(decoder: &'a mut Decoder<'de>, remaining: usize) -> Self {
+        Self { decoder, remaining }
+    }
+}
+
+impl<'de> SeqAccess<'de> for Elements<'_, 'de> {
+    type Error = ProofDecodeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, ProofDecodeError> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.decoder).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+impl<'de> MapAccess<'de> for Elements<'_, 'de> {
+    type Error = ProofDecodeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, ProofDecodeError> {
+        self.next_element_seed(seed)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, ProofDecodeError> {
+        seed.deserialize(&mut *self.decoder)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+impl<'de> EnumAccess<'de> for &mut Decoder<'de> {
+    type Error = ProofDecodeError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self), ProofDecodeError> {
+        let index = self.decode_u32()?;
+        let variant = seed.deserialize(IntoDeserializer::<ProofDecodeError>::into_deserializer(
+            index,
+        ))?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for &mut Decoder<'de> {
+    type Error = ProofDecodeError;
+
+    fn unit_variant(self) -> Result<(), ProofDecodeError> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, ProofDecodeError> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, ProofDecodeError> {
+        de::Deserializer::deserialize_tuple(self, len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ProofDecodeError> {
+        de::Deserializer::deserialize_tuple(self, fields.len(), visitor)
+    }
+}
@@ -6,7 +6,9 @@ extern crate alloc;
 
 mod adapters;
 mod domain;
+mod encoding;
 mod mmcs;
+mod observer;
 mod pcs;
 
 #[cfg(any(test, feature = "test-utils"))]
@@ -14,5 +16,7 @@ pub mod testing;
 
 pub use adapters::*;
 pub use domain::*;
+pub use encoding::*;
 pub use mmcs::*;
+pub use observer::*;
 pub use pcs::*;
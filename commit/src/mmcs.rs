@@ -77,3 +77,36 @@ pub trait Mmcs<T: Send + Sync>: Clone {
         proof: &Self::Proof,
     ) -> Result<(), Self::Error>;
 }
+
+/// An MMCS which can open rows at several indices with one proof, in which data shared between
+/// the openings, such as the sibling digests near the root of a Merkle tree, appears only once.
+///
+/// This is separate from `Mmcs` so that an MMCS which is never opened at several indices at once
+/// need not implement it. One without a more compact form can take `Vec<Self::Proof>` as its
+/// `MultiProof`, opening and verifying each index with `open_batch` and `verify_batch`.
+pub trait MultiMmcs<T: Send + Sync>: Mmcs<T> {
+    /// A proof of openings at several indices at once; see `open_multi_batch`.
+    type MultiProof: Clone + Serialize + DeserializeOwned;
+
+    /// Opens a batch of rows at each of `indices`, as `open_batch` does for a single index, but
+    /// with one proof for all of them.
+    /// Returns `(openings, proof)`, where `openings[q]` holds the rows opened at `indices[q]`.
+    /// The indices may be in any order, and may repeat.
+    fn open_multi_batch<M: Matrix<T>>(
+        &self,
+        indices: &[usize],
+        prover_data: &Self::ProverData<M>,
+    ) -> (Vec<Vec<Vec<T>>>, Self::MultiProof);
+
+    /// Verify the openings at several indices made by `open_multi_batch`.
+    /// `opened_values[q]` are the rows opened at `indices[q]`, and `dimensions` are as in
+    /// `verify_batch`. Openings at indices which share a row of some matrix must agree on it.
+    fn verify_multi_batch(
+        &self,
+        commit: &Self::Commitment,
+        dimensions: &[Dimensions],
+        indices: &[usize],
+        opened_values: &[Vec<Vec<T>>],
+        proof: &Self::MultiProof,
+    ) -> Result<(), Self::Error>;
+}
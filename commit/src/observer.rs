@@ -0,0 +1,76 @@
+//! Hooks for following the progress of a prover, and for cancelling it.
+
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// A phase of proving, along with the sizes of the data it works on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProverPhase {
+    /// Committing to main traces of at most `height` rows and `width` base field columns in all.
+    TraceCommit { height: usize, width: usize },
+    /// Committing to LogUp traces of at most `height` rows and `width` base field columns in all.
+    PermutationCommit { height: usize, width: usize },
+    /// Evaluating the constraints over quotient domains of `size` points in all.
+    Quotient { size: usize },
+    /// Committing to `num_chunks` quotient chunks in all, each of at most `height` rows.
+    QuotientCommit { num_chunks: usize, height: usize },
+    /// Opening the commitments of `num_rounds` rounds, which contains the FRI phases below.
+    Open { num_rounds: usize },
+    /// The FRI commit phase, folding a codeword of `2^log_max_height` points.
+    FriCommit { log_max_height: usize },
+    /// Grinding a proof of work of `bits` bits.
+    ProofOfWork { bits: usize },
+    /// Answering `num_queries` FRI queries.
+    FriQueries { num_queries: usize },
+}
+
+/// Receives the phases of a proof as the prover works through them, and may ask it to stop.
+///
+/// Every method defaults to doing nothing, so an observer implements only those it needs. Since
+/// provers run without `std`, events carry no timestamps: an observer which wants timings reads a
+/// clock as it receives them.
+pub trait ProverObserver {
+    /// Called when the prover starts `phase`.
+    fn phase_started(&mut self, _phase: ProverPhase) {}
+
+    /// Called when the prover finishes `phase`, with the value passed to `phase_started`.
+    fn phase_finished(&mut self, _phase: ProverPhase) {}
+
+    /// Whether the prover should stop. Provers check this before each of their top-level phases,
+    /// but not within them, so a PCS does not check it while opening.
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+}
+
+/// Ignores every event, and never cancels.
+impl ProverObserver for () {}
+
+/// A flag through which another thread can cancel a proof. Clones share the flag.
+///
+/// As a `ProverObserver`, it ignores every event, and cancels once `cancel` has been called.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask every prover observing this token, or a clone of it, to stop.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+impl ProverObserver for CancellationToken {
+    fn is_cancelled(&self) -> bool {
+        Self::is_cancelled(self)
+    }
+}
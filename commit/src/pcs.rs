@@ -1,7 +1,9 @@
 //! Traits for polynomial commitment schemes.
 
+use alloc::vec;
 use alloc::vec::Vec;
 use core::fmt::Debug;
+use core::ops::Range;
 
 use p3_field::ExtensionField;
 use p3_matrix::dense::RowMajorMatrix;
@@ -9,7 +11,7 @@ use p3_matrix::Matrix;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
-use crate::PolynomialSpace;
+use crate::{PolynomialSpace, ProverObserver};
 
 pub type Val<D> = <D as PolynomialSpace>::Val;
 
@@ -32,15 +34,61 @@ where
 
     type Error: Debug;
 
+    /// Whether this PCS hides the polynomials committed to. If so, a STARK using it randomizes its
+    /// polynomials before committing to them, using `random_matrix`, to be zero knowledge. It must
+    /// agree with whether `random_matrix` samples anything.
+    const ZK: bool = false;
+
     /// This should return a coset domain (s.t. Domain::next_point returns Some)
     fn natural_domain_for_degree(&self, degree: usize) -> Self::Domain;
 
+    /// The `log2` of the size of the largest domain this PCS supports. A verifier checks the sizes
+    /// claimed by a proof against it before building any domain from them.
+    ///
+    /// The default places no limit, leaving a PCS which does not override it to reject domains it
+    /// cannot build itself.
+    fn log_max_domain_size(&self) -> usize {
+        usize::MAX
+    }
+
     #[allow(clippy::type_complexity)]
     fn commit(
         &self,
         evaluations: Vec<(Self::Domain, RowMajorMatrix<Val<Self::Domain>>)>,
     ) -> (Self::Commitment, Self::ProverData);
 
+    /// Commit to the evaluations over `domain` of a matrix with `width` columns, together with
+    /// `others` as in `commit`. The matrix is not given whole: `columns` returns the evaluations of
+    /// the columns in a range, and is called on consecutive ranges covering `0..width`. The matrix
+    /// has index 0 in the prover data, and `others` follow it.
+    ///
+    /// This lets the caller generate the columns on demand, without holding the whole matrix, and
+    /// lets a PCS encode a few columns at a time. The default implementation requests every column
+    /// at once.
+    #[allow(clippy::type_complexity)]
+    fn commit_columns(
+        &self,
+        domain: Self::Domain,
+        width: usize,
+        mut columns: impl FnMut(Range<usize>) -> RowMajorMatrix<Val<Self::Domain>>,
+        others: Vec<(Self::Domain, RowMajorMatrix<Val<Self::Domain>>)>,
+    ) -> (Self::Commitment, Self::ProverData) {
+        let mut evaluations = vec![(domain, columns(0..width))];
+        evaluations.extend(others);
+        self.commit(evaluations)
+    }
+
+    /// Sample a `height x width` matrix of uniformly random values, for randomizing polynomials
+    /// before committing to them. Only hiding PCSs carry their own randomness; the default returns
+    /// `None`.
+    fn random_matrix(
+        &self,
+        _height: usize,
+        _width: usize,
+    ) -> Option<RowMajorMatrix<Val<Self::Domain>>> {
+        None
+    }
+
     fn get_evaluations_on_domain<'a>(
         &self,
         prover_data: &'a Self::ProverData,
@@ -62,6 +110,17 @@ where
         challenger: &mut Challenger,
     ) -> (OpenedValues<Challenge>, Self::Proof);
 
+    /// As `open`, reporting the phases of the opening argument to `observer`. The default
+    /// implementation reports nothing.
+    fn open_observed(
+        &self,
+        rounds: Vec<(&Self::ProverData, Vec<Vec<Challenge>>)>,
+        challenger: &mut Challenger,
+        _observer: &mut impl ProverObserver,
+    ) -> (OpenedValues<Challenge>, Self::Proof) {
+        self.open(rounds, challenger)
+    }
+
     #[allow(clippy::type_complexity)]
     fn verify(
         &self,
@@ -59,6 +59,10 @@ where
         }
     }
 
+    fn log_max_domain_size(&self) -> usize {
+        Val::TWO_ADICITY
+    }
+
     fn commit(
         &self,
         evaluations: Vec<(Self::Domain, RowMajorMatrix<Val>)>,
@@ -1,30 +1,121 @@
+use alloc::vec;
 use alloc::vec::Vec;
 use core::fmt::Debug;
 
+use itertools::Itertools;
 use p3_field::Field;
+use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
+use p3_util::log2_strict_usize;
 
 #[derive(Debug)]
 pub struct FriConfig<M> {
     pub log_blowup: usize,
+    /// The log2 of the largest arity a commit phase round folds by. See `log_arities`.
+    pub max_log_arity: usize,
+    /// The log2 of the number of coefficients of the final polynomial, which the prover sends
+    /// rather than folding it down to a constant. See `log_final_height`.
+    pub log_final_poly_len: usize,
     pub num_queries: usize,
     pub proof_of_work_bits: usize,
+    /// The proof of work bits the prover grinds before each folding challenge is sampled, on top
+    /// of the `proof_of_work_bits` ground before the queries. Zero for none.
+    pub commit_proof_of_work_bits: usize,
     pub mmcs: M,
 }
 
 impl<M> FriConfig<M> {
+    /// A FRI instance of arity 2 which folds down to a constant, without proof of work in the
+    /// commit phase. The other options are set with the `with_` methods.
+    pub const fn new(
+        log_blowup: usize,
+        num_queries: usize,
+        proof_of_work_bits: usize,
+        mmcs: M,
+    ) -> Self {
+        Self {
+            log_blowup,
+            max_log_arity: 1,
+            log_final_poly_len: 0,
+            num_queries,
+            proof_of_work_bits,
+            commit_proof_of_work_bits: 0,
+            mmcs,
+        }
+    }
+
+    /// Fold by up to `2^max_log_arity` in each commit phase round.
+    pub const fn with_max_log_arity(mut self, max_log_arity: usize) -> Self {
+        self.max_log_arity = max_log_arity;
+        self
+    }
+
+    /// Stop folding at a final polynomial of `2^log_final_poly_len` coefficients.
+    pub const fn with_log_final_poly_len(mut self, log_final_poly_len: usize) -> Self {
+        self.log_final_poly_len = log_final_poly_len;
+        self
+    }
+
+    /// Grind for `bits` of proof of work before each folding challenge is sampled.
+    pub const fn with_commit_proof_of_work_bits(mut self, bits: usize) -> Self {
+        self.commit_proof_of_work_bits = bits;
+        self
+    }
+
     pub const fn blowup(&self) -> usize {
         1 << self.log_blowup
     }
 
+    pub const fn final_poly_len(&self) -> usize {
+        1 << self.log_final_poly_len
+    }
+
     /// Returns the soundness bits of this FRI instance based on the
     /// [ethSTARK](https://eprint.iacr.org/2021/582) conjecture.
     ///
-    /// Certain users may instead want to look at proven soundness, a more complex calculation which
-    /// isn't currently supported by this crate.
+    /// Certain users may instead want to look at proven soundness, a more complex calculation; see
+    /// `proven_soundness_bits`.
     pub fn conjectured_soundness_bits(&self) -> usize {
         self.log_blowup * self.num_queries + self.proof_of_work_bits
     }
+
+    /// The log2 of the height of the codeword folding ends at, given the log2 heights of the
+    /// inputs, tallest first.
+    ///
+    /// This is the height `blowup * final_poly_len`, unless an input is shorter, in which case
+    /// folding goes on down to it so that it is rolled in. The final polynomial has the
+    /// codeword's height over `blowup` coefficients.
+    pub fn log_final_height(&self, log_input_heights: &[usize]) -> usize {
+        let log_final_height = self.log_blowup + self.log_final_poly_len;
+        log_input_heights.last().map_or(log_final_height, |&lh| {
+            lh.clamp(self.log_blowup, log_final_height)
+        })
+    }
+
+    /// The log2 of the arity each commit phase round folds by, given the log2 heights of the
+    /// inputs, tallest first.
+    ///
+    /// Folding starts at the height of the tallest input, and ends at `log_final_height`. Each
+    /// round folds by `2^max_log_arity`, or at least 2, unless that would fold past the height of
+    /// the next input, which must be rolled in, or past the final height.
+    pub fn log_arities(&self, log_input_heights: &[usize]) -> Vec<usize> {
+        let mut log_arities = vec![];
+        let Some(&(mut log_height)) = log_input_heights.first() else {
+            return log_arities;
+        };
+        let log_final_height = self.log_final_height(log_input_heights);
+        while log_height > log_final_height {
+            let log_next_height = log_input_heights
+                .iter()
+                .copied()
+                .find(|&lh| lh < log_height)
+                .map_or(log_final_height, |lh| lh.max(log_final_height));
+            let log_arity = self.max_log_arity.clamp(1, log_height - log_next_height);
+            log_arities.push(log_arity);
+            log_height -= log_arity;
+        }
+        log_arities
+    }
 }
 
 /// Whereas `FriConfig` encompasses parameters the end user can set, `FriGenericConfig` is
@@ -38,8 +129,10 @@ pub trait FriGenericConfig<F: Field> {
     fn extra_query_index_bits(&self) -> usize;
 
     /// Fold a row, returning a single column.
-    /// Right now the input row will always be 2 columns wide,
-    /// but we may support higher folding arity in the future.
+    /// The row holds `2^k` evaluations over a coset, in bit-reversed order, and is the `index`th
+    /// row of a codeword whose folded height is `2^log_height`. Folding by `2^k` should be the same
+    /// as folding adjacent pairs `k` times, with `beta`, `beta^2`, `beta^4` and so on; see
+    /// `fold_row_in_pairs`.
     fn fold_row(
         &self,
         index: usize,
@@ -51,3 +144,54 @@ pub trait FriGenericConfig<F: Field> {
     /// Same as applying fold_row to every row, possibly faster.
     fn fold_matrix<M: Matrix<F>>(&self, beta: F, m: M) -> Vec<F>;
 }
+
+/// Fold `evals`, the `index`th row of a codeword whose folded height is `2^log_height`, by
+/// folding adjacent pairs with `fold_pair` until one value is left, with `beta`, then `beta^2`,
+/// and so on.
+///
+/// `fold_pair(index, log_height, beta, lo, hi)` folds the `index`th pair of a codeword whose
+/// folded height is `2^log_height`. Since the row's evaluations are over a coset in bit-reversed
+/// order, adjacent pairs of them, and of each fold of them, are pairs `x`, `-x`.
+pub fn fold_row_in_pairs<F: Field>(
+    index: usize,
+    log_height: usize,
+    mut beta: F,
+    evals: impl Iterator<Item = F>,
+    fold_pair: impl Fn(usize, usize, F, F, F) -> F,
+) -> F {
+    let mut evals = evals.collect_vec();
+    let log_arity = log2_strict_usize(evals.len());
+    for log_folded_arity in (0..log_arity).rev() {
+        evals = evals
+            .chunks_exact(2)
+            .enumerate()
+            .map(|(i, pair)| {
+                fold_pair(
+                    (index << log_folded_arity) + i,
+                    log_height + log_folded_arity,
+                    beta,
+                    pair[0],
+                    pair[1],
+                )
+            })
+            .collect();
+        beta = beta.square();
+    }
+    evals[0]
+}
+
+/// Fold each row of `m` as `fold_row_in_pairs` does, where `fold_pairs` folds every row of a
+/// matrix of width 2.
+pub fn fold_matrix_in_pairs<F: Field, M: Matrix<F>>(
+    mut beta: F,
+    m: M,
+    fold_pairs: impl Fn(F, RowMajorMatrix<F>) -> Vec<F>,
+) -> Vec<F> {
+    let log_arity = log2_strict_usize(m.width());
+    let mut values = m.to_row_major_matrix().values;
+    for _ in 0..log_arity {
+        values = fold_pairs(beta, RowMajorMatrix::new(values, 2));
+        beta = beta.square();
+    }
+    values
+}
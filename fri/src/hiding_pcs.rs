@@ -1,9 +1,13 @@
 use alloc::vec::Vec;
 use core::cell::RefCell;
 use core::fmt::Debug;
+use core::ops::Range;
 
+use itertools::izip;
 use p3_challenger::{CanObserve, FieldChallenger, GrindingChallenger};
-use p3_commit::{Mmcs, OpenedValues, Pcs, TwoAdicMultiplicativeCoset};
+use p3_commit::{
+    Mmcs, MultiMmcs, OpenedValues, Pcs, PolynomialSpace, ProverObserver, TwoAdicMultiplicativeCoset,
+};
 use p3_dft::TwoAdicSubgroupDft;
 use p3_field::{ExtensionField, Field, TwoAdicField};
 use p3_matrix::dense::RowMajorMatrix;
@@ -14,7 +18,7 @@ use rand::Rng;
 use tracing::instrument;
 
 use crate::verifier::FriError;
-use crate::{BatchOpening, FriConfig, FriProof, TwoAdicFriPcs};
+use crate::{BatchOpening, FriConfig, FriProof, LdeMatrix, TwoAdicFriPcs};
 
 /// A hiding FRI PCS. Both MMCSs must also be hiding; this is not enforced at compile time so it's
 /// the user's responsibility to configure.
@@ -40,6 +44,23 @@ impl<Val, Dft, InputMmcs, FriMmcs, R> HidingFriPcs<Val, Dft, InputMmcs, FriMmcs,
             rng: rng.into(),
         }
     }
+
+    /// Have `commit_columns` encode chunks of columns whose DFT working memory is about `bytes`, as
+    /// in `TwoAdicFriPcs::with_chunk_budget`.
+    #[must_use]
+    pub fn with_chunk_budget(mut self, bytes: usize) -> Self {
+        self.inner = self.inner.with_chunk_budget(bytes);
+        self
+    }
+
+    /// Have `commit_columns` keep the LDE it commits to in a memory-mapped temporary file in
+    /// `dir`, as in `TwoAdicFriPcs::with_lde_dir`.
+    #[cfg(feature = "mmap")]
+    #[must_use]
+    pub fn with_lde_dir(mut self, dir: impl Into<std::path::PathBuf>) -> Self {
+        self.inner = self.inner.with_lde_dir(dir);
+        self
+    }
 }
 
 impl<Val, Dft, InputMmcs, FriMmcs, Challenge, Challenger, R> Pcs<Challenge, Challenger>
@@ -49,16 +70,16 @@ where
     Standard: Distribution<Val>,
     Dft: TwoAdicSubgroupDft<Val>,
     InputMmcs: Mmcs<Val>,
-    FriMmcs: Mmcs<Challenge>,
+    FriMmcs: MultiMmcs<Challenge>,
     Challenge: TwoAdicField + ExtensionField<Val>,
     Challenger:
         FieldChallenger<Val> + CanObserve<FriMmcs::Commitment> + GrindingChallenger<Witness = Val>,
     R: Rng + Send + Sync,
-    <InputMmcs as Mmcs<Val>>::ProverData<RowMajorMatrix<Val>>: Clone,
+    <InputMmcs as Mmcs<Val>>::ProverData<LdeMatrix<Val>>: Clone,
 {
     type Domain = TwoAdicMultiplicativeCoset<Val>;
     type Commitment = InputMmcs::Commitment;
-    type ProverData = InputMmcs::ProverData<RowMajorMatrix<Val>>;
+    type ProverData = InputMmcs::ProverData<LdeMatrix<Val>>;
     /// The first item contains the openings of the random polynomials added by this wrapper.
     /// The second item is the usual FRI proof.
     type Proof = (
@@ -67,11 +88,18 @@ where
     );
     type Error = FriError<FriMmcs::Error, InputMmcs::Error>;
 
+    const ZK: bool = true;
+
     fn natural_domain_for_degree(&self, degree: usize) -> Self::Domain {
         <TwoAdicFriPcs<Val, Dft, InputMmcs, FriMmcs> as Pcs<Challenge, Challenger>>::natural_domain_for_degree(
             &self.inner, degree)
     }
 
+    fn log_max_domain_size(&self) -> usize {
+        <TwoAdicFriPcs<Val, Dft, InputMmcs, FriMmcs> as Pcs<Challenge, Challenger>>::log_max_domain_size(
+            &self.inner)
+    }
+
     fn commit(
         &self,
         evaluations: Vec<(Self::Domain, RowMajorMatrix<Val>)>,
@@ -91,6 +119,52 @@ where
         )
     }
 
+    fn commit_columns(
+        &self,
+        domain: Self::Domain,
+        width: usize,
+        mut columns: impl FnMut(Range<usize>) -> RowMajorMatrix<Val>,
+        others: Vec<(Self::Domain, RowMajorMatrix<Val>)>,
+    ) -> (Self::Commitment, Self::ProverData) {
+        // The random codewords are the last columns, so they are generated with the final chunks.
+        let randomized_columns = |range: Range<usize>| {
+            if range.end <= width {
+                return columns(range);
+            }
+            let num_random = range.end - range.start.max(width);
+            let rng = &mut *self.rng.borrow_mut();
+            if range.start < width {
+                add_random_cols(columns(range.start..width), num_random, rng)
+            } else {
+                RowMajorMatrix::rand(rng, domain.size(), num_random)
+            }
+        };
+        let randomized_others = others
+            .into_iter()
+            .map(|(domain, mat)| {
+                (
+                    domain,
+                    add_random_cols(mat, self.num_random_codewords, &mut *self.rng.borrow_mut()),
+                )
+            })
+            .collect();
+        <TwoAdicFriPcs<Val, Dft, InputMmcs, FriMmcs> as Pcs<Challenge, Challenger>>::commit_columns(
+            &self.inner,
+            domain,
+            width + self.num_random_codewords,
+            randomized_columns,
+            randomized_others,
+        )
+    }
+
+    fn random_matrix(&self, height: usize, width: usize) -> Option<RowMajorMatrix<Val>> {
+        Some(RowMajorMatrix::rand(
+            &mut *self.rng.borrow_mut(),
+            height,
+            width,
+        ))
+    }
+
     fn get_evaluations_on_domain<'a>(
         &self,
         prover_data: &'a Self::ProverData,
@@ -121,7 +195,17 @@ where
         )>,
         challenger: &mut Challenger,
     ) -> (OpenedValues<Challenge>, Self::Proof) {
-        let (mut inner_opened_values, inner_proof) = self.inner.open(rounds, challenger);
+        self.open_observed(rounds, challenger, &mut ())
+    }
+
+    fn open_observed(
+        &self,
+        rounds: Vec<(&Self::ProverData, Vec<Vec<Challenge>>)>,
+        challenger: &mut Challenger,
+        observer: &mut impl ProverObserver,
+    ) -> (OpenedValues<Challenge>, Self::Proof) {
+        let (mut inner_opened_values, inner_proof) =
+            self.inner.open_observed(rounds, challenger, observer);
 
         // inner_opened_values includes opened values for the random codewords. Those should be
         // hidden from our caller, so we split them off and store them in the proof.
@@ -169,6 +253,20 @@ where
         challenger: &mut Challenger,
     ) -> Result<(), Self::Error> {
         let (opened_values_for_rand_cws, inner_proof) = proof;
+        // Every opened point of every matrix carries the values of its random codewords.
+        let valid_shape = opened_values_for_rand_cws.len() == rounds.len()
+            && izip!(opened_values_for_rand_cws, &rounds).all(|(rand_round, (_, mats))| {
+                rand_round.len() == mats.len()
+                    && izip!(rand_round, mats).all(|(rand_mat, (_, points))| {
+                        rand_mat.len() == points.len()
+                            && rand_mat
+                                .iter()
+                                .all(|rand_point| rand_point.len() == self.num_random_codewords)
+                    })
+            });
+        if !valid_shape {
+            return Err(FriError::InvalidProofShape);
+        }
         // Now we merge `opened_values_for_rand_cws` into the opened values in `rounds`, undoing
         // the split that we did in `open`, to get a complete set of opened values for the inner PCS
         // to check.
@@ -0,0 +1,108 @@
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+
+use p3_field::Field;
+use p3_matrix::dense::{DenseMatrix, DenseStorage};
+
+/// A committed LDE, whose values are in memory or, with the `mmap` feature, in a memory-mapped
+/// file.
+pub type LdeMatrix<T> = DenseMatrix<T, LdeStorage<T>>;
+
+/// The storage of a committed LDE.
+#[derive(Clone, Debug)]
+pub enum LdeStorage<T> {
+    Vec(Vec<T>),
+    /// Values in a memory-mapped temporary file, which the operating system pages in as rows are
+    /// hashed or opened, and writes back when memory is short.
+    #[cfg(feature = "mmap")]
+    Mapped(mapped::MappedValues<T>),
+}
+
+impl<T> Borrow<[T]> for LdeStorage<T> {
+    fn borrow(&self) -> &[T] {
+        match self {
+            Self::Vec(values) => values,
+            #[cfg(feature = "mmap")]
+            Self::Mapped(values) => values.as_slice(),
+        }
+    }
+}
+
+impl<T: Clone + Send + Sync> DenseStorage<T> for LdeStorage<T> {
+    fn to_vec(self) -> Vec<T> {
+        match self {
+            Self::Vec(values) => values,
+            #[cfg(feature = "mmap")]
+            Self::Mapped(values) => values.as_slice().to_vec(),
+        }
+    }
+}
+
+impl<T: Field> LdeStorage<T> {
+    /// `len` values in memory, initialized by `fill` from zeros.
+    pub(crate) fn in_memory(len: usize, fill: impl FnOnce(&mut [T])) -> Self {
+        let mut values = T::zero_vec(len);
+        fill(&mut values);
+        Self::Vec(values)
+    }
+}
+
+#[cfg(feature = "mmap")]
+pub use mapped::MappedValues;
+
+#[cfg(feature = "mmap")]
+mod mapped {
+    use alloc::sync::Arc;
+    use core::marker::PhantomData;
+    use core::{mem, slice};
+    use std::path::Path;
+
+    use memmap2::{Mmap, MmapMut};
+    use p3_field::Field;
+
+    use super::LdeStorage;
+
+    /// Values of type `T` in a read-only memory map of an unnamed temporary file, which is deleted
+    /// when the last clone is dropped.
+    #[derive(Clone, Debug)]
+    pub struct MappedValues<T> {
+        mmap: Arc<Mmap>,
+        len: usize,
+        _phantom: PhantomData<T>,
+    }
+
+    impl<T> MappedValues<T> {
+        pub(super) fn as_slice(&self) -> &[T] {
+            // Safety: the map is page aligned, holds `len` values of type `T` written by
+            // `LdeStorage::mapped`, and is never written to again.
+            unsafe { slice::from_raw_parts(self.mmap.as_ptr().cast(), self.len) }
+        }
+    }
+
+    impl<T: Field> LdeStorage<T> {
+        /// `len` values in a memory-mapped temporary file in `dir`, initialized by `fill` from
+        /// zeros. Empty storage is kept in memory, as a file cannot be mapped with no length.
+        ///
+        /// Panics if the file cannot be created or mapped.
+        pub(crate) fn mapped(dir: &Path, len: usize, fill: impl FnOnce(&mut [T])) -> Self {
+            if len == 0 {
+                return Self::in_memory(len, fill);
+            }
+            let file = tempfile::tempfile_in(dir).expect("failed to create a file for the LDE");
+            file.set_len((len * mem::size_of::<T>()) as u64)
+                .expect("failed to size the file for the LDE");
+            // Safety: the file is unnamed and only reachable through this map.
+            let mut mmap = unsafe { MmapMut::map_mut(&file) }.expect("failed to map the LDE");
+            // Safety: the map is page aligned and `len` values long, and field elements are plain
+            // data of which the zero bytes the file is extended with are a valid value.
+            let values = unsafe { slice::from_raw_parts_mut(mmap.as_mut_ptr().cast(), len) };
+            fill(values);
+            let mmap = mmap.make_read_only().expect("failed to map the LDE");
+            Self::Mapped(MappedValues {
+                mmap: Arc::new(mmap),
+                len,
+                _phantom: PhantomData,
+            })
+        }
+    }
+}
@@ -3,17 +3,25 @@
 #![no_std]
 
 extern crate alloc;
+#[cfg(feature = "mmap")]
+extern crate std;
 
 mod config;
 mod fold_even_odd;
 mod hiding_pcs;
+mod lde_storage;
+mod low_degree_test;
 mod proof;
 pub mod prover;
+mod soundness;
 mod two_adic_pcs;
 pub mod verifier;
 
 pub use config::*;
 pub use fold_even_odd::*;
 pub use hiding_pcs::*;
+pub use lde_storage::*;
+pub use low_degree_test::*;
 pub use proof::*;
+pub use soundness::*;
 pub use two_adic_pcs::*;
@@ -0,0 +1,196 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use p3_challenger::{CanObserve, FieldChallenger, GrindingChallenger};
+use p3_commit::MultiMmcs;
+use p3_field::{ExtensionField, Field, TwoAdicField};
+use p3_matrix::Dimensions;
+use p3_util::{log2_ceil_usize, log2_strict_usize, reverse_bits_len, reverse_slice_index_bits};
+use serde::{Deserialize, Serialize};
+
+use crate::verifier::{self, FriError};
+use crate::{
+    prover, BatchOpening, FriConfig, FriProof, TwoAdicFriGenericConfig,
+    TwoAdicFriGenericConfigForMmcs,
+};
+
+/// A proof that a vector of evaluations over a coset of a two-adic subgroup is close to the
+/// evaluations of a polynomial of low degree.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(bound(
+    serialize = "Witness: Serialize",
+    deserialize = "Witness: Deserialize<'de>"
+))]
+pub struct LowDegreeProof<F: Field, M: MultiMmcs<F>, Witness> {
+    /// The commitment to the evaluations, in bit-reversed order.
+    pub commitment: M::Commitment,
+    /// The FRI proof, whose queries open the evaluations against `commitment`.
+    pub fri_proof: FriProof<F, M, Witness, Vec<BatchOpening<F, M>>>,
+}
+
+/// Prove that `evals` are the evaluations of a polynomial of degree less than `degree_bound`,
+/// where `evals[i]` is its evaluation at `shift * g^i` for a generator `g` of the subgroup of order
+/// `evals.len()`.
+///
+/// FRI tests proximity to the code of rate `1 / config.blowup()`, so it only tests degree bounds
+/// which are powers of two, and there must be exactly `D * config.blowup()` evaluations, where `D`
+/// is `degree_bound` rounded up to a power of two. A smaller bound is enforced by degree
+/// correction: after committing to the evaluations of `p`, the prover samples a challenge `r` and
+/// runs FRI on those of `p(x) (1 + r x^(D - degree_bound))`. This has degree less than `D` if `p`
+/// has degree less than `degree_bound`, and otherwise does not, except with small probability over
+/// `r`. The verifier recomputes the corrected evaluations from the opened ones.
+///
+/// The evaluations are committed to with `config.mmcs`, and the shift, the degree bound and the
+/// commitment are observed before the FRI rounds so that the proof is bound to them.
+///
+/// Panics if `degree_bound` is zero, if `evals.len()` is not `D * config.blowup()`, or if the
+/// evaluations are not of a polynomial of low enough degree.
+pub fn prove_low_degree<Val, F, M, Challenger>(
+    config: &FriConfig<M>,
+    shift: F,
+    degree_bound: usize,
+    mut evals: Vec<F>,
+    challenger: &mut Challenger,
+) -> LowDegreeProof<F, M, Challenger::Witness>
+where
+    Val: Field,
+    F: ExtensionField<Val> + TwoAdicField,
+    M: MultiMmcs<F>,
+    Challenger: FieldChallenger<Val> + GrindingChallenger + CanObserve<M::Commitment>,
+{
+    assert!(degree_bound > 0, "the degree bound must be positive");
+    let log_height = log2_ceil_usize(degree_bound) + config.log_blowup;
+    assert_eq!(
+        log2_strict_usize(evals.len()),
+        log_height,
+        "the degree bound must match the number of evaluations and the rate"
+    );
+
+    // FRI folds codewords in bit-reversed order, in which the evaluations at `x` and `-x` are
+    // adjacent.
+    reverse_slice_index_bits(&mut evals);
+    let (commitment, data) = config.mmcs.commit_vec(evals.clone());
+    observe_statement(challenger, shift, degree_bound, &commitment);
+
+    if let Some((excess, r)) = sample_correction::<Val, F, _>(challenger, degree_bound) {
+        // The correction factors `1 + r x^excess`, in bit-reversed order like the evaluations.
+        let mut factors: Vec<F> = F::two_adic_generator(log_height)
+            .exp_u64(excess as u64)
+            .shifted_powers(shift.exp_u64(excess as u64))
+            .take(evals.len())
+            .collect();
+        reverse_slice_index_bits(&mut factors);
+        for (eval, factor) in evals.iter_mut().zip(factors) {
+            *eval *= F::ONE + r * factor;
+        }
+    }
+
+    let g: TwoAdicFriGenericConfigForMmcs<F, M> = TwoAdicFriGenericConfig(PhantomData);
+    let fri_proof = prover::prove(&g, config, vec![evals], challenger, |index| {
+        let (opened_values, opening_proof) = config.mmcs.open_batch(index, &data);
+        vec![BatchOpening {
+            opened_values,
+            opening_proof,
+        }]
+    });
+
+    LowDegreeProof {
+        commitment,
+        fri_proof,
+    }
+}
+
+/// Verify a proof generated by `prove_low_degree` that the committed evaluations over the coset
+/// `shift H` are close to those of a polynomial of degree less than `degree_bound`, where `H` is
+/// the subgroup of order `D * config.blowup()` and `D` is `degree_bound` rounded up to a power of
+/// two. The degree bound must be the one the proof was generated for.
+pub fn verify_low_degree<Val, F, M, Challenger>(
+    config: &FriConfig<M>,
+    shift: F,
+    degree_bound: usize,
+    proof: &LowDegreeProof<F, M, Challenger::Witness>,
+    challenger: &mut Challenger,
+) -> Result<(), FriError<M::Error, M::Error>>
+where
+    Val: Field,
+    F: ExtensionField<Val> + TwoAdicField,
+    M: MultiMmcs<F>,
+    Challenger: FieldChallenger<Val> + GrindingChallenger + CanObserve<M::Commitment>,
+{
+    if degree_bound == 0 {
+        return Err(FriError::InvalidProofShape);
+    }
+    let log_height = log2_ceil_usize(degree_bound) + config.log_blowup;
+    observe_statement(challenger, shift, degree_bound, &proof.commitment);
+    let correction = sample_correction::<Val, F, _>(challenger, degree_bound);
+
+    let g: TwoAdicFriGenericConfigForMmcs<F, M> = TwoAdicFriGenericConfig(PhantomData);
+    verifier::verify(
+        &g,
+        config,
+        &[log_height],
+        &proof.fri_proof,
+        challenger,
+        |index, input_proof| {
+            // Each query opens a single evaluation.
+            if input_proof.len() != 1
+                || input_proof[0].opened_values.len() != 1
+                || input_proof[0].opened_values[0].len() != 1
+            {
+                return Err(FriError::InvalidProofShape);
+            }
+            let BatchOpening {
+                opened_values,
+                opening_proof,
+            } = &input_proof[0];
+            let dims = &[Dimensions {
+                width: 1,
+                height: 1 << log_height,
+            }];
+            config
+                .mmcs
+                .verify_batch(&proof.commitment, dims, index, opened_values, opening_proof)
+                .map_err(FriError::InputError)?;
+            let mut eval = opened_values[0][0];
+            if let Some((excess, r)) = correction {
+                let x = shift
+                    * F::two_adic_generator(log_height)
+                        .exp_u64(reverse_bits_len(index, log_height) as u64);
+                eval *= F::ONE + r * x.exp_u64(excess as u64);
+            }
+            Ok(vec![(log_height, eval)])
+        },
+    )
+}
+
+fn observe_statement<Val, F, Challenger, Commitment>(
+    challenger: &mut Challenger,
+    shift: F,
+    degree_bound: usize,
+    commitment: &Commitment,
+) where
+    Val: Field,
+    F: ExtensionField<Val>,
+    Challenger: FieldChallenger<Val> + CanObserve<Commitment>,
+    Commitment: Clone,
+{
+    challenger.observe_ext_element(shift);
+    challenger.observe(Val::from_canonical_usize(degree_bound));
+    challenger.observe(commitment.clone());
+}
+
+/// If `degree_bound` is not a power of two, sample the challenge of the degree correction, and
+/// return it alongside the power of `x` which raises the degree bound to the next power of two.
+fn sample_correction<Val, F, Challenger>(
+    challenger: &mut Challenger,
+    degree_bound: usize,
+) -> Option<(usize, F)>
+where
+    Val: Field,
+    F: ExtensionField<Val>,
+    Challenger: FieldChallenger<Val>,
+{
+    let excess = degree_bound.next_power_of_two() - degree_bound;
+    (excess > 0).then(|| (excess, challenger.sample_ext_element()))
+}
@@ -1,6 +1,6 @@
 use alloc::vec::Vec;
 
-use p3_commit::Mmcs;
+use p3_commit::MultiMmcs;
 use p3_field::Field;
 use serde::{Deserialize, Serialize};
 
@@ -9,12 +9,17 @@ use serde::{Deserialize, Serialize};
     serialize = "Witness: Serialize, InputProof: Serialize",
     deserialize = "Witness: Deserialize<'de>, InputProof: Deserialize<'de>"
 ))]
-pub struct FriProof<F: Field, M: Mmcs<F>, Witness, InputProof> {
+pub struct FriProof<F: Field, M: MultiMmcs<F>, Witness, InputProof> {
     pub commit_phase_commits: Vec<M::Commitment>,
-    pub query_proofs: Vec<QueryProof<F, M, InputProof>>,
-    // This could become Vec<FC::Challenge> if this library was generalized to support non-constant
-    // final polynomials.
-    pub final_poly: F,
+    /// For each commit phase commitment, the proof of work witness ground before its folding
+    /// challenge was sampled; empty if `FriConfig::commit_proof_of_work_bits` is zero.
+    pub commit_pow_witnesses: Vec<Witness>,
+    pub query_proofs: Vec<QueryProof<F, InputProof>>,
+    /// For each commit phase commitment, a proof of the openings of the codeword made by all of the
+    /// queries, in which the Merkle paths they share appear once.
+    pub commit_phase_proofs: Vec<M::MultiProof>,
+    /// The coefficients of the final polynomial, lowest degree first.
+    pub final_poly: Vec<F>,
     pub pow_witness: Witness,
 }
 
@@ -23,20 +28,18 @@ pub struct FriProof<F: Field, M: Mmcs<F>, Witness, InputProof> {
     serialize = "InputProof: Serialize",
     deserialize = "InputProof: Deserialize<'de>",
 ))]
-pub struct QueryProof<F: Field, M: Mmcs<F>, InputProof> {
+pub struct QueryProof<F: Field, InputProof> {
     pub input_proof: InputProof,
     /// For each commit phase commitment, this contains openings of a commit phase codeword at the
-    /// queried location, along with an opening proof.
-    pub commit_phase_openings: Vec<CommitPhaseProofStep<F, M>>,
+    /// queried location. They are proven together with those of the other queries, in
+    /// `FriProof::commit_phase_proofs`.
+    pub commit_phase_openings: Vec<CommitPhaseProofStep<F>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(bound = "")]
-pub struct CommitPhaseProofStep<F: Field, M: Mmcs<F>> {
-    /// The opening of the commit phase codeword at the sibling location.
-    // This may change to Vec<FC::Challenge> if the library is generalized to support other FRI
-    // folding arities besides 2, meaning that there can be multiple siblings.
-    pub sibling_value: F,
-
-    pub opening_proof: M::Proof,
+pub struct CommitPhaseProofStep<F: Field> {
+    /// The openings of the commit phase codeword at the other locations of the queried row, of
+    /// which there are one fewer than the arity of the round.
+    pub sibling_values: Vec<F>,
 }
@@ -4,15 +4,15 @@ use core::iter;
 
 use itertools::{izip, Itertools};
 use p3_challenger::{CanObserve, FieldChallenger, GrindingChallenger};
-use p3_commit::Mmcs;
-use p3_field::{ExtensionField, Field};
+use p3_commit::{Mmcs, MultiMmcs, ProverObserver, ProverPhase};
+use p3_dft::{Radix2Dit, TwoAdicSubgroupDft};
+use p3_field::{ExtensionField, Field, TwoAdicField};
 use p3_matrix::dense::RowMajorMatrix;
-use p3_util::log2_strict_usize;
+use p3_util::{log2_strict_usize, reverse_slice_index_bits};
 use tracing::{info_span, instrument};
 
 use crate::{CommitPhaseProofStep, FriConfig, FriGenericConfig, FriProof, QueryProof};
 
-#[instrument(name = "FRI prover", skip_all)]
 pub fn prove<G, Val, Challenge, M, Challenger>(
     g: &G,
     config: &FriConfig<M>,
@@ -22,8 +22,28 @@ pub fn prove<G, Val, Challenge, M, Challenger>(
 ) -> FriProof<Challenge, M, Challenger::Witness, G::InputProof>
 where
     Val: Field,
-    Challenge: ExtensionField<Val>,
-    M: Mmcs<Challenge>,
+    Challenge: ExtensionField<Val> + TwoAdicField,
+    M: MultiMmcs<Challenge>,
+    Challenger: FieldChallenger<Val> + GrindingChallenger + CanObserve<M::Commitment>,
+    G: FriGenericConfig<Challenge>,
+{
+    prove_observed(g, config, inputs, challenger, open_input, &mut ())
+}
+
+/// As `prove`, reporting the commit phase, the proof of work and the query phase to `observer`.
+#[instrument(name = "FRI prover", skip_all)]
+pub fn prove_observed<G, Val, Challenge, M, Challenger>(
+    g: &G,
+    config: &FriConfig<M>,
+    inputs: Vec<Vec<Challenge>>,
+    challenger: &mut Challenger,
+    open_input: impl Fn(usize) -> G::InputProof,
+    observer: &mut impl ProverObserver,
+) -> FriProof<Challenge, M, Challenger::Witness, G::InputProof>
+where
+    Val: Field,
+    Challenge: ExtensionField<Val> + TwoAdicField,
+    M: MultiMmcs<Challenge>,
     Challenger: FieldChallenger<Val> + GrindingChallenger + CanObserve<M::Commitment>,
     G: FriGenericConfig<Challenge>,
 {
@@ -35,36 +55,63 @@ where
 
     let log_max_height = log2_strict_usize(inputs[0].len());
 
+    let phase = ProverPhase::FriCommit { log_max_height };
+    observer.phase_started(phase);
     let commit_phase_result = commit_phase(g, config, inputs, challenger);
+    observer.phase_finished(phase);
 
+    let phase = ProverPhase::ProofOfWork {
+        bits: config.proof_of_work_bits,
+    };
+    observer.phase_started(phase);
     let pow_witness = challenger.grind(config.proof_of_work_bits);
-
-    let query_proofs = info_span!("query phase").in_scope(|| {
-        iter::repeat_with(|| challenger.sample_bits(log_max_height + g.extra_query_index_bits()))
-            .take(config.num_queries)
-            .map(|index| QueryProof {
+    observer.phase_finished(phase);
+
+    let phase = ProverPhase::FriQueries {
+        num_queries: config.num_queries,
+    };
+    observer.phase_started(phase);
+    let (query_proofs, commit_phase_proofs) = info_span!("query phase").in_scope(|| {
+        let indices = iter::repeat_with(|| {
+            challenger.sample_bits(log_max_height + g.extra_query_index_bits())
+        })
+        .take(config.num_queries)
+        .collect_vec();
+        let (commit_phase_openings, commit_phase_proofs) = answer_queries(
+            config,
+            &commit_phase_result.data,
+            &commit_phase_result.log_arities,
+            indices
+                .iter()
+                .map(|index| index >> g.extra_query_index_bits())
+                .collect(),
+        );
+        let query_proofs = izip!(indices, commit_phase_openings)
+            .map(|(index, commit_phase_openings)| QueryProof {
                 input_proof: open_input(index),
-                commit_phase_openings: answer_query(
-                    config,
-                    &commit_phase_result.data,
-                    index >> g.extra_query_index_bits(),
-                ),
+                commit_phase_openings,
             })
-            .collect()
+            .collect();
+        (query_proofs, commit_phase_proofs)
     });
+    observer.phase_finished(phase);
 
     FriProof {
         commit_phase_commits: commit_phase_result.commits,
+        commit_pow_witnesses: commit_phase_result.pow_witnesses,
         query_proofs,
+        commit_phase_proofs,
         final_poly: commit_phase_result.final_poly,
         pow_witness,
     }
 }
 
-struct CommitPhaseResult<F: Field, M: Mmcs<F>> {
+struct CommitPhaseResult<F: Field, M: Mmcs<F>, Witness> {
     commits: Vec<M::Commitment>,
+    pow_witnesses: Vec<Witness>,
     data: Vec<M::ProverData<RowMajorMatrix<F>>>,
-    final_poly: F,
+    log_arities: Vec<usize>,
+    final_poly: Vec<F>,
 }
 
 #[instrument(name = "commit phase", skip_all)]
@@ -73,24 +120,34 @@ fn commit_phase<G, Val, Challenge, M, Challenger>(
     config: &FriConfig<M>,
     inputs: Vec<Vec<Challenge>>,
     challenger: &mut Challenger,
-) -> CommitPhaseResult<Challenge, M>
+) -> CommitPhaseResult<Challenge, M, Challenger::Witness>
 where
     Val: Field,
-    Challenge: ExtensionField<Val>,
+    Challenge: ExtensionField<Val> + TwoAdicField,
     M: Mmcs<Challenge>,
-    Challenger: FieldChallenger<Val> + CanObserve<M::Commitment>,
+    Challenger: FieldChallenger<Val> + GrindingChallenger + CanObserve<M::Commitment>,
     G: FriGenericConfig<Challenge>,
 {
+    let log_arities = config.log_arities(
+        &inputs
+            .iter()
+            .map(|v| log2_strict_usize(v.len()))
+            .collect_vec(),
+    );
     let mut inputs_iter = inputs.into_iter().peekable();
     let mut folded = inputs_iter.next().unwrap();
     let mut commits = vec![];
+    let mut pow_witnesses = vec![];
     let mut data = vec![];
 
-    while folded.len() > config.blowup() {
-        let leaves = RowMajorMatrix::new(folded, 2);
+    for &log_arity in &log_arities {
+        let leaves = RowMajorMatrix::new(folded, 1 << log_arity);
         let (commit, prover_data) = config.mmcs.commit_matrix(leaves);
         challenger.observe(commit.clone());
 
+        if config.commit_proof_of_work_bits > 0 {
+            pow_witnesses.push(challenger.grind(config.commit_proof_of_work_bits));
+        }
         let beta: Challenge = challenger.sample_ext_element();
         // We passed ownership of `current` to the MMCS, so get a reference to it
         let leaves = config.mmcs.get_matrices(&prover_data).pop().unwrap();
@@ -103,53 +160,69 @@ where
             // Each element of `inputs_iter` is a reduced opening polynomial, which is itself a
             // random linear combination `f_{i, 0} + alpha f_{i, 1} + ...`, but when we add it
             // to the current folded polynomial, we need to multiply by a new random factor since
-            // `f_{i, 0}` has no leading coefficient.Add commentMore actions
-            izip!(&mut folded, v).for_each(|(c, x)| *c += beta.square() * x);
+            // `f_{i, 0}` has no leading coefficient.
+            //
+            // The fold used `beta` up to `beta^(arity - 1)`, so we use the next power.
+            let beta_pow = beta.exp_power_of_2(log_arity);
+            izip!(&mut folded, v).for_each(|(c, x)| *c += beta_pow * x);
         }
     }
 
-    // We should be left with `blowup` evaluations of a constant polynomial.
-    assert_eq!(folded.len(), config.blowup());
-    let final_poly = folded[0];
-    for x in folded {
-        assert_eq!(x, final_poly);
+    // We should be left with `blowup` times as many evaluations as the final polynomial has
+    // coefficients, in bit-reversed order over a subgroup, so its coefficients past those are zero.
+    let final_poly_len = folded.len() >> config.log_blowup;
+    reverse_slice_index_bits(&mut folded);
+    let mut final_poly = Radix2Dit::default().idft(folded);
+    assert!(final_poly
+        .drain(final_poly_len..)
+        .all(|coeff| coeff == Challenge::ZERO));
+    for &coeff in &final_poly {
+        challenger.observe_ext_element(coeff);
     }
-    challenger.observe_ext_element(final_poly);
 
     CommitPhaseResult {
         commits,
+        pow_witnesses,
         data,
+        log_arities,
         final_poly,
     }
 }
 
-fn answer_query<F, M>(
+/// Open each commit phase codeword at every query, returning the openings of each query and the
+/// multi-proof of each commit phase round.
+fn answer_queries<F, M>(
     config: &FriConfig<M>,
     commit_phase_commits: &[M::ProverData<RowMajorMatrix<F>>],
-    index: usize,
-) -> Vec<CommitPhaseProofStep<F, M>>
+    log_arities: &[usize],
+    mut indices: Vec<usize>,
+) -> (Vec<Vec<CommitPhaseProofStep<F>>>, Vec<M::MultiProof>)
 where
     F: Field,
-    M: Mmcs<F>,
+    M: MultiMmcs<F>,
 {
-    commit_phase_commits
-        .iter()
-        .enumerate()
-        .map(|(i, commit)| {
-            let index_i = index >> i;
-            let index_i_sibling = index_i ^ 1;
-            let index_pair = index_i >> 1;
-
-            let (mut opened_rows, opening_proof) = config.mmcs.open_batch(index_pair, commit);
-            assert_eq!(opened_rows.len(), 1);
-            let opened_row = opened_rows.pop().unwrap();
-            assert_eq!(opened_row.len(), 2, "Committed data should be in pairs");
-            let sibling_value = opened_row[index_i_sibling % 2];
-
-            CommitPhaseProofStep {
-                sibling_value,
-                opening_proof,
+    let mut openings = vec![Vec::with_capacity(commit_phase_commits.len()); indices.len()];
+    let proofs = izip!(commit_phase_commits, log_arities)
+        .map(|(commit, &log_arity)| {
+            let index_rows = indices.iter().map(|index| index >> log_arity).collect_vec();
+            let (opened_rows, opening_proof) = config.mmcs.open_multi_batch(&index_rows, commit);
+
+            for (query_openings, index, mut rows) in izip!(&mut openings, &indices, opened_rows) {
+                assert_eq!(rows.len(), 1);
+                let mut sibling_values = rows.pop().unwrap();
+                assert_eq!(
+                    sibling_values.len(),
+                    1 << log_arity,
+                    "Committed data should be in rows of the arity"
+                );
+                // The verifier knows the value at the queried index.
+                sibling_values.remove(index & ((1 << log_arity) - 1));
+                query_openings.push(CommitPhaseProofStep { sibling_values });
             }
+
+            indices = index_rows;
+            opening_proof
         })
-        .collect()
+        .collect();
+    (openings, proofs)
 }
@@ -0,0 +1,394 @@
+//! Proven soundness of FRI, and of the DEEP-ALI STARK protocol it is part of.
+//!
+//! The bounds follow [ethSTARK](https://eprint.iacr.org/2021/582), section 5, with the
+//! correlated agreement theorems of [BCIKS20](https://eprint.iacr.org/2020/654) for batching and
+//! folding with powers of a single challenge. Each bound is an error probability, which we report
+//! in bits, as `-log2` of it.
+
+use core::f64::consts::LN_2;
+use core::ops::RangeInclusive;
+
+use crate::FriConfig;
+
+/// How far from the code a word must be for FRI to reject it, which determines the list size of
+/// the code and so the soundness of each step.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProximityRegime {
+    /// Proximity up to the unique decoding radius, `(1 - rate) / 2`.
+    UniqueDecoding,
+    /// Proximity up to the Johnson bound, `1 - sqrt(rate) (1 + 1 / 2m)`, for an `m` of at least 3.
+    JohnsonBound { m: usize },
+}
+
+/// What the soundness of a STARK proof depends on, besides its `FriConfig`.
+#[derive(Clone, Debug)]
+pub struct SoundnessParams {
+    /// The log2 of the size of the field challenges are drawn from, usually an extension field.
+    pub challenge_field_bits: f64,
+    /// The log2 of the degree bound of the tallest polynomial FRI is run on, i.e. of its trace
+    /// height.
+    pub log_degree: usize,
+    /// The number of polynomials FRI batches with powers of one challenge: a column for each
+    /// point it is opened at.
+    pub num_batched: usize,
+    /// The number of AIR constraints, which are combined with powers of one challenge.
+    pub num_constraints: usize,
+    /// The maximum degree of the AIR constraints.
+    pub constraint_degree: usize,
+    /// The proof of work bits ground before the DEEP point is sampled.
+    pub zeta_proof_of_work_bits: usize,
+}
+
+/// The soundness, in bits, of each step of a STARK proof, as given by `proven_soundness_bits`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SoundnessBits {
+    /// Combining the constraints with a random challenge.
+    pub ali: f64,
+    /// Checking the constraints at a random point out of the domain, including the proof of work
+    /// that precedes it.
+    pub deep: f64,
+    /// Batching the polynomials and folding them in the commit phase, including the proof of work
+    /// that precedes each fold.
+    pub commit_phase: f64,
+    /// The queries, including the proof of work that precedes them.
+    pub query_phase: f64,
+}
+
+impl SoundnessBits {
+    /// The soundness of the whole proof, whose error is the sum of those of each step.
+    pub fn total(&self) -> f64 {
+        sum_errors(&[self.ali, self.deep, self.commit_phase, self.query_phase])
+    }
+}
+
+/// The FRI parameters `choose_parameters` picks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FriParameters {
+    pub log_blowup: usize,
+    pub num_queries: usize,
+    pub proof_of_work_bits: usize,
+}
+
+impl<M> FriConfig<M> {
+    /// Returns the proven soundness bits of each step of a STARK proof using this FRI instance,
+    /// in the given regime.
+    pub fn proven_soundness_bits(
+        &self,
+        regime: ProximityRegime,
+        params: &SoundnessParams,
+    ) -> SoundnessBits {
+        soundness_bits(
+            regime,
+            params,
+            self.log_blowup,
+            &self.log_arities(&[params.log_degree + self.log_blowup]),
+            self.num_queries,
+            self.proof_of_work_bits,
+            self.commit_proof_of_work_bits,
+        )
+    }
+
+    /// Picks the blowup, number of queries and proof of work bits that reach `target_bits` of
+    /// proven soundness in the given regime, keeping this instance's arity, final polynomial and
+    /// commit phase proof of work.
+    ///
+    /// Blowups too small for the constraint degree are skipped. Of the rest, we pick the one with
+    /// the fewest query openings, weighted by the length of their Merkle paths, and then the fewest
+    /// proof of work bits that suffice. Returns `None` if no blowup in `log_blowups` reaches the
+    /// target, e.g. because the field is too small.
+    pub fn choose_parameters(
+        &self,
+        target_bits: usize,
+        regime: ProximityRegime,
+        params: &SoundnessParams,
+        log_blowups: RangeInclusive<usize>,
+        max_proof_of_work_bits: usize,
+    ) -> Option<FriParameters> {
+        // The quotient has `constraint_degree - 1` times the trace's degree, and must fit in the
+        // evaluation domain.
+        let min_blowup = params.constraint_degree.saturating_sub(1).max(1);
+        let target_bits = target_bits as f64;
+
+        let mut best: Option<(usize, FriParameters)> = None;
+        for log_blowup in log_blowups.filter(|&lb| 1 << lb >= min_blowup) {
+            let log_arities = FriConfig::new(log_blowup, 0, 0, ())
+                .with_max_log_arity(self.max_log_arity)
+                .with_log_final_poly_len(self.log_final_poly_len)
+                .log_arities(&[params.log_degree + log_blowup]);
+            let bits = |num_queries, proof_of_work_bits| {
+                soundness_bits(
+                    regime,
+                    params,
+                    log_blowup,
+                    &log_arities,
+                    num_queries,
+                    proof_of_work_bits,
+                    self.commit_proof_of_work_bits,
+                )
+                .total()
+            };
+
+            // Each query adds at least a fraction of a bit, so this bounds the search.
+            let Some(num_queries) = (1..=MAX_QUERIES)
+                .find(|&num_queries| bits(num_queries, max_proof_of_work_bits) >= target_bits)
+            else {
+                continue;
+            };
+            let proof_of_work_bits = (0..=max_proof_of_work_bits)
+                .find(|&pow_bits| bits(num_queries, pow_bits) >= target_bits)
+                .unwrap();
+
+            let cost = num_queries * (params.log_degree + log_blowup);
+            if best.is_none_or(|(best_cost, _)| cost < best_cost) {
+                best = Some((
+                    cost,
+                    FriParameters {
+                        log_blowup,
+                        num_queries,
+                        proof_of_work_bits,
+                    },
+                ));
+            }
+        }
+        best.map(|(_, parameters)| parameters)
+    }
+}
+
+/// The most queries `choose_parameters` considers.
+const MAX_QUERIES: usize = 1000;
+
+#[allow(clippy::too_many_arguments)]
+fn soundness_bits(
+    regime: ProximityRegime,
+    params: &SoundnessParams,
+    log_blowup: usize,
+    log_arities: &[usize],
+    num_queries: usize,
+    proof_of_work_bits: usize,
+    commit_proof_of_work_bits: usize,
+) -> SoundnessBits {
+    let field_size = exp2(params.challenge_field_bits);
+    let degree = exp2(params.log_degree as f64);
+    let height = exp2((params.log_degree + log_blowup) as f64);
+    let sqrt_rate = exp2(-(log_blowup as f64) / 2.0);
+    let rate = sqrt_rate * sqrt_rate;
+
+    // The size of the list of codewords close to a word, and the probability that a query of a
+    // word far from the code passes.
+    let (list_size, query_error) = match regime {
+        ProximityRegime::UniqueDecoding => (1.0, (1.0 + rate) / 2.0),
+        ProximityRegime::JohnsonBound { m } => {
+            assert!(m >= 3, "the Johnson bound regime needs m >= 3");
+            let m = m as f64;
+            ((m + 0.5) / sqrt_rate, sqrt_rate * (1.0 + 1.0 / (2.0 * m)))
+        }
+    };
+    // The probability that a random linear combination, with powers of one challenge, of words of
+    // length `n` is close to the code while they are not, per power.
+    let correlated_agreement_error = |n: f64| match regime {
+        ProximityRegime::UniqueDecoding => n / field_size,
+        ProximityRegime::JohnsonBound { m } => {
+            let m_half_pow_7 = (0..7).fold(1.0, |acc, _| acc * (m as f64 + 0.5));
+            m_half_pow_7 * n * n / (3.0 * rate * sqrt_rate * field_size)
+        }
+    };
+
+    // ALI: the constraints combined with powers of one challenge vanish on the trace domain while
+    // some constraint does not, for one of the list of codewords close to the trace.
+    let ali_error = list_size * params.num_constraints as f64 / field_size;
+
+    // DEEP: the trace and quotient agree at a random point out of the evaluation domain, but do
+    // not satisfy the constraints, which have degree at most `constraint_degree * degree`.
+    let deep_error =
+        list_size * (params.constraint_degree as f64 * degree) / (field_size - height - degree);
+
+    // Batching the polynomials, and then folding each codeword, whose rows are a batch of `arity`
+    // words of a smaller code, after grinding.
+    let batch_error =
+        params.num_batched.saturating_sub(1) as f64 * correlated_agreement_error(height);
+    let mut log_height = params.log_degree + log_blowup;
+    let fold_error: f64 = log_arities
+        .iter()
+        .map(|&log_arity| {
+            log_height -= log_arity;
+            ((1 << log_arity) - 1) as f64 * correlated_agreement_error(exp2(log_height as f64))
+        })
+        .sum::<f64>()
+        / exp2(commit_proof_of_work_bits as f64);
+
+    SoundnessBits {
+        ali: -log2(ali_error),
+        deep: -log2(deep_error) + params.zeta_proof_of_work_bits as f64,
+        commit_phase: -log2(batch_error + fold_error),
+        query_phase: -log2(query_error) * num_queries as f64 + proof_of_work_bits as f64,
+    }
+}
+
+/// The soundness bits of a sum of errors, given the soundness bits of each of them.
+fn sum_errors(bits: &[f64]) -> f64 {
+    let min_bits = bits.iter().copied().fold(f64::INFINITY, f64::min);
+    if min_bits == f64::INFINITY {
+        return min_bits;
+    }
+    // Scale the errors by the largest of them, so that none overflow.
+    min_bits - log2(bits.iter().map(|&b| exp2(min_bits - b)).sum())
+}
+
+// `core` has no floating point logarithm or exponential, so we have our own, which are accurate
+// well beyond the hundredth of a bit that matters here.
+
+fn log2(x: f64) -> f64 {
+    if x == 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if x == f64::INFINITY {
+        return x;
+    }
+    debug_assert!(x > 0.0);
+    // Write `x = 2^e y` with `y` in `[1, 2)`, for which `ln y = 2 atanh((y - 1) / (y + 1))`.
+    let (mut e, mut y) = (0, x);
+    while y >= 2.0 {
+        y /= 2.0;
+        e += 1;
+    }
+    while y < 1.0 {
+        y *= 2.0;
+        e -= 1;
+    }
+    let z = (y - 1.0) / (y + 1.0);
+    let z2 = z * z;
+    let mut term = z;
+    let mut atanh = 0.0;
+    for k in 0..30 {
+        atanh += term / (2 * k + 1) as f64;
+        term *= z2;
+    }
+    e as f64 + 2.0 * atanh / LN_2
+}
+
+fn exp2(x: f64) -> f64 {
+    // Beyond these, the result is out of the range of `f64`.
+    if x < -1100.0 {
+        return 0.0;
+    }
+    if x > 1100.0 {
+        return f64::INFINITY;
+    }
+    // Write `x = e + f` with `f` in `[0, 1)`, and sum the series of `2^f = exp(f ln 2)`.
+    let mut e = x as i32;
+    if e as f64 > x {
+        e -= 1;
+    }
+    let t = (x - e as f64) * LN_2;
+    let mut term = 1.0;
+    let mut exp = 0.0;
+    for k in 1..30 {
+        exp += term;
+        term *= t / k as f64;
+    }
+    let mut result = exp;
+    for _ in 0..e.unsigned_abs() {
+        if e > 0 {
+            result *= 2.0;
+        } else {
+            result /= 2.0;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use core::f64::consts::SQRT_2;
+
+    use super::*;
+
+    fn params() -> SoundnessParams {
+        SoundnessParams {
+            // A quintic extension of a 31-bit field.
+            challenge_field_bits: 5.0 * 31.0,
+            log_degree: 20,
+            num_batched: 200,
+            num_constraints: 100,
+            constraint_degree: 3,
+            zeta_proof_of_work_bits: 0,
+        }
+    }
+
+    fn config(log_blowup: usize, num_queries: usize, proof_of_work_bits: usize) -> FriConfig<()> {
+        FriConfig::new(log_blowup, num_queries, proof_of_work_bits, ())
+    }
+
+    #[test]
+    fn log2_and_exp2() {
+        for x in [1e-30, 0.3, 1.0, 3.0, 1e40] {
+            assert!((exp2(log2(x)) / x - 1.0).abs() < 1e-12);
+        }
+        assert!((log2(3.0) - 1.584_962_500_721_156).abs() < 1e-12);
+        assert!((exp2(-0.5) - SQRT_2 / 2.0).abs() < 1e-12);
+        assert_eq!(exp2(-100.0), 1.0 / (1u128 << 100) as f64);
+        assert_eq!(sum_errors(&[10.0, 10.0]), 9.0);
+        assert_eq!(sum_errors(&[f64::INFINITY, 10.0]), 10.0);
+    }
+
+    #[test]
+    fn query_phase() {
+        // With a blowup of 2, each query of a word outside the unique decoding radius passes with
+        // probability 3/4.
+        let bits =
+            config(1, 100, 16).proven_soundness_bits(ProximityRegime::UniqueDecoding, &params());
+        assert!((bits.query_phase - (16.0 - 100.0 * log2(0.75))).abs() < 1e-9);
+
+        // Within the Johnson bound, each query of a larger blowup is worth more, but the commit
+        // phase is weaker.
+        let unique =
+            config(3, 100, 0).proven_soundness_bits(ProximityRegime::UniqueDecoding, &params());
+        let johnson = config(3, 100, 0)
+            .proven_soundness_bits(ProximityRegime::JohnsonBound { m: 3 }, &params());
+        assert!(johnson.query_phase > unique.query_phase);
+        assert!(johnson.commit_phase < unique.commit_phase);
+        assert!(johnson.total() <= johnson.query_phase);
+
+        // Grinding before each fold strengthens the commit phase.
+        let mut ground = config(3, 100, 0);
+        ground.commit_proof_of_work_bits = 16;
+        let ground =
+            ground.proven_soundness_bits(ProximityRegime::JohnsonBound { m: 3 }, &params());
+        assert!(ground.commit_phase > johnson.commit_phase);
+    }
+
+    #[test]
+    fn choose_parameters() {
+        let regime = ProximityRegime::JohnsonBound { m: 3 };
+        let parameters = config(0, 0, 0)
+            .choose_parameters(80, regime, &params(), 1..=4, 20)
+            .unwrap();
+        let FriParameters {
+            log_blowup,
+            num_queries,
+            proof_of_work_bits,
+        } = parameters;
+        assert!(
+            config(log_blowup, num_queries, proof_of_work_bits)
+                .proven_soundness_bits(regime, &params())
+                .total()
+                >= 80.0
+        );
+        assert!(
+            config(log_blowup, num_queries - 1, proof_of_work_bits)
+                .proven_soundness_bits(regime, &params())
+                .total()
+                < 80.0
+        );
+
+        // A 31-bit field is too small for the challenges.
+        let small_field = SoundnessParams {
+            challenge_field_bits: 31.0,
+            ..params()
+        };
+        assert_eq!(
+            config(0, 0, 0).choose_parameters(80, regime, &small_field, 1..=4, 20),
+            None
+        );
+    }
+}
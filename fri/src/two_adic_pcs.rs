@@ -1,12 +1,16 @@
 use alloc::collections::BTreeMap;
 use alloc::vec;
 use alloc::vec::Vec;
+use core::cmp::Reverse;
 use core::fmt::Debug;
 use core::marker::PhantomData;
+use core::ops::Range;
 
 use itertools::{izip, Itertools};
 use p3_challenger::{CanObserve, FieldChallenger, GrindingChallenger};
-use p3_commit::{Mmcs, OpenedValues, Pcs, PolynomialSpace, TwoAdicMultiplicativeCoset};
+use p3_commit::{
+    Mmcs, MultiMmcs, OpenedValues, Pcs, PolynomialSpace, ProverObserver, TwoAdicMultiplicativeCoset,
+};
 use p3_dft::TwoAdicSubgroupDft;
 use p3_field::{
     batch_multiplicative_inverse, cyclic_subgroup_coset_known_order, dot_product, ExtensionField,
@@ -14,7 +18,7 @@ use p3_field::{
 };
 use p3_interpolation::interpolate_coset;
 use p3_matrix::bitrev::{BitReversableMatrix, BitReversalPerm};
-use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixViewMut};
 use p3_matrix::{Dimensions, Matrix};
 use p3_maybe_rayon::prelude::*;
 use p3_util::linear_map::LinearMap;
@@ -23,13 +27,23 @@ use serde::{Deserialize, Serialize};
 use tracing::{info_span, instrument};
 
 use crate::verifier::{self, FriError};
-use crate::{prover, FriConfig, FriGenericConfig, FriProof};
+use crate::{
+    fold_matrix_in_pairs, fold_row_in_pairs, prover, FriConfig, FriGenericConfig, FriProof,
+    LdeMatrix, LdeStorage,
+};
 
 #[derive(Debug)]
 pub struct TwoAdicFriPcs<Val, Dft, InputMmcs, FriMmcs> {
     dft: Dft,
     mmcs: InputMmcs,
     fri: FriConfig<FriMmcs>,
+    /// The number of bytes of DFT working memory `commit_columns` may use to encode a chunk of
+    /// columns. If `None`, every column is encoded at once.
+    chunk_budget: Option<usize>,
+    /// The directory of the temporary file holding the LDE committed to by `commit_columns`. If
+    /// `None`, the LDE is held in memory.
+    #[cfg(feature = "mmap")]
+    lde_dir: Option<std::path::PathBuf>,
     _phantom: PhantomData<Val>,
 }
 
@@ -39,13 +53,88 @@ impl<Val, Dft, InputMmcs, FriMmcs> TwoAdicFriPcs<Val, Dft, InputMmcs, FriMmcs> {
             dft,
             mmcs,
             fri,
+            chunk_budget: None,
+            #[cfg(feature = "mmap")]
+            lde_dir: None,
             _phantom: PhantomData,
         }
     }
 
+    /// Have `commit_columns` encode chunks of columns whose DFT working memory is about `bytes`.
+    ///
+    /// This only bounds the scratch space of the DFT. Each chunk's LDE is copied into the
+    /// committed LDE as soon as it is computed, and the committed LDE is kept as prover data, in
+    /// memory unless `with_lde_dir` places it in a file. A chunk always has at least one column,
+    /// even if its LDE exceeds the budget.
+    #[must_use]
+    pub const fn with_chunk_budget(mut self, bytes: usize) -> Self {
+        self.chunk_budget = Some(bytes);
+        self
+    }
+
+    /// Have `commit_columns` keep the LDE it commits to in a memory-mapped temporary file in
+    /// `dir`, rather than in memory. The MMCS hashes and opens its rows through the map, so the
+    /// operating system can page them out to the file, and only the chunk being encoded and the
+    /// pages in use need to fit in memory. The file is deleted once the prover data is dropped.
+    ///
+    /// The other matrices committed alongside it, and those committed by `commit`, are still held
+    /// in memory.
+    #[cfg(feature = "mmap")]
+    #[must_use]
+    pub fn with_lde_dir(mut self, dir: impl Into<std::path::PathBuf>) -> Self {
+        self.lde_dir = Some(dir.into());
+        self
+    }
+
     pub fn fri_config(&self) -> &FriConfig<FriMmcs> {
         &self.fri
     }
+
+    /// The number of columns `commit_columns` encodes at once, for a matrix of the given width
+    /// whose LDE has `lde_height` rows.
+    fn chunk_width(&self, width: usize, lde_height: usize) -> usize {
+        let Some(budget) = self.chunk_budget else {
+            return width;
+        };
+        // The DFT's output and its bit-reversed copy each hold the chunk's LDE.
+        let lde_column_bytes = 2 * lde_height * size_of::<Val>();
+        (budget / lde_column_bytes).clamp(1, width.max(1))
+    }
+
+    /// Storage for the `len` values of an LDE committed to by `commit_columns`, initialized by
+    /// `fill`, in a file if `with_lde_dir` was set and otherwise in memory.
+    fn lde_storage(&self, len: usize, fill: impl FnOnce(&mut [Val])) -> LdeStorage<Val>
+    where
+        Val: Field,
+    {
+        #[cfg(feature = "mmap")]
+        if let Some(dir) = &self.lde_dir {
+            return LdeStorage::mapped(dir, len, fill);
+        }
+        LdeStorage::in_memory(len, fill)
+    }
+}
+
+impl<Val, Dft, InputMmcs, FriMmcs> TwoAdicFriPcs<Val, Dft, InputMmcs, FriMmcs>
+where
+    Val: TwoAdicField,
+    Dft: TwoAdicSubgroupDft<Val>,
+{
+    /// The bit-reversed LDE of `evals` over `domain`, which is what we commit to.
+    fn lde(
+        &self,
+        domain: TwoAdicMultiplicativeCoset<Val>,
+        evals: RowMajorMatrix<Val>,
+    ) -> LdeMatrix<Val> {
+        assert_eq!(domain.size(), evals.height());
+        let shift = Val::GENERATOR / domain.shift;
+        let lde = self
+            .dft
+            .coset_lde_batch(evals, self.fri.log_blowup, shift)
+            .bit_reverse_rows()
+            .to_row_major_matrix();
+        LdeMatrix::new(LdeStorage::Vec(lde.values), lde.width)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -55,6 +144,27 @@ pub struct BatchOpening<Val: Field, InputMmcs: Mmcs<Val>> {
     pub opening_proof: <InputMmcs as Mmcs<Val>>::Proof,
 }
 
+/// Whether `batch_openings`, the openings of one query, hold a row for each matrix of each round,
+/// as wide as the values claimed for that matrix at each of its points.
+#[allow(clippy::type_complexity)]
+pub fn valid_batch_openings<Val, Domain, Commitment, Challenge, InputMmcs>(
+    rounds: &[(Commitment, Vec<(Domain, Vec<(Challenge, Vec<Challenge>)>)>)],
+    batch_openings: &[BatchOpening<Val, InputMmcs>],
+) -> bool
+where
+    Val: Field,
+    InputMmcs: Mmcs<Val>,
+{
+    batch_openings.len() == rounds.len()
+        && izip!(batch_openings, rounds).all(|(batch_opening, (_, mats))| {
+            !mats.is_empty()
+                && batch_opening.opened_values.len() == mats.len()
+                && izip!(&batch_opening.opened_values, mats).all(|(row, (_, points))| {
+                    points.iter().all(|(_, values)| values.len() == row.len())
+                })
+        })
+}
+
 pub struct TwoAdicFriGenericConfig<InputProof, InputError>(
     pub PhantomData<(InputProof, InputError)>,
 );
@@ -79,75 +189,72 @@ impl<F: TwoAdicField, InputProof, InputError: Debug> FriGenericConfig<F>
         beta: F,
         evals: impl Iterator<Item = F>,
     ) -> F {
-        let arity = 2;
-        let log_arity = 1;
-        let (e0, e1) = evals
-            .collect_tuple()
-            .expect("TwoAdicFriFolder only supports arity=2");
-        // If performance critical, make this API stateful to avoid this
-        // This is a bit more math than is necessary, but leaving it here
-        // in case we want higher arity in the future
-        let subgroup_start = F::two_adic_generator(log_height + log_arity)
-            .exp_u64(reverse_bits_len(index, log_height) as u64);
-        let mut xs = F::two_adic_generator(log_arity)
-            .shifted_powers(subgroup_start)
-            .take(arity)
-            .collect_vec();
-        reverse_slice_index_bits(&mut xs);
-        assert_eq!(log_arity, 1, "can only interpolate two points for now");
-        // interpolate and evaluate at beta
-        e0 + (beta - xs[0]) * (e1 - e0) / (xs[1] - xs[0])
+        fold_row_in_pairs(index, log_height, beta, evals, fold_pair)
     }
 
     fn fold_matrix<M: Matrix<F>>(&self, beta: F, m: M) -> Vec<F> {
-        // We use the fact that
-        //     p_e(x^2) = (p(x) + p(-x)) / 2
-        //     p_o(x^2) = (p(x) - p(-x)) / (2 x)
-        // that is,
-        //     p_e(g^(2i)) = (p(g^i) + p(g^(n/2 + i))) / 2
-        //     p_o(g^(2i)) = (p(g^i) - p(g^(n/2 + i))) / (2 g^i)
-        // so
-        //     result(g^(2i)) = p_e(g^(2i)) + beta p_o(g^(2i))
-        //                    = (1/2 + beta/2 g_inv^i) p(g^i)
-        //                    + (1/2 - beta/2 g_inv^i) p(g^(n/2 + i))
-        let g_inv = F::two_adic_generator(log2_strict_usize(m.height()) + 1).inverse();
-        let one_half = F::ONE.halve();
-        let half_beta = beta * one_half;
-
-        // TODO: vectorize this (after we have packed extension fields)
-
-        // beta/2 times successive powers of g_inv
-        let mut powers = g_inv
-            .shifted_powers(half_beta)
-            .take(m.height())
-            .collect_vec();
-        reverse_slice_index_bits(&mut powers);
-
-        m.par_rows()
-            .zip(powers)
-            .map(|(mut row, power)| {
-                let (lo, hi) = row.next_tuple().unwrap();
-                (one_half + power) * lo + (one_half - power) * hi
-            })
-            .collect()
+        fold_matrix_in_pairs(beta, m, fold_pairs)
     }
 }
 
+/// Fold the `index`th pair of a codeword whose folded height is `2^log_height`.
+fn fold_pair<F: TwoAdicField>(index: usize, log_height: usize, beta: F, e0: F, e1: F) -> F {
+    // The pair holds the evaluations at `x` and `-x`.
+    let x =
+        F::two_adic_generator(log_height + 1).exp_u64(reverse_bits_len(index, log_height) as u64);
+    // interpolate and evaluate at beta
+    e0 + (beta - x) * (e1 - e0) / (-x - x)
+}
+
+/// Fold each row of `m`, which has width 2.
+fn fold_pairs<F: TwoAdicField>(beta: F, m: RowMajorMatrix<F>) -> Vec<F> {
+    // We use the fact that
+    //     p_e(x^2) = (p(x) + p(-x)) / 2
+    //     p_o(x^2) = (p(x) - p(-x)) / (2 x)
+    // that is,
+    //     p_e(g^(2i)) = (p(g^i) + p(g^(n/2 + i))) / 2
+    //     p_o(g^(2i)) = (p(g^i) - p(g^(n/2 + i))) / (2 g^i)
+    // so
+    //     result(g^(2i)) = p_e(g^(2i)) + beta p_o(g^(2i))
+    //                    = (1/2 + beta/2 g_inv^i) p(g^i)
+    //                    + (1/2 - beta/2 g_inv^i) p(g^(n/2 + i))
+    let g_inv = F::two_adic_generator(log2_strict_usize(m.height()) + 1).inverse();
+    let one_half = F::ONE.halve();
+    let half_beta = beta * one_half;
+
+    // TODO: vectorize this (after we have packed extension fields)
+
+    // beta/2 times successive powers of g_inv
+    let mut powers = g_inv
+        .shifted_powers(half_beta)
+        .take(m.height())
+        .collect_vec();
+    reverse_slice_index_bits(&mut powers);
+
+    m.par_rows()
+        .zip(powers)
+        .map(|(mut row, power)| {
+            let (lo, hi) = row.next_tuple().unwrap();
+            (one_half + power) * lo + (one_half - power) * hi
+        })
+        .collect()
+}
+
 impl<Val, Dft, InputMmcs, FriMmcs, Challenge, Challenger> Pcs<Challenge, Challenger>
     for TwoAdicFriPcs<Val, Dft, InputMmcs, FriMmcs>
 where
     Val: TwoAdicField,
     Dft: TwoAdicSubgroupDft<Val>,
     InputMmcs: Mmcs<Val>,
-    FriMmcs: Mmcs<Challenge>,
+    FriMmcs: MultiMmcs<Challenge>,
     Challenge: TwoAdicField + ExtensionField<Val>,
     Challenger:
         FieldChallenger<Val> + CanObserve<FriMmcs::Commitment> + GrindingChallenger<Witness = Val>,
-    <InputMmcs as Mmcs<Val>>::ProverData<RowMajorMatrix<Val>>: Clone,
+    <InputMmcs as Mmcs<Val>>::ProverData<LdeMatrix<Val>>: Clone,
 {
     type Domain = TwoAdicMultiplicativeCoset<Val>;
     type Commitment = InputMmcs::Commitment;
-    type ProverData = InputMmcs::ProverData<RowMajorMatrix<Val>>;
+    type ProverData = InputMmcs::ProverData<LdeMatrix<Val>>;
     type Proof = FriProof<Challenge, FriMmcs, Val, Vec<BatchOpening<Val, InputMmcs>>>;
     type Error = FriError<FriMmcs::Error, InputMmcs::Error>;
 
@@ -159,26 +266,54 @@ where
         }
     }
 
+    fn log_max_domain_size(&self) -> usize {
+        Val::TWO_ADICITY
+    }
+
     fn commit(
         &self,
         evaluations: Vec<(Self::Domain, RowMajorMatrix<Val>)>,
     ) -> (Self::Commitment, Self::ProverData) {
         let ldes: Vec<_> = evaluations
             .into_iter()
-            .map(|(domain, evals)| {
-                assert_eq!(domain.size(), evals.height());
-                let shift = Val::GENERATOR / domain.shift;
-                // Commit to the bit-reversed LDE.
-                self.dft
-                    .coset_lde_batch(evals, self.fri.log_blowup, shift)
-                    .bit_reverse_rows()
-                    .to_row_major_matrix()
-            })
+            .map(|(domain, evals)| self.lde(domain, evals))
             .collect();
 
         self.mmcs.commit(ldes)
     }
 
+    fn commit_columns(
+        &self,
+        domain: Self::Domain,
+        width: usize,
+        mut columns: impl FnMut(Range<usize>) -> RowMajorMatrix<Val>,
+        others: Vec<(Self::Domain, RowMajorMatrix<Val>)>,
+    ) -> (Self::Commitment, Self::ProverData) {
+        let lde_height = domain.size() << self.fri.log_blowup;
+        let chunk_width = self.chunk_width(width, lde_height);
+        let storage = self.lde_storage(width * lde_height, |values| {
+            let mut lde = RowMajorMatrixViewMut::new(values, width);
+            for start in (0..width).step_by(chunk_width) {
+                let end = (start + chunk_width).min(width);
+                let evals = columns(start..end);
+                assert_eq!(evals.width(), end - start);
+                let chunk_lde =
+                    info_span!("lde of columns", start, end).in_scope(|| self.lde(domain, evals));
+                lde.par_rows_mut()
+                    .zip(chunk_lde.par_row_slices())
+                    .for_each(|(row, chunk_row)| row[start..end].copy_from_slice(chunk_row));
+            }
+        });
+
+        let mut ldes = vec![LdeMatrix::new(storage, width)];
+        ldes.extend(
+            others
+                .into_iter()
+                .map(|(domain, evals)| self.lde(domain, evals)),
+        );
+        self.mmcs.commit(ldes)
+    }
+
     fn get_evaluations_on_domain<'a>(
         &self,
         prover_data: &'a Self::ProverData,
@@ -204,6 +339,15 @@ where
             >,
         )>,
         challenger: &mut Challenger,
+    ) -> (OpenedValues<Challenge>, Self::Proof) {
+        self.open_observed(rounds, challenger, &mut ())
+    }
+
+    fn open_observed(
+        &self,
+        rounds: Vec<(&Self::ProverData, Vec<Vec<Challenge>>)>,
+        challenger: &mut Challenger,
+        observer: &mut impl ProverObserver,
     ) -> (OpenedValues<Challenge>, Self::Proof) {
         /*
 
@@ -328,7 +472,7 @@ where
         let g: TwoAdicFriGenericConfigForMmcs<Val, InputMmcs> =
             TwoAdicFriGenericConfig(PhantomData);
 
-        let fri_proof = prover::prove(&g, &self.fri, fri_input, challenger, |index| {
+        let open_input = |index| {
             rounds
                 .iter()
                 .map(|(data, _)| {
@@ -342,7 +486,9 @@ where
                     }
                 })
                 .collect()
-        });
+        };
+        let fri_proof =
+            prover::prove_observed(&g, &self.fri, fri_input, challenger, open_input, observer);
 
         (all_opened_values, fri_proof)
     }
@@ -368,87 +514,116 @@ where
         proof: &Self::Proof,
         challenger: &mut Challenger,
     ) -> Result<(), Self::Error> {
+        // FRI folds the codewords of each height in turn, so the domains determine the commit
+        // phase rounds, which FRI checks. Check the shape of each query's openings before using
+        // the proof.
+        let log_heights = rounds
+            .iter()
+            .flat_map(|(_, mats)| mats)
+            .map(|(domain, _)| log2_strict_usize(domain.size()) + self.fri.log_blowup)
+            .sorted_by_key(|&log_height| Reverse(log_height))
+            .dedup()
+            .collect_vec();
+        let Some(&log_global_max_height) = log_heights.first() else {
+            return Err(FriError::InvalidProofShape);
+        };
+        let valid_shape = log_global_max_height <= Val::TWO_ADICITY
+            && proof
+                .query_proofs
+                .iter()
+                .all(|qp| valid_batch_openings(&rounds, &qp.input_proof));
+        if !valid_shape {
+            return Err(FriError::InvalidProofShape);
+        }
+
         // Batch combination challenge
         let alpha: Challenge = challenger.sample_ext_element();
 
-        let log_global_max_height = proof.commit_phase_commits.len() + self.fri.log_blowup;
-
         let g: TwoAdicFriGenericConfigForMmcs<Val, InputMmcs> =
             TwoAdicFriGenericConfig(PhantomData);
 
-        verifier::verify(&g, &self.fri, proof, challenger, |index, input_proof| {
-            // TODO: separate this out into functions
-
-            // log_height -> (alpha_pow, reduced_opening)
-            let mut reduced_openings = BTreeMap::<usize, (Challenge, Challenge)>::new();
-
-            for (batch_opening, (batch_commit, mats)) in izip!(input_proof, &rounds) {
-                let batch_heights = mats
-                    .iter()
-                    .map(|(domain, _)| domain.size() << self.fri.log_blowup)
-                    .collect_vec();
-                let batch_dims = batch_heights
-                    .iter()
-                    // TODO: MMCS doesn't really need width; we put 0 for now.
-                    .map(|&height| Dimensions { width: 0, height })
-                    .collect_vec();
-
-                let batch_max_height = batch_heights.iter().max().expect("Empty batch?");
-                let log_batch_max_height = log2_strict_usize(*batch_max_height);
-                let bits_reduced = log_global_max_height - log_batch_max_height;
-                let reduced_index = index >> bits_reduced;
-
-                self.mmcs.verify_batch(
-                    batch_commit,
-                    &batch_dims,
-                    reduced_index,
-                    &batch_opening.opened_values,
-                    &batch_opening.opening_proof,
-                )?;
-                for (mat_opening, (mat_domain, mat_points_and_values)) in
-                    izip!(&batch_opening.opened_values, mats)
-                {
-                    let log_height = log2_strict_usize(mat_domain.size()) + self.fri.log_blowup;
-
-                    let bits_reduced = log_global_max_height - log_height;
-                    let rev_reduced_index = reverse_bits_len(index >> bits_reduced, log_height);
-
-                    // todo: this can be nicer with domain methods?
-
-                    let x = Val::GENERATOR
-                        * Val::two_adic_generator(log_height).exp_u64(rev_reduced_index as u64);
-
-                    let (alpha_pow, ro) = reduced_openings
-                        .entry(log_height)
-                        .or_insert((Challenge::ONE, Challenge::ZERO));
-
-                    for (z, ps_at_z) in mat_points_and_values {
-                        for (&p_at_x, &p_at_z) in izip!(mat_opening, ps_at_z) {
-                            let quotient = (-p_at_z + p_at_x) / (-*z + x);
-                            *ro += *alpha_pow * quotient;
-                            *alpha_pow *= alpha;
+        verifier::verify(
+            &g,
+            &self.fri,
+            &log_heights,
+            proof,
+            challenger,
+            |index, input_proof| {
+                // TODO: separate this out into functions
+
+                // log_height -> (alpha_pow, reduced_opening)
+                let mut reduced_openings = BTreeMap::<usize, (Challenge, Challenge)>::new();
+
+                for (batch_opening, (batch_commit, mats)) in izip!(input_proof, &rounds) {
+                    let batch_heights = mats
+                        .iter()
+                        .map(|(domain, _)| domain.size() << self.fri.log_blowup)
+                        .collect_vec();
+                    let batch_dims = batch_heights
+                        .iter()
+                        // TODO: MMCS doesn't really need width; we put 0 for now.
+                        .map(|&height| Dimensions { width: 0, height })
+                        .collect_vec();
+
+                    // The shape check above rules out empty batches.
+                    let batch_max_height = batch_heights.iter().max().unwrap();
+                    let log_batch_max_height = log2_strict_usize(*batch_max_height);
+                    let bits_reduced = log_global_max_height - log_batch_max_height;
+                    let reduced_index = index >> bits_reduced;
+
+                    self.mmcs
+                        .verify_batch(
+                            batch_commit,
+                            &batch_dims,
+                            reduced_index,
+                            &batch_opening.opened_values,
+                            &batch_opening.opening_proof,
+                        )
+                        .map_err(FriError::InputError)?;
+                    for (mat_opening, (mat_domain, mat_points_and_values)) in
+                        izip!(&batch_opening.opened_values, mats)
+                    {
+                        let log_height = log2_strict_usize(mat_domain.size()) + self.fri.log_blowup;
+
+                        let bits_reduced = log_global_max_height - log_height;
+                        let rev_reduced_index = reverse_bits_len(index >> bits_reduced, log_height);
+
+                        // todo: this can be nicer with domain methods?
+
+                        let x = Val::GENERATOR
+                            * Val::two_adic_generator(log_height).exp_u64(rev_reduced_index as u64);
+
+                        let (alpha_pow, ro) = reduced_openings
+                            .entry(log_height)
+                            .or_insert((Challenge::ONE, Challenge::ZERO));
+
+                        for (z, ps_at_z) in mat_points_and_values {
+                            for (&p_at_x, &p_at_z) in izip!(mat_opening, ps_at_z) {
+                                let quotient = (-p_at_z + p_at_x) / (-*z + x);
+                                *ro += *alpha_pow * quotient;
+                                *alpha_pow *= alpha;
+                            }
                         }
                     }
                 }
-            }
-
-            // `reduced_openings` would have a log_height = log_blowup entry only if there was a
-            // trace matrix of height 1. In this case `f` is constant, so `f(zeta) - f(x))/(zeta - x)`Add commentMore actions
-            // must equal `0`.
-            if let Some((_alpha_pow, ro)) = reduced_openings.get(&self.fri.log_blowup) {
-                assert!(ro.is_zero());
-            }
 
-            // Return reduced openings descending by log_height.
-            Ok(reduced_openings
-                .into_iter()
-                .rev()
-                .map(|(log_height, (_alpha_pow, ro))| (log_height, ro))
-                .collect())
-        })
-        .expect("fri err");
+                // `reduced_openings` would have a log_height = log_blowup entry only if there was a
+                // trace matrix of height 1. In this case `f` is constant, so `f(zeta) - f(x))/(zeta - x)`Add commentMore actions
+                // must equal `0`.
+                if let Some((_alpha_pow, ro)) = reduced_openings.get(&self.fri.log_blowup) {
+                    if !ro.is_zero() {
+                        return Err(FriError::ConstantOpeningMismatch);
+                    }
+                }
 
-        Ok(())
+                // Return reduced openings descending by log_height.
+                Ok(reduced_openings
+                    .into_iter()
+                    .rev()
+                    .map(|(log_height, (_alpha_pow, ro))| (log_height, ro))
+                    .collect())
+            },
+        )
     }
 }
 
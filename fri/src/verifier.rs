@@ -3,9 +3,10 @@ use alloc::vec::Vec;
 
 use itertools::{izip, Itertools};
 use p3_challenger::{CanObserve, FieldChallenger, GrindingChallenger};
-use p3_commit::Mmcs;
-use p3_field::{ExtensionField, Field};
+use p3_commit::MultiMmcs;
+use p3_field::{ExtensionField, Field, TwoAdicField};
 use p3_matrix::Dimensions;
+use p3_util::reverse_bits_len;
 
 use crate::{CommitPhaseProofStep, FriConfig, FriGenericConfig, FriProof};
 
@@ -16,124 +17,180 @@ pub enum FriError<CommitMmcsErr, InputError> {
     InputError(InputError),
     FinalPolyMismatch,
     InvalidPowWitness,
+    /// A matrix of height one, and so a constant polynomial, was opened to a value other than its
+    /// claimed evaluation.
+    ConstantOpeningMismatch,
 }
 
 pub fn verify<G, Val, Challenge, M, Challenger>(
     g: &G,
     config: &FriConfig<M>,
+    log_input_heights: &[usize],
     proof: &FriProof<Challenge, M, Challenger::Witness, G::InputProof>,
     challenger: &mut Challenger,
-    open_input: impl Fn(usize, &G::InputProof) -> Result<Vec<(usize, Challenge)>, G::InputError>,
+    open_input: impl Fn(
+        usize,
+        &G::InputProof,
+    ) -> Result<Vec<(usize, Challenge)>, FriError<M::Error, G::InputError>>,
 ) -> Result<(), FriError<M::Error, G::InputError>>
 where
     Val: Field,
-    Challenge: ExtensionField<Val>,
-    M: Mmcs<Challenge>,
+    Challenge: ExtensionField<Val> + TwoAdicField,
+    M: MultiMmcs<Challenge>,
     Challenger: FieldChallenger<Val> + GrindingChallenger + CanObserve<M::Commitment>,
     G: FriGenericConfig<Challenge>,
 {
-    let betas: Vec<Challenge> = proof
-        .commit_phase_commits
-        .iter()
-        .map(|comm| {
-            challenger.observe(comm.clone());
-            challenger.sample_ext_element()
+    // Check the shape of the proof before using any of it, so that a malformed proof is rejected
+    // rather than indexed out of bounds or silently truncated.
+    let log_arities = config.log_arities(log_input_heights);
+    let log_final_height = config.log_final_height(log_input_heights);
+    let num_rounds = log_arities.len();
+    if proof.commit_phase_commits.len() != num_rounds
+        || proof.query_proofs.len() != config.num_queries
+        || proof.query_proofs.iter().any(|qp| {
+            qp.commit_phase_openings.len() != num_rounds
+                || izip!(&qp.commit_phase_openings, &log_arities)
+                    .any(|(step, &log_arity)| step.sibling_values.len() != (1 << log_arity) - 1)
         })
-        .collect();
-    challenger.observe_ext_element(proof.final_poly);
-
-    if proof.query_proofs.len() != config.num_queries {
+        || proof.commit_pow_witnesses.len()
+            != if config.commit_proof_of_work_bits > 0 {
+                num_rounds
+            } else {
+                0
+            }
+        || proof.commit_phase_proofs.len() != num_rounds
+        || proof.final_poly.len() != 1 << (log_final_height - config.log_blowup)
+    {
         return Err(FriError::InvalidProofShape);
     }
 
+    let mut betas: Vec<Challenge> = Vec::with_capacity(num_rounds);
+    for (round, comm) in proof.commit_phase_commits.iter().enumerate() {
+        challenger.observe(comm.clone());
+        // Check the commit phase PoW, if any, before sampling the round's challenge.
+        if let Some(&witness) = proof.commit_pow_witnesses.get(round) {
+            if !challenger.check_witness(config.commit_proof_of_work_bits, witness) {
+                return Err(FriError::InvalidPowWitness);
+            }
+        }
+        betas.push(challenger.sample_ext_element());
+    }
+    for &coeff in &proof.final_poly {
+        challenger.observe_ext_element(coeff);
+    }
+
     // Check PoW.
     if !challenger.check_witness(config.proof_of_work_bits, proof.pow_witness) {
         return Err(FriError::InvalidPowWitness);
     }
 
-    let log_max_height = proof.commit_phase_commits.len() + config.log_blowup;
+    let log_max_height = log_arities.iter().sum::<usize>() + log_final_height;
+
+    // The rows of each commit phase codeword opened by the queries, which are checked against the
+    // round's multi-proof once every query has been folded.
+    let mut opened_rows = vec![(vec![], vec![]); num_rounds];
 
     for qp in &proof.query_proofs {
         let index = challenger.sample_bits(log_max_height + g.extra_query_index_bits());
-        let ro = open_input(index, &qp.input_proof).map_err(FriError::InputError)?;
+        let ro = open_input(index, &qp.input_proof)?;
 
         debug_assert!(
             ro.iter().tuple_windows().all(|((l, _), (r, _))| l > r),
             "reduced openings sorted by height descending"
         );
 
-        let folded_eval = verify_query(
+        let (final_index, folded_eval) = verify_query(
             g,
-            config,
             index >> g.extra_query_index_bits(),
             izip!(
                 &betas,
-                &proof.commit_phase_commits,
-                &qp.commit_phase_openings
+                &log_arities,
+                &qp.commit_phase_openings,
+                &mut opened_rows
             ),
             ro,
             log_max_height,
-        )?;
+        );
 
-        if folded_eval != proof.final_poly {
+        // The final codeword is in bit-reversed order over the subgroup of its height.
+        let x = Challenge::two_adic_generator(log_final_height)
+            .exp_u64(reverse_bits_len(final_index, log_final_height) as u64);
+        let final_eval = proof
+            .final_poly
+            .iter()
+            .rev()
+            .fold(Challenge::ZERO, |acc, &coeff| acc * x + coeff);
+
+        if folded_eval != final_eval {
             return Err(FriError::FinalPolyMismatch);
         }
     }
 
+    let mut log_folded_height = log_max_height;
+    for (&log_arity, comm, (index_rows, rows), opening_proof) in izip!(
+        &log_arities,
+        &proof.commit_phase_commits,
+        opened_rows,
+        &proof.commit_phase_proofs
+    ) {
+        log_folded_height -= log_arity;
+        let dims = &[Dimensions {
+            width: 1 << log_arity,
+            height: 1 << log_folded_height,
+        }];
+        config
+            .mmcs
+            .verify_multi_batch(comm, dims, &index_rows, &rows, opening_proof)
+            .map_err(FriError::CommitPhaseMmcsError)?;
+    }
+
     Ok(())
 }
 
-type CommitStep<'a, F, M> = (
+/// A query's step in a commit phase round: the round's challenge and log arity, the query's
+/// opening, and the row indices and rows opened in the round so far, which the query adds its own
+/// to.
+type CommitStep<'a, F> = (
     &'a F,
-    &'a <M as Mmcs<F>>::Commitment,
-    &'a CommitPhaseProofStep<F, M>,
+    &'a usize,
+    &'a CommitPhaseProofStep<F>,
+    &'a mut (Vec<usize>, Vec<Vec<Vec<F>>>),
 );
 
-fn verify_query<'a, G, F, M>(
+/// Fold a query through the commit phase rounds, returning its index in the final codeword and
+/// the folded evaluation there.
+fn verify_query<'a, G, F>(
     g: &G,
-    config: &FriConfig<M>,
     mut index: usize,
-    steps: impl Iterator<Item = CommitStep<'a, F, M>>,
+    steps: impl Iterator<Item = CommitStep<'a, F>>,
     reduced_openings: Vec<(usize, F)>,
     log_max_height: usize,
-) -> Result<F, FriError<M::Error, G::InputError>>
+) -> (usize, F)
 where
     F: Field,
-    M: Mmcs<F> + 'a,
     G: FriGenericConfig<F>,
 {
     let mut folded_eval = F::ZERO;
     let mut ro_iter = reduced_openings.into_iter().peekable();
 
-    for (log_folded_height, (&beta, comm, opening)) in izip!((0..log_max_height).rev(), steps) {
-        if let Some((_, ro)) = ro_iter.next_if(|(lh, _)| *lh == log_folded_height + 1) {
+    let mut log_height = log_max_height;
+    for (&beta, &log_arity, opening, (index_rows, rows)) in steps {
+        if let Some((_, ro)) = ro_iter.next_if(|(lh, _)| *lh == log_height) {
             folded_eval += ro;
         }
 
-        let index_sibling = index ^ 1;
-        let index_pair = index >> 1;
+        let index_row = index >> log_arity;
 
-        let mut evals = vec![folded_eval; 2];
-        evals[index_sibling % 2] = opening.sibling_value;
+        let mut evals = opening.sibling_values.clone();
+        evals.insert(index & ((1 << log_arity) - 1), folded_eval);
 
-        let dims = &[Dimensions {
-            width: 2,
-            height: 1 << log_folded_height,
-        }];
-        config
-            .mmcs
-            .verify_batch(
-                comm,
-                dims,
-                index_pair,
-                &[evals.clone()],
-                &opening.opening_proof,
-            )
-            .map_err(FriError::CommitPhaseMmcsError)?;
+        index_rows.push(index_row);
+        rows.push(vec![evals.clone()]);
 
-        index = index_pair;
+        index = index_row;
+        log_height -= log_arity;
 
-        folded_eval = g.fold_row(index, log_folded_height, beta, evals.into_iter());
+        folded_eval = g.fold_row(index, log_height, beta, evals.into_iter());
 
         // If there are new polynomials to roll in at the folded height, do so.
         //
@@ -142,19 +199,23 @@ where
         // to the current folded polynomial evaluation claim, we need to multiply by a new random factor
         // since `f_{i, 0}` has no leading coefficient.
         //
-        // We use `beta^2` as the random factor since `beta` is already used in the folding.
-        // This increases the query phase error probability by a negligible amount, and does not change
-        // the required number of FRI queries.
-        if let Some((_, ro)) = ro_iter.next_if(|(lh, _)| *lh == log_folded_height) {
-            folded_eval += beta.square() * ro;
+        // We use `beta^arity` as the random factor since `beta` up to `beta^(arity - 1)` is already
+        // used in the folding. This increases the query phase error probability by a negligible
+        // amount, and does not change the required number of FRI queries.
+        if let Some((_, ro)) = ro_iter.next_if(|(lh, _)| *lh == log_height) {
+            folded_eval += beta.exp_power_of_2(log_arity) * ro;
         }
     }
 
-    debug_assert!(index < config.blowup(), "index was {}", index);
+    // Without any commit phase rounds, the tallest input is the final codeword itself.
+    if let Some((_, ro)) = ro_iter.next_if(|(lh, _)| *lh == log_height) {
+        folded_eval += ro;
+    }
+
     debug_assert!(
         ro_iter.next().is_none(),
         "verifier reduced_openings were not in descending order?"
     );
 
-    Ok(folded_eval)
+    (index, folded_eval)
 }
@@ -3,11 +3,12 @@ use std::marker::PhantomData;
 
 use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
 use p3_challenger::{CanSampleBits, DuplexChallenger, FieldChallenger};
-use p3_commit::ExtensionMmcs;
+use p3_commit::{decode_proof, encode_proof, ExtensionMmcs};
 use p3_dft::{Radix2Dit, TwoAdicSubgroupDft};
 use p3_field::extension::BinomialExtensionField;
 use p3_field::{Field, FieldAlgebra};
-use p3_fri::{prover, verifier, FriConfig, TwoAdicFriGenericConfig};
+use p3_fri::verifier::FriError;
+use p3_fri::{prover, verifier, FriConfig, FriProof, TwoAdicFriGenericConfig};
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::util::reverse_matrix_index_bits;
 use p3_matrix::Matrix;
@@ -29,22 +30,35 @@ type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
 type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
 type MyFriConfig = FriConfig<ChallengeMmcs>;
 
-fn get_ldt_for_testing<R: Rng>(rng: &mut R) -> (Perm, MyFriConfig) {
+fn get_ldt_for_testing<R: Rng>(
+    rng: &mut R,
+    max_log_arity: usize,
+    log_final_poly_len: usize,
+    commit_proof_of_work_bits: usize,
+) -> (Perm, MyFriConfig) {
     let perm = Perm::new_from_rng_128(rng);
     let hash = MyHash::new(perm.clone());
     let compress = MyCompress::new(perm.clone());
     let mmcs = ChallengeMmcs::new(ValMmcs::new(hash, compress));
-    let fri_config = FriConfig {
-        log_blowup: 1,
-        num_queries: 10,
-        proof_of_work_bits: 8,
-        mmcs,
-    };
+    let fri_config = FriConfig::new(1, 10, 8, mmcs)
+        .with_max_log_arity(max_log_arity)
+        .with_log_final_poly_len(log_final_poly_len)
+        .with_commit_proof_of_work_bits(commit_proof_of_work_bits);
     (perm, fri_config)
 }
 
-fn do_test_fri_ldt<R: Rng>(rng: &mut R) {
-    let (perm, fc) = get_ldt_for_testing(rng);
+fn do_test_fri_ldt<R: Rng>(
+    rng: &mut R,
+    max_log_arity: usize,
+    log_final_poly_len: usize,
+    commit_proof_of_work_bits: usize,
+) {
+    let (perm, fc) = get_ldt_for_testing(
+        rng,
+        max_log_arity,
+        log_final_poly_len,
+        commit_proof_of_work_bits,
+    );
     let dft = Radix2Dit::default();
 
     let shift = Val::GENERATOR;
@@ -108,11 +122,40 @@ fn do_test_fri_ldt<R: Rng>(rng: &mut R) {
         (proof, chal.sample_bits(8))
     };
 
+    // The proof survives a round trip through its binary encoding.
+    let bytes = encode_proof(&proof);
+    let proof: FriProof<Challenge, ChallengeMmcs, Val, Vec<(usize, Challenge)>> =
+        decode_proof(&bytes).unwrap();
+    assert_eq!(encode_proof(&proof), bytes);
+
+    let log_input_heights = (3..10)
+        .rev()
+        .map(|deg_bits| deg_bits + 1)
+        .collect::<Vec<_>>();
+
+    // A commit phase proof of work witness which doesn't match its round is rejected.
+    if let Some(witness) = proof.commit_pow_witnesses.last() {
+        let mut tampered = proof.clone();
+        *tampered.commit_pow_witnesses.last_mut().unwrap() = *witness + Val::ONE;
+        let mut v_challenger = Challenger::new(perm.clone());
+        let _alpha: Challenge = v_challenger.sample_ext_element();
+        let result = verifier::verify(
+            &TwoAdicFriGenericConfig::<Vec<(usize, Challenge)>, ()>(PhantomData),
+            &fc,
+            &log_input_heights,
+            &tampered,
+            &mut v_challenger,
+            |_index, proof| Ok(proof.clone()),
+        );
+        assert!(matches!(result, Err(FriError::InvalidPowWitness)));
+    }
+
     let mut v_challenger = Challenger::new(perm);
     let _alpha: Challenge = v_challenger.sample_ext_element();
     verifier::verify(
         &TwoAdicFriGenericConfig::<Vec<(usize, Challenge)>, ()>(PhantomData),
         &fc,
+        &log_input_heights,
         &proof,
         &mut v_challenger,
         |_index, proof| Ok(proof.clone()),
@@ -131,6 +174,50 @@ fn test_fri_ldt() {
     // FRI is kind of flaky depending on indexing luck
     for i in 0..4 {
         let mut rng = ChaCha20Rng::seed_from_u64(i);
-        do_test_fri_ldt(&mut rng);
+        do_test_fri_ldt(&mut rng, 1, 0, 0);
+    }
+}
+
+#[test]
+fn test_fri_ldt_higher_arity() {
+    for max_log_arity in 2..=4 {
+        let mut rng = ChaCha20Rng::seed_from_u64(max_log_arity as u64);
+        do_test_fri_ldt(&mut rng, max_log_arity, 0, 0);
     }
 }
+
+#[test]
+fn test_fri_ldt_final_poly() {
+    for (max_log_arity, log_final_poly_len) in [(1, 1), (1, 3), (2, 2), (3, 4)] {
+        let mut rng = ChaCha20Rng::seed_from_u64(log_final_poly_len as u64);
+        do_test_fri_ldt(&mut rng, max_log_arity, log_final_poly_len, 0);
+    }
+}
+
+#[test]
+fn test_fri_ldt_commit_proof_of_work() {
+    for (max_log_arity, commit_proof_of_work_bits) in [(1, 4), (2, 8)] {
+        let mut rng = ChaCha20Rng::seed_from_u64(commit_proof_of_work_bits as u64);
+        do_test_fri_ldt(&mut rng, max_log_arity, 0, commit_proof_of_work_bits);
+    }
+}
+
+#[test]
+fn test_log_arities() {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let (_, mut fc) = get_ldt_for_testing(&mut rng, 3, 0, 0);
+    assert_eq!(fc.log_arities(&[11, 9, 8, 2]), vec![2, 1, 3, 3, 1]);
+    assert_eq!(fc.log_arities(&[4, 4]), vec![3]);
+    fc.max_log_arity = 0;
+    assert_eq!(fc.log_arities(&[4]), vec![1, 1, 1]);
+
+    // Folding stops at the final polynomial, unless an input is shorter.
+    fc.max_log_arity = 2;
+    fc.log_final_poly_len = 3;
+    assert_eq!(fc.log_final_height(&[11, 9]), 4);
+    assert_eq!(fc.log_arities(&[11, 9]), vec![2, 2, 2, 1]);
+    assert_eq!(fc.log_final_height(&[11, 9, 2]), 2);
+    assert_eq!(fc.log_arities(&[11, 9, 2]), vec![2, 2, 2, 2, 1]);
+    assert_eq!(fc.log_final_height(&[3]), 3);
+    assert!(fc.log_arities(&[3]).is_empty());
+}
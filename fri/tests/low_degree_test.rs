@@ -0,0 +1,178 @@
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_challenger::{CanSampleBits, DuplexChallenger};
+use p3_commit::{decode_proof, encode_proof, ExtensionMmcs};
+use p3_dft::{Radix2Dit, TwoAdicSubgroupDft};
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{Field, FieldAlgebra, FieldExtensionAlgebra};
+use p3_fri::verifier::FriError;
+use p3_fri::{prove_low_degree, verify_low_degree, FriConfig, LowDegreeProof};
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+type Val = BabyBear;
+type Challenge = BinomialExtensionField<Val, 4>;
+
+type Perm = Poseidon2BabyBear<16>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type ValMmcs =
+    MerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type MyFriConfig = FriConfig<ChallengeMmcs>;
+type MyProof = LowDegreeProof<Challenge, ChallengeMmcs, Val>;
+
+fn get_config<R: Rng>(
+    rng: &mut R,
+    log_blowup: usize,
+    max_log_arity: usize,
+    log_final_poly_len: usize,
+) -> (Perm, MyFriConfig) {
+    let perm = Perm::new_from_rng_128(rng);
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let mmcs = ChallengeMmcs::new(ValMmcs::new(hash, compress));
+    let fri_config = FriConfig::new(log_blowup, 10, 8, mmcs)
+        .with_max_log_arity(max_log_arity)
+        .with_log_final_poly_len(log_final_poly_len);
+    (perm, fri_config)
+}
+
+/// The evaluations over the coset `shift H` of a random polynomial of degree less than `degree`,
+/// where `H` has order `height`.
+fn random_evals<R: Rng>(
+    rng: &mut R,
+    degree: usize,
+    height: usize,
+    shift: Challenge,
+) -> Vec<Challenge> {
+    let mut coeffs: Vec<Challenge> = (0..degree).map(|_| rng.gen()).collect();
+    coeffs.resize(height, Challenge::ZERO);
+    Radix2Dit::default().coset_dft(coeffs, shift)
+}
+
+fn do_test_low_degree<R: Rng>(
+    rng: &mut R,
+    degree_bound: usize,
+    log_blowup: usize,
+    max_log_arity: usize,
+    log_final_poly_len: usize,
+) {
+    let (perm, config) = get_config(rng, log_blowup, max_log_arity, log_final_poly_len);
+    let shift: Challenge = rng.gen();
+    let height = degree_bound.next_power_of_two() << log_blowup;
+    let evals = random_evals(rng, degree_bound, height, shift);
+
+    let mut p_challenger = Challenger::new(perm.clone());
+    let proof = prove_low_degree(&config, shift, degree_bound, evals, &mut p_challenger);
+
+    // The proof survives a round trip through its binary encoding.
+    let bytes = encode_proof(&proof);
+    let proof: MyProof = decode_proof(&bytes).unwrap();
+
+    let mut v_challenger = Challenger::new(perm.clone());
+    verify_low_degree(&config, shift, degree_bound, &proof, &mut v_challenger).unwrap();
+    assert_eq!(
+        p_challenger.sample_bits(8),
+        v_challenger.sample_bits(8),
+        "prover and verifier transcript have same state after the test"
+    );
+
+    // A proof for another coset, or for a lower degree bound, is rejected.
+    let mut v_challenger = Challenger::new(perm.clone());
+    assert!(verify_low_degree(
+        &config,
+        shift + Challenge::ONE,
+        degree_bound,
+        &proof,
+        &mut v_challenger
+    )
+    .is_err());
+    if degree_bound > 1 {
+        let mut v_challenger = Challenger::new(perm.clone());
+        assert!(
+            verify_low_degree(&config, shift, degree_bound - 1, &proof, &mut v_challenger).is_err()
+        );
+        let mut v_challenger = Challenger::new(perm);
+        assert!(matches!(
+            verify_low_degree(&config, shift, degree_bound / 2, &proof, &mut v_challenger),
+            Err(FriError::InvalidProofShape)
+        ));
+    }
+}
+
+#[test]
+fn test_low_degree() {
+    for log_degree in 0..8 {
+        let mut rng = ChaCha20Rng::seed_from_u64(log_degree as u64);
+        do_test_low_degree(&mut rng, 1 << log_degree, 1, 1, 0);
+    }
+}
+
+#[test]
+fn test_low_degree_not_power_of_two() {
+    for degree_bound in [3, 5, 20, 33, 100] {
+        let mut rng = ChaCha20Rng::seed_from_u64(degree_bound as u64);
+        do_test_low_degree(&mut rng, degree_bound, 1, 1, 0);
+    }
+}
+
+#[test]
+fn test_low_degree_with_options() {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    do_test_low_degree(&mut rng, 64, 2, 1, 0);
+    do_test_low_degree(&mut rng, 64, 1, 3, 0);
+    do_test_low_degree(&mut rng, 64, 1, 2, 2);
+    do_test_low_degree(&mut rng, 50, 1, 2, 2);
+}
+
+#[test]
+fn test_tampered_evaluation() {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let (perm, config) = get_config(&mut rng, 1, 1, 0);
+    let shift = Challenge::from_base(Val::GENERATOR);
+    let evals = random_evals(&mut rng, 32, 64, shift);
+
+    let mut proof = prove_low_degree(
+        &config,
+        shift,
+        32,
+        evals,
+        &mut Challenger::new(perm.clone()),
+    );
+    proof.fri_proof.query_proofs[0].input_proof[0].opened_values[0][0] += Challenge::ONE;
+    let result = verify_low_degree(&config, shift, 32, &proof, &mut Challenger::new(perm));
+    assert!(matches!(result, Err(FriError::InputError(_))));
+}
+
+#[test]
+#[should_panic]
+fn test_high_degree_panics() {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let (perm, config) = get_config(&mut rng, 1, 1, 0);
+    let shift = Challenge::from_base(Val::GENERATOR);
+    let evals = random_evals(&mut rng, 64, 64, shift);
+    prove_low_degree(&config, shift, 32, evals, &mut Challenger::new(perm));
+}
+
+#[test]
+#[should_panic]
+fn test_high_degree_not_power_of_two_panics() {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let (perm, config) = get_config(&mut rng, 1, 1, 0);
+    let shift = Challenge::from_base(Val::GENERATOR);
+    let evals = random_evals(&mut rng, 21, 64, shift);
+    prove_low_degree(&config, shift, 20, evals, &mut Challenger::new(perm));
+}
+
+#[test]
+#[should_panic]
+fn test_mismatched_degree_bound_panics() {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let (perm, config) = get_config(&mut rng, 1, 1, 0);
+    let shift = Challenge::from_base(Val::GENERATOR);
+    let evals = random_evals(&mut rng, 16, 32, shift);
+    prove_low_degree(&config, shift, 32, evals, &mut Challenger::new(perm));
+}
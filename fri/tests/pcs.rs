@@ -168,7 +168,11 @@ mod babybear_fri_pcs {
     type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
     type MyPcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
 
-    fn get_pcs(log_blowup: usize) -> (MyPcs, Challenger) {
+    fn get_pcs(
+        log_blowup: usize,
+        max_log_arity: usize,
+        log_final_poly_len: usize,
+    ) -> (MyPcs, Challenger) {
         let perm = Perm::new_from_rng_128(&mut seeded_rng());
         let hash = MyHash::new(perm.clone());
         let compress = MyCompress::new(perm.clone());
@@ -176,22 +180,25 @@ mod babybear_fri_pcs {
         let val_mmcs = ValMmcs::new(hash, compress);
         let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
 
-        let fri_config = FriConfig {
-            log_blowup,
-            num_queries: 10,
-            proof_of_work_bits: 8,
-            mmcs: challenge_mmcs,
-        };
+        let fri_config = FriConfig::new(log_blowup, 10, 8, challenge_mmcs)
+            .with_max_log_arity(max_log_arity)
+            .with_log_final_poly_len(log_final_poly_len);
 
         let pcs = MyPcs::new(Dft::default(), val_mmcs, fri_config);
         (pcs, Challenger::new(perm.clone()))
     }
 
     mod blowup_1 {
-        make_tests_for_pcs!(super::get_pcs(1));
+        make_tests_for_pcs!(super::get_pcs(1, 1, 0));
     }
     mod blowup_2 {
-        make_tests_for_pcs!(super::get_pcs(2));
+        make_tests_for_pcs!(super::get_pcs(2, 1, 0));
+    }
+    mod arity_8 {
+        make_tests_for_pcs!(super::get_pcs(1, 3, 0));
+    }
+    mod final_poly_8 {
+        make_tests_for_pcs!(super::get_pcs(1, 1, 3));
     }
 }
 
@@ -222,18 +229,14 @@ mod m31_fri_pcs {
 
     type Pcs = CirclePcs<Val, ValMmcs, ChallengeMmcs>;
 
-    fn get_pcs(log_blowup: usize) -> (Pcs, Challenger) {
+    fn get_pcs(log_blowup: usize, max_log_arity: usize) -> (Pcs, Challenger) {
         let byte_hash = ByteHash {};
         let field_hash = FieldHash::new(byte_hash);
         let compress = MyCompress::new(byte_hash);
         let val_mmcs = ValMmcs::new(field_hash, compress);
         let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
-        let fri_config = FriConfig {
-            log_blowup,
-            num_queries: 10,
-            proof_of_work_bits: 8,
-            mmcs: challenge_mmcs,
-        };
+        let fri_config =
+            FriConfig::new(log_blowup, 10, 8, challenge_mmcs).with_max_log_arity(max_log_arity);
         let pcs = Pcs {
             mmcs: val_mmcs,
             fri_config,
@@ -243,9 +246,12 @@ mod m31_fri_pcs {
     }
 
     mod blowup_1 {
-        make_tests_for_pcs!(super::get_pcs(1));
+        make_tests_for_pcs!(super::get_pcs(1, 1));
     }
     mod blowup_2 {
-        make_tests_for_pcs!(super::get_pcs(2));
+        make_tests_for_pcs!(super::get_pcs(2, 1));
+    }
+    mod arity_8 {
+        make_tests_for_pcs!(super::get_pcs(1, 3));
     }
 }
@@ -15,13 +15,13 @@ use p3_field::{
 use p3_util::{assume, branch_hint};
 use rand::distributions::{Distribution, Standard};
 use rand::Rng;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
 /// The Goldilocks prime
 const P: u64 = 0xFFFF_FFFF_0000_0001;
 
 /// The prime field known as Goldilocks, defined as `F_p` where `p = 2^64 - 2^32 + 1`.
-#[derive(Copy, Clone, Default, Serialize, Deserialize)]
+#[derive(Copy, Clone, Default)]
 #[repr(transparent)] // Packed field implementations rely on this!
 pub struct Goldilocks {
     /// Not necessarily canonical.
@@ -45,6 +45,38 @@ impl PartialEq for Goldilocks {
 
 impl Eq for Goldilocks {}
 
+/// The serialized form of a `Goldilocks`: a struct holding its canonical value.
+///
+/// This has the shape of the derived serialization of `Goldilocks`, but the derived one wrote the
+/// internal value, which need not be canonical. Data holding a non-canonical value, which only the
+/// derived serialization could produce, no longer deserializes.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Goldilocks")]
+struct SerializedGoldilocks {
+    value: u64,
+}
+
+impl Serialize for Goldilocks {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializedGoldilocks {
+            value: self.as_canonical_u64(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Goldilocks {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let val = SerializedGoldilocks::deserialize(d)?.value;
+        // Only canonical values are accepted, so that each element has a single encoding.
+        if val < P {
+            Ok(Self::new(val))
+        } else {
+            Err(serde::de::Error::custom("Invalid field element"))
+        }
+    }
+}
+
 impl Packable for Goldilocks {}
 
 impl Hash for Goldilocks {
@@ -58,12 +58,7 @@ fn main() -> Result<(), impl Debug> {
     type Dft = RecursiveDft<Val>;
     let dft = Dft::new(trace.height());
 
-    let fri_config = FriConfig {
-        log_blowup: 1,
-        num_queries: 100,
-        proof_of_work_bits: 16,
-        mmcs: challenge_mmcs,
-    };
+    let fri_config = FriConfig::new(1, 100, 16, challenge_mmcs);
     type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
     let pcs = Pcs::new(dft, val_mmcs, fri_config);
 
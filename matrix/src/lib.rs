@@ -13,9 +13,9 @@ use p3_field::{
     dot_product, ExtensionField, Field, FieldAlgebra, FieldExtensionAlgebra, PackedValue,
 };
 use p3_maybe_rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use strided::{VerticallyStridedMatrixView, VerticallyStridedRowIndexMap};
 use tracing::instrument;
-use serde::{Deserialize, Serialize};
 
 use crate::dense::RowMajorMatrix;
 
@@ -210,6 +210,31 @@ pub trait Matrix<T: Send + Sync>: Send + Sync {
             .collect_vec()
     }
 
+    /// Pack together a collection of rows at each of the given offsets from the matrix.
+    ///
+    /// Returns a vector corresponding to `offsets.len()` packed rows, generalizing
+    /// `vertically_packed_row_pair`. The i'th element of the k'th row contains the packing of the
+    /// i'th element of the rows r + offsets[k] * step through r + offsets[k] * step + P::WIDTH - 1.
+    /// If at some point we exceed the height of the matrix, wrap around and include initial rows.
+    #[inline]
+    fn vertically_packed_row_window<P>(&self, r: usize, offsets: &[usize], step: usize) -> Vec<P>
+    where
+        T: Copy,
+        P: PackedValue<Value = T>,
+    {
+        offsets
+            .iter()
+            .flat_map(|&offset| {
+                let rows = (0..P::WIDTH)
+                    .map(|c| self.row_slice((r + offset * step + c) % self.height()))
+                    .collect_vec();
+                (0..self.width())
+                    .map(|c| P::from_fn(|i| rows[i][c]))
+                    .collect_vec()
+            })
+            .collect_vec()
+    }
+
     fn vertically_strided(self, stride: usize, offset: usize) -> VerticallyStridedMatrixView<Self>
     where
         Self: Sized,
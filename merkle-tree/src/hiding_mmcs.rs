@@ -2,7 +2,7 @@ use alloc::vec::Vec;
 use core::cell::RefCell;
 
 use itertools::Itertools;
-use p3_commit::Mmcs;
+use p3_commit::{Mmcs, MultiMmcs};
 use p3_field::PackedValue;
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::stack::HorizontalPair;
@@ -101,13 +101,7 @@ where
         (Vec<Vec<P::Value>>, Vec<[PW::Value; DIGEST_ELEMS]>),
     ) {
         let (salted_openings, siblings) = self.inner.open_batch(index, prover_data);
-        let (openings, salts): (Vec<_>, Vec<_>) = salted_openings
-            .into_iter()
-            .map(|row| {
-                let (a, b) = row.split_at(row.len() - SALT_ELEMS);
-                (a.to_vec(), b.to_vec())
-            })
-            .unzip();
+        let (openings, salts) = unsalt::<_, SALT_ELEMS>(salted_openings);
         (openings, (salts, siblings))
     }
 
@@ -127,16 +121,97 @@ where
         proof: &Self::Proof,
     ) -> Result<(), Self::Error> {
         let (salts, siblings) = proof;
+        let opened_salted_values = salt::<_, SALT_ELEMS>(opened_values, salts)?;
+        self.inner
+            .verify_batch(commit, dimensions, index, &opened_salted_values, siblings)
+    }
+}
+
+impl<P, PW, H, C, R, const DIGEST_ELEMS: usize, const SALT_ELEMS: usize> MultiMmcs<P::Value>
+    for MerkleTreeHidingMmcs<P, PW, H, C, R, DIGEST_ELEMS, SALT_ELEMS>
+where
+    P: PackedValue,
+    P::Value: Serialize + DeserializeOwned,
+    PW: PackedValue,
+    H: CryptographicHasher<P::Value, [PW::Value; DIGEST_ELEMS]>,
+    H: CryptographicHasher<P, [PW; DIGEST_ELEMS]>,
+    H: Sync,
+    C: PseudoCompressionFunction<[PW::Value; DIGEST_ELEMS], 2>,
+    C: PseudoCompressionFunction<[PW; DIGEST_ELEMS], 2>,
+    C: Sync,
+    R: Rng + Clone,
+    PW::Value: Eq,
+    [PW::Value; DIGEST_ELEMS]: Serialize + for<'de> Deserialize<'de>,
+    Standard: Distribution<P::Value>,
+{
+    /// The first item is the salts of each opening; the second is the usual Merkle multi-proof.
+    type MultiProof = (Vec<Vec<Vec<P::Value>>>, Vec<[PW::Value; DIGEST_ELEMS]>);
 
+    fn open_multi_batch<M: Matrix<P::Value>>(
+        &self,
+        indices: &[usize],
+        prover_data: &Self::ProverData<M>,
+    ) -> (Vec<Vec<Vec<P::Value>>>, Self::MultiProof) {
+        let (salted_openings, siblings) = self.inner.open_multi_batch(indices, prover_data);
+        let (openings, salts) = salted_openings
+            .into_iter()
+            .map(unsalt::<_, SALT_ELEMS>)
+            .unzip();
+        (openings, (salts, siblings))
+    }
+
+    fn verify_multi_batch(
+        &self,
+        commit: &Self::Commitment,
+        dimensions: &[Dimensions],
+        indices: &[usize],
+        opened_values: &[Vec<Vec<P::Value>>],
+        proof: &Self::MultiProof,
+    ) -> Result<(), Self::Error> {
+        let (salts, siblings) = proof;
+        if salts.len() != opened_values.len() {
+            return Err(MerkleTreeError::WrongBatchSize);
+        }
         let opened_salted_values = opened_values
             .iter()
-            .zip(salts.iter())
-            .map(|(opened, salt)| opened.iter().chain(salt.iter()).copied().collect_vec())
-            .collect_vec();
-
+            .zip(salts)
+            .map(|(opened, salts)| salt::<_, SALT_ELEMS>(opened, salts))
+            .collect::<Result<Vec<_>, _>>()?;
         self.inner
-            .verify_batch(commit, dimensions, index, &opened_salted_values, siblings)
+            .verify_multi_batch(commit, dimensions, indices, &opened_salted_values, siblings)
+    }
+}
+
+/// Split the salts off the end of each opened row.
+fn unsalt<T: Clone, const SALT_ELEMS: usize>(
+    salted_openings: Vec<Vec<T>>,
+) -> (Vec<Vec<T>>, Vec<Vec<T>>) {
+    salted_openings
+        .into_iter()
+        .map(|row| {
+            let (a, b) = row.split_at(row.len() - SALT_ELEMS);
+            (a.to_vec(), b.to_vec())
+        })
+        .unzip()
+}
+
+/// Append its salt to each opened row, checking that there is a salt of the right length for each.
+fn salt<T: Copy, const SALT_ELEMS: usize>(
+    opened_values: &[Vec<T>],
+    salts: &[Vec<T>],
+) -> Result<Vec<Vec<T>>, MerkleTreeError> {
+    if salts.len() != opened_values.len() {
+        return Err(MerkleTreeError::WrongBatchSize);
+    }
+    if salts.iter().any(|salt| salt.len() != SALT_ELEMS) {
+        return Err(MerkleTreeError::WrongWidth);
     }
+
+    Ok(opened_values
+        .iter()
+        .zip(salts.iter())
+        .map(|(opened, salt)| opened.iter().chain(salt.iter()).copied().collect_vec())
+        .collect_vec())
 }
 
 #[cfg(test)]
@@ -145,7 +220,7 @@ mod tests {
 
     use itertools::Itertools;
     use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
-    use p3_commit::Mmcs;
+    use p3_commit::{Mmcs, MultiMmcs};
     use p3_field::{Field, FieldAlgebra};
     use p3_matrix::dense::RowMajorMatrix;
     use p3_matrix::Matrix;
@@ -208,4 +283,81 @@ mod tests {
         let (opened_values, proof) = mmcs.open_batch(17, &prover_data);
         mmcs.verify_batch(&commit, &dims, 17, &opened_values, &proof)
     }
+
+    #[test]
+    fn multi_batch() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm);
+        let mmcs = MyMmcs::new(hash, compress, thread_rng());
+
+        let mats = [32, 8]
+            .into_iter()
+            .map(|height| RowMajorMatrix::<F>::rand(&mut thread_rng(), height, 3))
+            .collect_vec();
+        let dims = mats.iter().map(|m| m.dimensions()).collect_vec();
+        let (commit, prover_data) = mmcs.commit(mats);
+
+        let indices = [9, 30, 8, 9];
+        let (opened_values, (salts, siblings)) = mmcs.open_multi_batch(&indices, &prover_data);
+        assert!(opened_values.iter().flatten().all(|row| row.len() == 3));
+        mmcs.verify_multi_batch(
+            &commit,
+            &dims,
+            &indices,
+            &opened_values,
+            &(salts.clone(), siblings.clone()),
+        )
+        .expect("expected verification to succeed");
+
+        let mut short_salt = salts;
+        short_salt[2][0].pop();
+        assert!(matches!(
+            mmcs.verify_multi_batch(
+                &commit,
+                &dims,
+                &indices,
+                &opened_values,
+                &(short_salt, siblings)
+            ),
+            Err(MerkleTreeError::WrongWidth)
+        ));
+    }
+
+    #[test]
+    fn malformed_salts() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm);
+        let mmcs = MyMmcs::new(hash, compress, thread_rng());
+
+        let mats = (0..3)
+            .map(|_| RowMajorMatrix::<F>::rand(&mut thread_rng(), 16, 2))
+            .collect_vec();
+        let dims = mats.iter().map(|m| m.dimensions()).collect_vec();
+        let (commit, prover_data) = mmcs.commit(mats);
+        let (opened_values, (salts, siblings)) = mmcs.open_batch(5, &prover_data);
+
+        let mut missing_salt = salts.clone();
+        missing_salt.pop();
+        assert!(matches!(
+            mmcs.verify_batch(
+                &commit,
+                &dims,
+                5,
+                &opened_values,
+                &(missing_salt, siblings.clone())
+            ),
+            Err(MerkleTreeError::WrongBatchSize)
+        ));
+
+        let mut short_salt = salts;
+        short_salt[1].pop();
+        assert!(matches!(
+            mmcs.verify_batch(&commit, &dims, 5, &opened_values, &(short_salt, siblings)),
+            Err(MerkleTreeError::WrongWidth)
+        ));
+    }
 }
@@ -3,7 +3,7 @@ use core::cmp::Reverse;
 use core::marker::PhantomData;
 
 use itertools::Itertools;
-use p3_commit::Mmcs;
+use p3_commit::{Mmcs, MultiMmcs};
 use p3_field::PackedValue;
 use p3_matrix::{Dimensions, Matrix};
 use p3_symmetric::{CryptographicHasher, Hash, PseudoCompressionFunction};
@@ -11,7 +11,7 @@ use p3_util::log2_ceil_usize;
 use serde::{Deserialize, Serialize};
 
 use crate::MerkleTree;
-use crate::MerkleTreeError::{RootMismatch, WrongBatchSize, WrongHeight};
+use crate::MerkleTreeError::{ConflictingOpenings, RootMismatch, WrongBatchSize, WrongHeight};
 
 /// A vector commitment scheme backed by a `MerkleTree`.
 ///
@@ -36,6 +36,8 @@ pub enum MerkleTreeError {
         num_siblings: usize,
     },
     RootMismatch,
+    /// Two openings of a multi-opening share a row of some matrix, but disagree on its values.
+    ConflictingOpenings,
 }
 
 impl<P, PW, H, C, const DIGEST_ELEMS: usize> MerkleTreeMmcs<P, PW, H, C, DIGEST_ELEMS> {
@@ -81,19 +83,8 @@ where
         index: usize,
         prover_data: &MerkleTree<P::Value, PW::Value, M, DIGEST_ELEMS>,
     ) -> (Vec<Vec<P::Value>>, Vec<[PW::Value; DIGEST_ELEMS]>) {
-        let max_height = self.get_max_height(prover_data);
-        let log_max_height = log2_ceil_usize(max_height);
-
-        let openings = prover_data
-            .leaves
-            .iter()
-            .map(|matrix| {
-                let log2_height = log2_ceil_usize(matrix.height());
-                let bits_reduced = log_max_height - log2_height;
-                let reduced_index = index >> bits_reduced;
-                matrix.row(reduced_index).collect()
-            })
-            .collect_vec();
+        let log_max_height = log2_ceil_usize(self.get_max_height(prover_data));
+        let openings = open_rows(prover_data, log_max_height, index);
 
         let proof: Vec<_> = (0..log_max_height)
             .map(|i| prover_data.digest_layers[i][(index >> i) ^ 1])
@@ -118,7 +109,7 @@ where
         proof: &Self::Proof,
     ) -> Result<(), Self::Error> {
         // Check that the openings have the correct shape.
-        if dimensions.len() != opened_values.len() {
+        if dimensions.is_empty() || dimensions.len() != opened_values.len() {
             return Err(WrongBatchSize);
         }
 
@@ -194,13 +185,187 @@ where
     }
 }
 
+impl<P, PW, H, C, const DIGEST_ELEMS: usize> MultiMmcs<P::Value>
+    for MerkleTreeMmcs<P, PW, H, C, DIGEST_ELEMS>
+where
+    P: PackedValue,
+    PW: PackedValue,
+    H: CryptographicHasher<P::Value, [PW::Value; DIGEST_ELEMS]>,
+    H: CryptographicHasher<P, [PW; DIGEST_ELEMS]>,
+    H: Sync,
+    C: PseudoCompressionFunction<[PW::Value; DIGEST_ELEMS], 2>,
+    C: PseudoCompressionFunction<[PW; DIGEST_ELEMS], 2>,
+    C: Sync,
+    PW::Value: Eq,
+    [PW::Value; DIGEST_ELEMS]: Serialize + for<'de> Deserialize<'de>,
+{
+    /// The sibling digests which cannot be computed from the openings, layer by layer from the
+    /// leaves, and in order of node index within a layer.
+    type MultiProof = Vec<[PW::Value; DIGEST_ELEMS]>;
+
+    fn open_multi_batch<M: Matrix<P::Value>>(
+        &self,
+        indices: &[usize],
+        prover_data: &MerkleTree<P::Value, PW::Value, M, DIGEST_ELEMS>,
+    ) -> (Vec<Vec<Vec<P::Value>>>, Vec<[PW::Value; DIGEST_ELEMS]>) {
+        let log_max_height = log2_ceil_usize(self.get_max_height(prover_data));
+        let openings = indices
+            .iter()
+            .map(|&index| open_rows(prover_data, log_max_height, index))
+            .collect();
+
+        // In each layer, only the siblings of the nodes on no query's path are needed.
+        let mut nodes = indices.iter().copied().sorted().dedup().collect_vec();
+        let mut proof = Vec::new();
+        for layer in &prover_data.digest_layers[..log_max_height] {
+            proof.extend(
+                nodes
+                    .iter()
+                    .filter(|&&node| nodes.binary_search(&(node ^ 1)).is_err())
+                    .map(|&node| layer[node ^ 1]),
+            );
+            nodes = nodes.iter().map(|node| node >> 1).dedup().collect();
+        }
+
+        (openings, proof)
+    }
+
+    fn verify_multi_batch(
+        &self,
+        commit: &Self::Commitment,
+        dimensions: &[Dimensions],
+        indices: &[usize],
+        opened_values: &[Vec<Vec<P::Value>>],
+        proof: &Self::MultiProof,
+    ) -> Result<(), Self::Error> {
+        // Check that the openings have the correct shape.
+        if dimensions.is_empty()
+            || indices.is_empty()
+            || indices.len() != opened_values.len()
+            || opened_values
+                .iter()
+                .any(|opened| opened.len() != dimensions.len())
+        {
+            return Err(WrongBatchSize);
+        }
+
+        let max_height = dimensions.iter().map(|dim| dim.height).max().unwrap();
+        let log_max_height = log2_ceil_usize(max_height);
+        let wrong_height = || WrongHeight {
+            max_height,
+            num_siblings: proof.len(),
+        };
+
+        // The matrices, grouped by the layer of the tree at which their rows are hashed in.
+        let mut groups = dimensions
+            .iter()
+            .enumerate()
+            .sorted_by_key(|(_, dims)| Reverse(dims.height))
+            .chunk_by(|(_, dims)| log2_ceil_usize(dims.height))
+            .into_iter()
+            .map(|(log_height, group)| (log_height, group.map(|(i, _)| i).collect_vec()))
+            .collect_vec()
+            .into_iter()
+            .peekable();
+
+        // The queries in order of index, so that those sharing a node are adjacent in every layer.
+        let queries = (0..indices.len())
+            .sorted_by_key(|&q| indices[q])
+            .collect_vec();
+
+        // The digest of each node on some query's path, in order of node index, starting with the
+        // leaves.
+        let mut nodes: Vec<(usize, [PW::Value; DIGEST_ELEMS])> = Vec::new();
+        let mut siblings = proof.iter();
+        for log_height in (0..=log_max_height).rev() {
+            if log_height < log_max_height {
+                let mut next_nodes = Vec::with_capacity(nodes.len());
+                let mut layer = nodes.iter().peekable();
+                while let Some(&(node, digest)) = layer.next() {
+                    let pair = if node & 1 == 0 {
+                        match layer.next_if(|(next, _)| *next == node ^ 1) {
+                            Some(&(_, right)) => [digest, right],
+                            None => [digest, *siblings.next().ok_or_else(wrong_height)?],
+                        }
+                    } else {
+                        [*siblings.next().ok_or_else(wrong_height)?, digest]
+                    };
+                    next_nodes.push((node >> 1, self.compress.compress(pair)));
+                }
+                nodes = next_nodes;
+            }
+
+            if let Some((_, matrices)) = groups.next_if(|(lh, _)| *lh == log_height) {
+                let bits_reduced = log_max_height - log_height;
+                let mut digests = Vec::with_capacity(queries.len());
+                for (node, mut group) in &queries.iter().chunk_by(|&&q| indices[q] >> bits_reduced)
+                {
+                    let first = group.next().unwrap();
+                    if group.any(|&q| {
+                        matrices
+                            .iter()
+                            .any(|&i| opened_values[q][i] != opened_values[*first][i])
+                    }) {
+                        return Err(ConflictingOpenings);
+                    }
+                    let digest = self.hash.hash_iter_slices(
+                        matrices
+                            .iter()
+                            .map(|&i| opened_values[*first][i].as_slice()),
+                    );
+                    digests.push((node, digest));
+                }
+
+                if log_height == log_max_height {
+                    nodes = digests;
+                } else {
+                    for ((_, root), (_, digest)) in nodes.iter_mut().zip(digests) {
+                        *root = self.compress.compress([*root, digest]);
+                    }
+                }
+            }
+        }
+
+        if siblings.next().is_some() {
+            return Err(wrong_height());
+        }
+        match nodes.as_slice() {
+            [(0, root)] if commit == root => Ok(()),
+            _ => Err(RootMismatch),
+        }
+    }
+}
+
+/// The row of each matrix in `prover_data` at `index`, reduced for the smaller matrices as
+/// described in `Mmcs::open_batch`.
+fn open_rows<F, W, M, const DIGEST_ELEMS: usize>(
+    prover_data: &MerkleTree<F, W, M, DIGEST_ELEMS>,
+    log_max_height: usize,
+    index: usize,
+) -> Vec<Vec<F>>
+where
+    F: Clone + Send + Sync,
+    M: Matrix<F>,
+{
+    prover_data
+        .leaves
+        .iter()
+        .map(|matrix| {
+            let log2_height = log2_ceil_usize(matrix.height());
+            let bits_reduced = log_max_height - log2_height;
+            let reduced_index = index >> bits_reduced;
+            matrix.row(reduced_index).collect()
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use alloc::vec;
 
     use itertools::Itertools;
     use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
-    use p3_commit::Mmcs;
+    use p3_commit::{Mmcs, MultiMmcs};
     use p3_field::{Field, FieldAlgebra};
     use p3_matrix::dense::RowMajorMatrix;
     use p3_matrix::{Dimensions, Matrix};
@@ -209,7 +374,7 @@ mod tests {
     };
     use rand::thread_rng;
 
-    use super::MerkleTreeMmcs;
+    use super::{MerkleTreeError, MerkleTreeMmcs};
 
     type F = BabyBear;
 
@@ -479,6 +644,35 @@ mod tests {
         .expect_err("expected verification to fail");
     }
 
+    #[test]
+    fn verify_malformed_batch_fails() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm);
+        let mmcs = MyMmcs::new(hash, compress);
+
+        let mat = RowMajorMatrix::<F>::rand(&mut thread_rng(), 8, 2);
+        let dims = vec![mat.dimensions()];
+        let (commit, prover_data) = mmcs.commit(vec![mat]);
+        let (opened_values, proof) = mmcs.open_batch(3, &prover_data);
+
+        // No matrices at all.
+        assert!(matches!(
+            mmcs.verify_batch(&commit, &[], 3, &[], &proof),
+            Err(MerkleTreeError::WrongBatchSize)
+        ));
+
+        // A sibling too few.
+        assert!(matches!(
+            mmcs.verify_batch(&commit, &dims, 3, &opened_values, &proof[1..].to_vec()),
+            Err(MerkleTreeError::WrongHeight {
+                max_height: 8,
+                num_siblings: 2
+            })
+        ));
+    }
+
     #[test]
     fn size_gaps() {
         let mut rng = thread_rng();
@@ -539,6 +733,85 @@ mod tests {
         .expect("expected verification to succeed");
     }
 
+    #[test]
+    fn multi_batch() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(&mut rng);
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm);
+        let mmcs = MyMmcs::new(hash, compress);
+
+        // Matrices of several heights, including one which is not a power of two.
+        let mats = [64, 64, 20, 4, 1]
+            .into_iter()
+            .enumerate()
+            .map(|(i, height)| RowMajorMatrix::<F>::rand(&mut rng, height, i + 1))
+            .collect_vec();
+        let dims = mats.iter().map(|m| m.dimensions()).collect_vec();
+        let (commit, prover_data) = mmcs.commit(mats);
+
+        // Unsorted, with a repeat and with indices sharing rows of the smaller matrices.
+        let indices = [37, 5, 39, 36, 5, 0, 12];
+        let (opened_values, proof) = mmcs.open_multi_batch(&indices, &prover_data);
+        for (&index, opened) in indices.iter().zip(&opened_values) {
+            assert_eq!(opened, &mmcs.open_batch(index, &prover_data).0);
+        }
+        // The paths share the siblings near the root.
+        assert!(proof.len() < indices.len() * 6);
+        mmcs.verify_multi_batch(&commit, &dims, &indices, &opened_values, &proof)
+            .expect("expected verification to succeed");
+
+        // A single index needs the same siblings as `open_batch`.
+        let (single_values, single_proof) = mmcs.open_multi_batch(&[17], &prover_data);
+        assert_eq!(single_proof, mmcs.open_batch(17, &prover_data).1);
+        mmcs.verify_multi_batch(&commit, &dims, &[17], &single_values, &single_proof)
+            .expect("expected verification to succeed");
+
+        // Openings at another index.
+        let mut other_indices = indices;
+        other_indices[1] = 6;
+        assert!(matches!(
+            mmcs.verify_multi_batch(&commit, &dims, &other_indices, &opened_values, &proof),
+            Err(MerkleTreeError::RootMismatch)
+        ));
+
+        // A tampered sibling.
+        let mut tampered = proof.clone();
+        tampered[3][0] += F::ONE;
+        assert!(matches!(
+            mmcs.verify_multi_batch(&commit, &dims, &indices, &opened_values, &tampered),
+            Err(MerkleTreeError::RootMismatch)
+        ));
+
+        // A sibling too few or too many.
+        for siblings in [&proof[1..], &[proof.as_slice(), &proof[..1]].concat()] {
+            assert!(matches!(
+                mmcs.verify_multi_batch(
+                    &commit,
+                    &dims,
+                    &indices,
+                    &opened_values,
+                    &siblings.to_vec()
+                ),
+                Err(MerkleTreeError::WrongHeight { max_height: 64, .. })
+            ));
+        }
+
+        // Indices 37 and 36 share a row of the matrix of height 20, so must agree on it.
+        let mut conflicting = opened_values.clone();
+        conflicting[3][2][0] += F::ONE;
+        assert!(matches!(
+            mmcs.verify_multi_batch(&commit, &dims, &indices, &conflicting, &proof),
+            Err(MerkleTreeError::ConflictingOpenings)
+        ));
+
+        // An opening missing.
+        assert!(matches!(
+            mmcs.verify_multi_batch(&commit, &dims, &indices, &opened_values[1..], &proof),
+            Err(MerkleTreeError::WrongBatchSize)
+        ));
+    }
+
     #[test]
     fn different_widths() {
         let mut rng = thread_rng();
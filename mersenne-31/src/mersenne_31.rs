@@ -14,13 +14,13 @@ use p3_field::{
 };
 use rand::distributions::{Distribution, Standard};
 use rand::Rng;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
 /// The Mersenne31 prime
 const P: u32 = (1 << 31) - 1;
 
 /// The prime field `F_p` where `p = 2^31 - 1`.
-#[derive(Copy, Clone, Default, Serialize, Deserialize)]
+#[derive(Copy, Clone, Default)]
 #[repr(transparent)] // Packed field implementations rely on this!
 pub struct Mersenne31 {
     /// Not necessarily canonical, but must fit in 31 bits.
@@ -44,6 +44,38 @@ impl PartialEq for Mersenne31 {
 
 impl Eq for Mersenne31 {}
 
+/// The serialized form of a `Mersenne31`: a struct holding its canonical value.
+///
+/// This has the shape of the derived serialization of `Mersenne31`, but the derived one wrote the
+/// internal value, which need not be canonical. Data holding a non-canonical value, which only the
+/// derived serialization could produce, no longer deserializes.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Mersenne31")]
+struct SerializedMersenne31 {
+    value: u32,
+}
+
+impl Serialize for Mersenne31 {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializedMersenne31 {
+            value: self.as_canonical_u32(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Mersenne31 {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let val = SerializedMersenne31::deserialize(d)?.value;
+        // Only canonical values are accepted, so that each element has a single encoding.
+        if val < P {
+            Ok(Self::new(val))
+        } else {
+            Err(serde::de::Error::custom("Invalid field element"))
+        }
+    }
+}
+
 impl Packable for Mersenne31 {}
 
 impl Hash for Mersenne31 {
@@ -153,7 +153,12 @@ impl<FP: FieldParameters> Serialize for MontyField31<FP> {
 impl<'de, FP: FieldParameters> Deserialize<'de> for MontyField31<FP> {
     fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
         let val = u32::deserialize(d)?;
-        Ok(MontyField31::from_canonical_u32(val))
+        // A value outside the field would break the Montgomery form, so reject it.
+        if val < FP::PRIME {
+            Ok(MontyField31::from_canonical_u32(val))
+        } else {
+            Err(serde::de::Error::custom("Invalid field element"))
+        }
     }
 }
 
@@ -0,0 +1,25 @@
+use p3_air::Air;
+use p3_uni_stark::{
+    ProverConstraintFolder, StarkGenericConfig, SymbolicAirBuilder, Val, VerifierConstraintFolder,
+};
+
+/// An AIR which can be used as one table of a multi-table STARK.
+///
+/// This is implemented for every AIR which works with the builders used by the prover and verifier,
+/// and is object safe, so tables of different types can be passed together as
+/// `&[&dyn MultiStarkAir<SC>]`.
+pub trait MultiStarkAir<SC: StarkGenericConfig>:
+    Air<SymbolicAirBuilder<Val<SC>>>
+    + for<'a> Air<ProverConstraintFolder<'a, SC>>
+    + for<'a> Air<VerifierConstraintFolder<'a, SC>>
+{
+}
+
+impl<SC, A> MultiStarkAir<SC> for A
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>>
+        + for<'a> Air<ProverConstraintFolder<'a, SC>>
+        + for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+{
+}
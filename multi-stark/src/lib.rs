@@ -0,0 +1,19 @@
+//! A STARK framework for proving several AIRs, each over its own trace, in a single proof.
+//!
+//! This is a front end to `p3_uni_stark::prove_tables`: all traces are committed to in one PCS
+//! round, and all quotient polynomials in another, so the tables share a single set of challenges
+//! and a single opening argument.
+
+#![no_std]
+
+extern crate alloc;
+
+mod air;
+mod proof;
+mod prover;
+mod verifier;
+
+pub use air::*;
+pub use proof::*;
+pub use prover::*;
+pub use verifier::*;
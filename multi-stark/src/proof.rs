@@ -0,0 +1,34 @@
+use alloc::vec::Vec;
+
+use p3_uni_stark::{Com, Commitments, OpenedValues, PcsProof, PowWitness, StarkGenericConfig};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct MultiProof<SC: StarkGenericConfig> {
+    /// Commitments shared by all tables: one to every trace, one to the LogUp trace of every table
+    /// with interactions, and one to every quotient chunk.
+    pub(crate) commitments: Commitments<Com<SC>>,
+    /// The proof of work witness ground before `zeta` was sampled, if the config asks for one.
+    pub(crate) zeta_pow_witness: Option<PowWitness<SC>>,
+    /// The opened values of each table, in the order the AIRs were given.
+    pub(crate) opened_values: Vec<OpenedValues<SC::Challenge>>,
+    pub(crate) opening_proof: PcsProof<SC>,
+    /// The log2 of each table's trace height.
+    pub(crate) degree_bits: Vec<usize>,
+    /// The final value of each table's LogUp running sum, for tables with interactions. These must
+    /// add up to zero for the sends and receives of all tables to balance.
+    pub(crate) cumulative_sums: Vec<Option<SC::Challenge>>,
+}
+
+impl<SC: StarkGenericConfig> MultiProof<SC> {
+    /// The opened values of each table.
+    pub fn opened_values(&self) -> &[OpenedValues<SC::Challenge>] {
+        &self.opened_values
+    }
+
+    /// The log2 of each table's trace height.
+    pub fn degree_bits(&self) -> &[usize] {
+        &self.degree_bits
+    }
+}
@@ -0,0 +1,126 @@
+use alloc::vec::Vec;
+
+use itertools::{izip, Itertools};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_uni_stark::{
+    prove_tables, setup_keys, unwrap_proof, Instance, InteractionScope, ProverTable,
+    StarkGenericConfig, StarkProvingKey, TablesProof, Val,
+};
+use tracing::instrument;
+
+use crate::{MultiProof, MultiStarkAir};
+
+/// Prove that each of `traces` satisfies the corresponding AIR in `airs`, in a single proof.
+///
+/// The traces may have different heights. `public_values[i]` are the public values of table `i`.
+/// Interactions are checked across all tables, so a tuple sent by one table may be received by
+/// another.
+///
+/// The AIRs' keys are computed on the fly; use `prove_with_instances` to reuse proving keys
+/// computed once by `setup_keys`.
+#[instrument(skip_all)]
+pub fn prove<SC, A>(
+    config: &SC,
+    airs: &[&A],
+    challenger: &mut SC::Challenger,
+    traces: Vec<RowMajorMatrix<Val<SC>>>,
+    public_values: &[Vec<Val<SC>>],
+) -> MultiProof<SC>
+where
+    SC: StarkGenericConfig,
+    A: MultiStarkAir<SC> + ?Sized,
+{
+    assert_eq!(
+        airs.len(),
+        public_values.len(),
+        "expected one set of public values per AIR"
+    );
+    let proving_keys = izip!(airs, public_values)
+        .map(|(&air, pis)| setup_keys(config, air, pis.len()).0)
+        .collect_vec();
+    let instances = public_values
+        .iter()
+        .map(|pis| Instance::new(pis.clone()))
+        .collect_vec();
+    let extension_traces = airs.iter().map(|_| None).collect();
+    prove_with_instances(
+        config,
+        &proving_keys.iter().collect_vec(),
+        airs,
+        challenger,
+        traces,
+        extension_traces,
+        &instances,
+    )
+}
+
+/// Prove that each of `traces` satisfies the corresponding AIR in `airs`, in a single proof, given
+/// the proving key of each AIR from `setup_keys`.
+///
+/// `extension_traces[i]` holds the extension field columns of table `i`, if its AIR has any, and
+/// `instances[i]` its public inputs. Tables given the same proving key share the opening of its
+/// preprocessed trace. If the PCS is hiding, the proof is zero knowledge, apart from the LogUp sum
+/// of each table, which the proof reveals.
+#[instrument(skip_all)]
+#[allow(clippy::too_many_arguments)]
+pub fn prove_with_instances<SC, A>(
+    config: &SC,
+    proving_keys: &[&StarkProvingKey<SC>],
+    airs: &[&A],
+    challenger: &mut SC::Challenger,
+    traces: Vec<RowMajorMatrix<Val<SC>>>,
+    extension_traces: Vec<Option<RowMajorMatrix<SC::Challenge>>>,
+    instances: &[Instance<SC>],
+) -> MultiProof<SC>
+where
+    SC: StarkGenericConfig,
+    A: MultiStarkAir<SC> + ?Sized,
+{
+    assert_eq!(airs.len(), traces.len(), "expected one trace per AIR");
+    assert_eq!(
+        airs.len(),
+        proving_keys.len(),
+        "expected one proving key per AIR"
+    );
+    assert_eq!(
+        airs.len(),
+        extension_traces.len(),
+        "expected one optional extension trace per AIR"
+    );
+    assert_eq!(airs.len(), instances.len(), "expected one instance per AIR");
+
+    let tables = izip!(airs, proving_keys, traces, extension_traces, instances)
+        .map(
+            |(&air, &proving_key, trace, extension_trace, instance)| ProverTable {
+                air,
+                proving_key,
+                trace,
+                extension_trace,
+                instance,
+            },
+        )
+        .collect();
+    let proof = prove_tables(
+        config,
+        challenger,
+        tables,
+        InteractionScope::Global,
+        &mut (),
+    );
+    let TablesProof {
+        commitments,
+        zeta_pow_witness,
+        opened_values,
+        opening_proof,
+        degree_bits,
+        cumulative_sums,
+    } = unwrap_proof(proof);
+    MultiProof {
+        commitments,
+        zeta_pow_witness,
+        opened_values,
+        opening_proof,
+        degree_bits,
+        cumulative_sums,
+    }
+}
@@ -0,0 +1,112 @@
+use alloc::vec::Vec;
+
+use itertools::{izip, Itertools};
+use p3_uni_stark::{
+    setup_keys, verify_tables, Instance, InteractionScope, PcsError, StarkGenericConfig,
+    StarkVerifyingKey, Val, VerificationError, VerifierTable,
+};
+use tracing::instrument;
+
+use crate::{MultiProof, MultiStarkAir};
+
+/// Verify a proof generated by `prove` for the same AIRs, in the same order.
+///
+/// The AIRs' keys are computed on the fly; use `verify_with_instances` to reuse verifying keys
+/// computed once by `setup_keys`.
+#[instrument(skip_all)]
+pub fn verify<SC, A>(
+    config: &SC,
+    airs: &[&A],
+    challenger: &mut SC::Challenger,
+    proof: &MultiProof<SC>,
+    public_values: &[Vec<Val<SC>>],
+) -> Result<(), VerificationError<PcsError<SC>>>
+where
+    SC: StarkGenericConfig,
+    A: MultiStarkAir<SC> + ?Sized,
+{
+    if airs.len() != public_values.len() {
+        return Err(VerificationError::InvalidProofShape);
+    }
+    let verifying_keys = izip!(airs, public_values)
+        .map(|(&air, pis)| setup_keys(config, air, pis.len()).1)
+        .collect_vec();
+    let instances = public_values
+        .iter()
+        .map(|pis| Instance::new(pis.clone()))
+        .collect_vec();
+    verify_with_instances(
+        config,
+        &verifying_keys.iter().collect_vec(),
+        airs,
+        challenger,
+        proof,
+        &instances,
+    )
+}
+
+/// Verify a proof generated by `prove_with_instances` for the same AIRs, in the same order, given
+/// the verifying key of each AIR from `setup_keys` and the public inputs of each table.
+#[instrument(skip_all)]
+pub fn verify_with_instances<SC, A>(
+    config: &SC,
+    verifying_keys: &[&StarkVerifyingKey<SC>],
+    airs: &[&A],
+    challenger: &mut SC::Challenger,
+    proof: &MultiProof<SC>,
+    instances: &[Instance<SC>],
+) -> Result<(), VerificationError<PcsError<SC>>>
+where
+    SC: StarkGenericConfig,
+    A: MultiStarkAir<SC> + ?Sized,
+{
+    let MultiProof {
+        commitments,
+        zeta_pow_witness,
+        opened_values,
+        opening_proof,
+        degree_bits,
+        cumulative_sums,
+    } = proof;
+
+    let num_tables = airs.len();
+    if verifying_keys.len() != num_tables
+        || instances.len() != num_tables
+        || opened_values.len() != num_tables
+        || degree_bits.len() != num_tables
+        || cumulative_sums.len() != num_tables
+    {
+        return Err(VerificationError::InvalidProofShape);
+    }
+
+    let tables = izip!(
+        airs,
+        verifying_keys,
+        instances,
+        degree_bits,
+        opened_values,
+        cumulative_sums
+    )
+    .map(
+        |(&air, &verifying_key, instance, &degree_bits, opened_values, &cumulative_sum)| {
+            VerifierTable {
+                air,
+                verifying_key,
+                instance,
+                degree_bits,
+                opened_values,
+                cumulative_sum,
+            }
+        },
+    )
+    .collect_vec();
+    verify_tables(
+        config,
+        challenger,
+        commitments,
+        *zeta_pow_witness,
+        opening_proof,
+        &tables,
+        InteractionScope::Global,
+    )
+}
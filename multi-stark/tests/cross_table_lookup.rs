@@ -0,0 +1,153 @@
+use p3_air::{Air, AirBuilder, BaseAir, Interaction, VirtualPairCol};
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{Field, FieldAlgebra};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_multi_stark::{prove, verify, MultiStarkAir};
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::StarkConfig;
+use rand::thread_rng;
+
+const RANGE_BUS: usize = 0;
+
+/// A single column of values, each of which is sent once to the range check table.
+struct ValuesAir;
+
+impl<F: Field> BaseAir<F> for ValuesAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn sends(&self) -> Vec<Interaction<F>> {
+        vec![Interaction::new(
+            vec![VirtualPairCol::single_main(0)],
+            VirtualPairCol::constant(F::ONE),
+            RANGE_BUS,
+        )]
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for ValuesAir {
+    fn eval(&self, _builder: &mut AB) {}
+}
+
+/// A counter `0, 1, 2, ...` with a multiplicity column, receiving each counter value from the
+/// range check bus `multiplicity` times.
+struct RangeAir;
+
+impl<F: Field> BaseAir<F> for RangeAir {
+    fn width(&self) -> usize {
+        2
+    }
+
+    fn receives(&self) -> Vec<Interaction<F>> {
+        vec![Interaction::new(
+            vec![VirtualPairCol::single_main(0)],
+            VirtualPairCol::single_main(1),
+            RANGE_BUS,
+        )]
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for RangeAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let next = main.row_slice(1);
+
+        builder.when_first_row().assert_zero(local[0]);
+        builder
+            .when_transition()
+            .assert_eq(local[0] + AB::Expr::ONE, next[0]);
+    }
+}
+
+fn values_trace<F: Field>(values: &[u32]) -> RowMajorMatrix<F> {
+    RowMajorMatrix::new_col(values.iter().map(|&v| F::from_canonical_u32(v)).collect())
+}
+
+fn range_trace<F: Field>(height: usize, lookups: &[&[u32]]) -> RowMajorMatrix<F> {
+    let mut multiplicities = vec![0u32; height];
+    for &value in lookups.iter().copied().flatten() {
+        if let Some(multiplicity) = multiplicities.get_mut(value as usize) {
+            *multiplicity += 1;
+        }
+    }
+    let values = multiplicities
+        .into_iter()
+        .enumerate()
+        .flat_map(|(i, multiplicity)| {
+            [
+                F::from_canonical_usize(i),
+                F::from_canonical_u32(multiplicity),
+            ]
+        })
+        .collect();
+    RowMajorMatrix::new(values, 2)
+}
+
+type Val = BabyBear;
+type Perm = Poseidon2BabyBear<16>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type ValMmcs =
+    MerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel<Val>;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+fn make_config(perm: &Perm) -> MyConfig {
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig::new(2, 28, 8, challenge_mmcs);
+    let pcs = Pcs::new(Dft::default(), val_mmcs, fri_config);
+    MyConfig::new(pcs)
+}
+
+fn do_test(first_values: &[u32], second_values: &[u32]) {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config(&perm);
+
+    let airs: [&dyn MultiStarkAir<MyConfig>; 3] = [&ValuesAir, &RangeAir, &ValuesAir];
+    let traces = vec![
+        values_trace::<Val>(first_values),
+        range_trace::<Val>(1 << 6, &[first_values, second_values]),
+        values_trace::<Val>(second_values),
+    ];
+    let public_values = vec![vec![]; 3];
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &airs, &mut challenger, traces, &public_values);
+
+    let serialized_proof = postcard::to_allocvec(&proof).expect("unable to serialize proof");
+    let proof = postcard::from_bytes(&serialized_proof).expect("unable to deserialize proof");
+
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &airs, &mut challenger, &proof, &public_values).expect("verification failed");
+}
+
+#[test]
+fn test_cross_table_lookup() {
+    let first_values = [3, 1, 4, 1, 5, 9, 2, 6];
+    let second_values = (0..16).map(|i| (i * 7) % 64).collect::<Vec<_>>();
+    do_test(&first_values, &second_values);
+}
+
+#[test]
+#[should_panic(expected = "do not balance")]
+fn test_cross_table_lookup_out_of_range() {
+    let first_values = [3, 1, 4, 1, 5, 9, 2, 64];
+    let second_values = [0; 16];
+    do_test(&first_values, &second_values);
+}
@@ -0,0 +1,272 @@
+use std::borrow::Borrow;
+
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir, PairBuilder};
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{Field, FieldAlgebra};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_multi_stark::{prove, prove_with_instances, verify, verify_with_instances, MultiStarkAir};
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{setup_keys, Instance, StarkConfig, VerificationError};
+use rand::thread_rng;
+
+/// Fibonacci sequence starting from the public values `a, b` and ending in `x`.
+struct FibonacciAir;
+
+impl<F> BaseAir<F> for FibonacciAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for FibonacciAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let pis = builder.public_values();
+        let (a, b, x) = (pis[0], pis[1], pis[2]);
+
+        let local = main.row_slice(0);
+        let next = main.row_slice(1);
+        let local: &[AB::Var] = (*local).borrow();
+        let next: &[AB::Var] = (*next).borrow();
+
+        let mut when_first_row = builder.when_first_row();
+        when_first_row.assert_eq(local[0], a);
+        when_first_row.assert_eq(local[1], b);
+
+        let mut when_transition = builder.when_transition();
+        when_transition.assert_eq(local[1], next[0]);
+        when_transition.assert_eq(local[0] + local[1], next[1]);
+
+        builder.when_last_row().assert_eq(local[1], x);
+    }
+}
+
+fn fibonacci_trace<F: Field>(n: usize) -> RowMajorMatrix<F> {
+    let mut values = Vec::with_capacity(2 * n);
+    let (mut left, mut right) = (F::ZERO, F::ONE);
+    for _ in 0..n {
+        values.extend([left, right]);
+        (left, right) = (right, left + right);
+    }
+    RowMajorMatrix::new(values, 2)
+}
+
+/// A counter `a` with `b = a^3` on each row, giving a degree 3 constraint.
+struct CubeAir;
+
+impl<F> BaseAir<F> for CubeAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for CubeAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let next = main.row_slice(1);
+
+        builder.assert_eq(local[0].into().cube(), local[1]);
+        builder.when_first_row().assert_zero(local[0]);
+        builder
+            .when_transition()
+            .assert_eq(local[0] + AB::Expr::ONE, next[0]);
+    }
+}
+
+fn cube_trace<F: Field>(n: usize) -> RowMajorMatrix<F> {
+    let values = (0..n)
+        .flat_map(|i| {
+            let a = F::from_canonical_usize(i);
+            [a, a.cube()]
+        })
+        .collect();
+    RowMajorMatrix::new(values, 2)
+}
+
+/// A counter `a` which is checked every other row, `a[i + 2] = a[i] + 2`, over a window which skips
+/// the row in between.
+struct StrideAir;
+
+impl<F> BaseAir<F> for StrideAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn row_offsets(&self) -> Vec<usize> {
+        vec![0, 2]
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for StrideAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0)[0];
+        let after_next = main.row_slice(1)[0];
+
+        builder.when_first_row().assert_zero(local);
+        builder
+            .when_transition_window(3)
+            .assert_eq(local + AB::Expr::TWO, after_next);
+    }
+}
+
+fn stride_trace<F: Field>(n: usize) -> RowMajorMatrix<F> {
+    RowMajorMatrix::new_col((0..n).map(F::from_canonical_usize).collect())
+}
+
+/// A preprocessed column of squares, shifted by the public value.
+struct ShiftedSquaresAir;
+
+impl<F: Field> BaseAir<F> for ShiftedSquaresAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
+        Some(RowMajorMatrix::new_col(
+            (0..1 << 4)
+                .map(|i| F::from_canonical_usize(i * i))
+                .collect(),
+        ))
+    }
+}
+
+impl<AB: PairBuilder + AirBuilderWithPublicValues> Air<AB> for ShiftedSquaresAir {
+    fn eval(&self, builder: &mut AB) {
+        let shift = builder.public_values()[0];
+        let x = builder.main().row_slice(0)[0];
+        let square = builder.preprocessed().row_slice(0)[0];
+        builder.assert_eq(x - square, shift);
+    }
+}
+
+fn shifted_squares_trace<F: Field>(shift: usize) -> RowMajorMatrix<F> {
+    RowMajorMatrix::new_col(
+        (0..1 << 4)
+            .map(|i| F::from_canonical_usize(i * i + shift))
+            .collect(),
+    )
+}
+
+type Val = BabyBear;
+type Perm = Poseidon2BabyBear<16>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type ValMmcs =
+    MerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel<Val>;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+fn make_config(perm: &Perm) -> MyConfig {
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig::new(2, 28, 8, challenge_mmcs);
+    let pcs = Pcs::new(Dft::default(), val_mmcs, fri_config);
+    MyConfig::new(pcs)
+}
+
+fn do_test(fib_x: u64) -> Result<(), VerificationError<impl core::fmt::Debug>> {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config(&perm);
+
+    let airs: [&dyn MultiStarkAir<MyConfig>; 4] =
+        [&FibonacciAir, &CubeAir, &FibonacciAir, &StrideAir];
+    let traces = vec![
+        fibonacci_trace::<Val>(1 << 3),
+        cube_trace::<Val>(1 << 6),
+        fibonacci_trace::<Val>(1 << 4),
+        stride_trace::<Val>(1 << 5),
+    ];
+    let public_values = vec![
+        vec![Val::ZERO, Val::ONE, Val::from_canonical_u64(21)],
+        vec![],
+        vec![Val::ZERO, Val::ONE, Val::from_canonical_u64(fib_x)],
+        vec![],
+    ];
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &airs, &mut challenger, traces, &public_values);
+    assert_eq!(proof.degree_bits(), &[3, 6, 4, 5]);
+
+    let serialized_proof = postcard::to_allocvec(&proof).expect("unable to serialize proof");
+    let proof = postcard::from_bytes(&serialized_proof).expect("unable to deserialize proof");
+
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &airs, &mut challenger, &proof, &public_values)
+}
+
+#[test]
+fn test_multi_table() {
+    do_test(987).expect("verification failed");
+}
+
+#[test]
+fn test_multi_table_incorrect_public_value() {
+    let result = do_test(988);
+    assert!(matches!(
+        result,
+        Err(VerificationError::OodEvaluationMismatch)
+    ));
+}
+
+#[test]
+fn test_multi_table_shared_preprocessed_trace() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config(&perm);
+
+    let (squares_pk, squares_vk) = setup_keys(&config, &ShiftedSquaresAir, 1);
+    let (cube_pk, cube_vk) = setup_keys(&config, &CubeAir, 0);
+    let airs: [&dyn MultiStarkAir<MyConfig>; 3] =
+        [&ShiftedSquaresAir, &CubeAir, &ShiftedSquaresAir];
+    let traces = vec![
+        shifted_squares_trace::<Val>(3),
+        cube_trace::<Val>(1 << 5),
+        shifted_squares_trace::<Val>(8),
+    ];
+    let instances = [3, 0, 8].map(|shift| {
+        let public_values = if shift == 0 {
+            vec![]
+        } else {
+            vec![Val::from_canonical_usize(shift)]
+        };
+        Instance::new(public_values)
+    });
+
+    // The two tables given the same key share the opening of its preprocessed trace.
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_with_instances(
+        &config,
+        &[&squares_pk, &cube_pk, &squares_pk],
+        &airs,
+        &mut challenger,
+        traces,
+        vec![None, None, None],
+        &instances,
+    );
+    assert_eq!(proof.degree_bits(), &[4, 5, 4]);
+
+    let mut challenger = Challenger::new(perm);
+    verify_with_instances(
+        &config,
+        &[&squares_vk, &cube_vk, &squares_vk],
+        &airs,
+        &mut challenger,
+        &proof,
+        &instances,
+    )
+    .expect("verification failed");
+}
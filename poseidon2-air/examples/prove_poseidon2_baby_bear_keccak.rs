@@ -103,12 +103,8 @@ fn main() -> Result<(), impl Debug> {
 
     let dft = Dft::default();
 
-    let fri_config = FriConfig {
-        log_blowup: 1, // TODO: Should this be 3? Why is it working?
-        num_queries: 100,
-        proof_of_work_bits: 16,
-        mmcs: challenge_mmcs,
-    };
+    // TODO: Should the log blowup be 3? Why is it working?
+    let fri_config = FriConfig::new(1, 100, 16, challenge_mmcs);
     type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
     let pcs = Pcs::new(dft, val_mmcs, fri_config);
 
@@ -103,12 +103,7 @@ fn main() -> Result<(), impl Debug> {
 
     let dft = Dft::default();
 
-    let fri_config = FriConfig {
-        log_blowup: 1,
-        num_queries: 100,
-        proof_of_work_bits: 16,
-        mmcs: challenge_mmcs,
-    };
+    let fri_config = FriConfig::new(2, 100, 16, challenge_mmcs);
     type Pcs = HidingFriPcs<Val, Dft, ValMmcs, ChallengeMmcs, StdRng>;
     let pcs = Pcs::new(dft, val_mmcs, fri_config, 4, StdRng::from_entropy());
 
@@ -0,0 +1,167 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::panic::Location;
+
+use p3_air::{Air, PairCol};
+use p3_field::Field;
+use tracing::instrument;
+
+use crate::symbolic_builder::{log_quotient_degree, symbolic_builder};
+use crate::{CompiledAir, Instruction, SymbolicAirBuilder, SymbolicExpression};
+
+/// Statistics about the constraints of an AIR, to help tune it before proving.
+///
+/// Degrees are multiples of the trace length `n`, as returned by
+/// `SymbolicExpression::constraint_degree`, so they include the degree of any transition selector
+/// which the quotient needs room for. The LogUp constraints of the AIR's interactions are not
+/// included.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AirStats {
+    /// The degree of each constraint, in the order in which `Air::eval` asserts them.
+    pub constraint_degrees: Vec<usize>,
+    /// The location of the `assert_*` call which asserted each constraint.
+    pub constraint_locations: Vec<&'static Location<'static>>,
+    /// The index of the first constraint of maximal degree, if there are any constraints.
+    pub max_degree_constraint: Option<usize>,
+    /// The number of multiplications needed to evaluate every constraint on a row, once common
+    /// subexpressions are merged.
+    pub num_multiplications: usize,
+    /// The main trace columns which neither a constraint nor an interaction refers to.
+    pub unused_columns: Vec<usize>,
+    /// The extension field columns of the main trace which no constraint refers to.
+    pub unused_extension_columns: Vec<usize>,
+    /// The preprocessed columns which neither a constraint nor an interaction refers to.
+    pub unused_preprocessed_columns: Vec<usize>,
+    /// The log2 of the number of quotient chunks, which is also the blowup of the quotient domain
+    /// over the trace domain.
+    pub log_quotient_degree: usize,
+}
+
+impl AirStats {
+    /// Analyze the constraints of `air`, which has `preprocessed_width` preprocessed columns and
+    /// `num_public_values` public values, for a PCS which is hiding if `is_zk`.
+    #[instrument(name = "analyze constraints", skip_all)]
+    pub fn new<F, A>(
+        air: &A,
+        preprocessed_width: usize,
+        num_public_values: usize,
+        is_zk: bool,
+    ) -> Self
+    where
+        F: Field,
+        A: Air<SymbolicAirBuilder<F>> + ?Sized,
+    {
+        let (constraints, constraint_locations) =
+            symbolic_builder(air, preprocessed_width, num_public_values)
+                .constraints_with_locations();
+        let constraint_degrees: Vec<usize> = constraints
+            .iter()
+            .map(SymbolicExpression::constraint_degree)
+            .collect();
+        let max_degree = constraint_degrees.iter().copied().max();
+        let max_degree_constraint =
+            max_degree.and_then(|max| constraint_degrees.iter().position(|&d| d == max));
+
+        let compiled = CompiledAir::from_constraints(
+            &constraints,
+            air.width(),
+            air.extension_width(),
+            preprocessed_width,
+            num_public_values,
+            air.num_extension_public_values(),
+            air.row_offsets(),
+            air.row_selectors(),
+            air.periodic_columns(),
+        );
+        let mut main_used = vec![false; air.width()];
+        let mut extension_used = vec![false; air.extension_width()];
+        let mut preprocessed_used = vec![false; preprocessed_width];
+        let mut num_multiplications = 0;
+        for instruction in compiled.instructions() {
+            match *instruction {
+                Instruction::Main { index, .. } => main_used[index] = true,
+                Instruction::MainExtension { index, .. } => extension_used[index] = true,
+                Instruction::Preprocessed { index, .. } => preprocessed_used[index] = true,
+                Instruction::Mul(..) => num_multiplications += 1,
+                _ => {}
+            }
+        }
+        for interaction in air.sends().iter().chain(&air.receives()) {
+            for column in interaction.fields.iter().chain([&interaction.count]) {
+                for (col, _) in column.column_weights() {
+                    match *col {
+                        PairCol::Main(index) => main_used[index] = true,
+                        PairCol::Preprocessed(index) => preprocessed_used[index] = true,
+                    }
+                }
+            }
+        }
+
+        Self {
+            max_degree_constraint,
+            num_multiplications,
+            unused_columns: unused(&main_used),
+            unused_extension_columns: unused(&extension_used),
+            unused_preprocessed_columns: unused(&preprocessed_used),
+            log_quotient_degree: log_quotient_degree(max_degree.unwrap_or(0), is_zk),
+            constraint_degrees,
+            constraint_locations,
+        }
+    }
+
+    pub fn num_constraints(&self) -> usize {
+        self.constraint_degrees.len()
+    }
+
+    /// The maximal degree of the constraints, or zero if there are none.
+    pub fn max_degree(&self) -> usize {
+        self.max_degree_constraint
+            .map_or(0, |i| self.constraint_degrees[i])
+    }
+
+    /// The number of quotient chunks.
+    pub const fn quotient_degree(&self) -> usize {
+        1 << self.log_quotient_degree
+    }
+}
+
+fn unused(used: &[bool]) -> Vec<usize> {
+    used.iter()
+        .enumerate()
+        .filter(|(_, &used)| !used)
+        .map(|(i, _)| i)
+        .collect()
+}
+
+impl fmt::Display for AirStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "constraints: {}", self.num_constraints())?;
+        match self.max_degree_constraint {
+            Some(i) => writeln!(
+                f,
+                "max degree: {} (constraint {i} at {})",
+                self.max_degree(),
+                self.constraint_locations[i]
+            )?,
+            None => writeln!(f, "max degree: 0")?,
+        }
+        writeln!(f, "multiplications: {}", self.num_multiplications)?;
+        writeln!(f, "unused columns: {:?}", self.unused_columns)?;
+        if !self.unused_extension_columns.is_empty() {
+            writeln!(
+                f,
+                "unused extension columns: {:?}",
+                self.unused_extension_columns
+            )?;
+        }
+        if !self.unused_preprocessed_columns.is_empty() {
+            writeln!(
+                f,
+                "unused preprocessed columns: {:?}",
+                self.unused_preprocessed_columns
+            )?;
+        }
+        write!(f, "quotient chunks: {}", self.quotient_degree())
+    }
+}
@@ -0,0 +1,162 @@
+use alloc::vec::Vec;
+
+use itertools::{izip, Itertools};
+use p3_air::Air;
+use p3_matrix::dense::RowMajorMatrix;
+use tracing::instrument;
+
+use crate::tables::check_extension_trace;
+use crate::{
+    prove_tables, setup_keys, unwrap_proof, BatchProof, Instance, InteractionScope,
+    ProverConstraintFolder, ProverTable, StarkGenericConfig, StarkProvingKey, SymbolicAirBuilder,
+    TablesProof, Val,
+};
+
+/// Prove that each of `traces` satisfies `air`, in a single proof.
+///
+/// The instances are independent, and their traces may have different heights: each must satisfy
+/// `air` on its own, with `public_values[i]` as the public values of `traces[i]`. All traces are
+/// committed to in one PCS round, and all quotient chunks in another, so the instances share their
+/// challenges and a single opening argument.
+#[instrument(skip_all)]
+#[allow(clippy::multiple_bound_locations)] // cfg not supported in where clauses?
+pub fn prove_batch<
+    SC,
+    #[cfg(debug_assertions)] A: for<'a> Air<crate::DebugConstraintBuilder<'a, Val<SC>, SC::Challenge>>,
+    #[cfg(not(debug_assertions))] A,
+>(
+    config: &SC,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    traces: Vec<RowMajorMatrix<Val<SC>>>,
+    public_values: &[Vec<Val<SC>>],
+) -> BatchProof<SC>
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    let num_public_values = public_values.first().map_or(0, Vec::len);
+    let (proving_key, _) = setup_keys(config, air, num_public_values);
+    prove_batch_with_key(config, &proving_key, air, challenger, traces, public_values)
+}
+
+/// Prove that each of `traces` satisfies `air`, in a single proof, given the proving key of `air`
+/// from `setup_keys`.
+#[instrument(skip_all)]
+#[allow(clippy::multiple_bound_locations)] // cfg not supported in where clauses?
+pub fn prove_batch_with_key<
+    SC,
+    #[cfg(debug_assertions)] A: for<'a> Air<crate::DebugConstraintBuilder<'a, Val<SC>, SC::Challenge>>,
+    #[cfg(not(debug_assertions))] A,
+>(
+    config: &SC,
+    proving_key: &StarkProvingKey<SC>,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    traces: Vec<RowMajorMatrix<Val<SC>>>,
+    public_values: &[Vec<Val<SC>>],
+) -> BatchProof<SC>
+where
+    SC: StarkGenericConfig,
+    A: for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    let instances = public_values
+        .iter()
+        .map(|pis| Instance::new(pis.clone()))
+        .collect_vec();
+    prove_batch_with_instances(
+        config,
+        proving_key,
+        air,
+        challenger,
+        traces,
+        None,
+        &instances,
+    )
+}
+
+/// As `prove_batch_with_key`, for AIRs which may have extension field columns or extension field
+/// public values.
+///
+/// `extension_traces[i]` holds the extension field columns of `traces[i]`, and must be given if
+/// and only if the AIR has any. `instances[i]` holds the public inputs of `traces[i]`. If the AIR
+/// has a preprocessed trace, it is opened once for all instances, whose traces must all have its
+/// height.
+#[instrument(skip_all)]
+#[allow(clippy::multiple_bound_locations)] // cfg not supported in where clauses?
+pub fn prove_batch_with_instances<
+    SC,
+    #[cfg(debug_assertions)] A: for<'a> Air<crate::DebugConstraintBuilder<'a, Val<SC>, SC::Challenge>>,
+    #[cfg(not(debug_assertions))] A,
+>(
+    config: &SC,
+    proving_key: &StarkProvingKey<SC>,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    traces: Vec<RowMajorMatrix<Val<SC>>>,
+    extension_traces: Option<Vec<RowMajorMatrix<SC::Challenge>>>,
+    instances: &[Instance<SC>],
+) -> BatchProof<SC>
+where
+    SC: StarkGenericConfig,
+    A: for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    assert!(!traces.is_empty(), "expected at least one trace");
+    assert_eq!(
+        traces.len(),
+        instances.len(),
+        "expected one instance per trace"
+    );
+    let extension_traces = match extension_traces {
+        Some(extension_traces) => {
+            assert_eq!(
+                extension_traces.len(),
+                traces.len(),
+                "expected one extension trace per trace"
+            );
+            extension_traces.into_iter().map(Some).collect_vec()
+        }
+        None => traces.iter().map(|_| None).collect_vec(),
+    };
+
+    for (trace, extension_trace) in izip!(&traces, &extension_traces) {
+        check_extension_trace(air, trace, extension_trace.as_ref());
+    }
+
+    #[cfg(debug_assertions)]
+    for (trace, extension_trace, instance) in izip!(&traces, &extension_traces, instances) {
+        crate::check_constraints::assert_no_failures(&crate::check_constraints_with_extension(
+            air,
+            trace,
+            extension_trace.as_ref(),
+            &instance.public_values,
+            &instance.extension_public_values,
+        ));
+    }
+
+    let tables = izip!(traces, extension_traces, instances)
+        .map(|(trace, extension_trace, instance)| ProverTable {
+            air,
+            proving_key,
+            trace,
+            extension_trace,
+            instance,
+        })
+        .collect();
+    let proof = prove_tables(config, challenger, tables, InteractionScope::Table, &mut ());
+    let TablesProof {
+        commitments,
+        zeta_pow_witness,
+        opened_values,
+        opening_proof,
+        degree_bits,
+        cumulative_sums: _,
+    } = unwrap_proof(proof);
+    BatchProof {
+        commitments,
+        zeta_pow_witness,
+        opened_values,
+        opening_proof,
+        degree_bits,
+    }
+}
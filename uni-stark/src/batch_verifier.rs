@@ -0,0 +1,106 @@
+use alloc::vec::Vec;
+
+use itertools::{izip, Itertools};
+use p3_air::Air;
+use tracing::instrument;
+
+use crate::{
+    setup_keys, verify_tables, BatchProof, Instance, InteractionScope, PcsError,
+    StarkGenericConfig, StarkVerifyingKey, SymbolicAirBuilder, Val, VerificationError,
+    VerifierConstraintFolder, VerifierTable,
+};
+
+/// Verify a proof that was generated by `prove_batch`.
+///
+/// The AIR's keys are computed on the fly; use `verify_batch_with_key` to reuse a verifying key
+/// computed once by `setup_keys`.
+#[instrument(skip_all)]
+pub fn verify_batch<SC, A>(
+    config: &SC,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    proof: &BatchProof<SC>,
+    public_values: &[Vec<Val<SC>>],
+) -> Result<(), VerificationError<PcsError<SC>>>
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+{
+    let num_public_values = public_values.first().map_or(0, Vec::len);
+    let (_, verifying_key) = setup_keys(config, air, num_public_values);
+    verify_batch_with_key(
+        config,
+        &verifying_key,
+        air,
+        challenger,
+        proof,
+        public_values,
+    )
+}
+
+/// Verify a proof generated by `prove_batch`, given the verifying key of `air` from `setup_keys`.
+#[instrument(skip_all)]
+pub fn verify_batch_with_key<SC, A>(
+    config: &SC,
+    verifying_key: &StarkVerifyingKey<SC>,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    proof: &BatchProof<SC>,
+    public_values: &[Vec<Val<SC>>],
+) -> Result<(), VerificationError<PcsError<SC>>>
+where
+    SC: StarkGenericConfig,
+    A: for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+{
+    let instances = public_values
+        .iter()
+        .map(|pis| Instance::new(pis.clone()))
+        .collect_vec();
+    verify_batch_with_instances(config, verifying_key, air, challenger, proof, &instances)
+}
+
+/// Verify a proof generated by `prove_batch_with_instances` for the public inputs `instances`,
+/// given the verifying key of `air` from `setup_keys`.
+#[instrument(skip_all)]
+pub fn verify_batch_with_instances<SC, A>(
+    config: &SC,
+    verifying_key: &StarkVerifyingKey<SC>,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    proof: &BatchProof<SC>,
+    instances: &[Instance<SC>],
+) -> Result<(), VerificationError<PcsError<SC>>>
+where
+    SC: StarkGenericConfig,
+    A: for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+{
+    let BatchProof {
+        commitments,
+        zeta_pow_witness,
+        opened_values,
+        opening_proof,
+        degree_bits,
+    } = proof;
+    if opened_values.len() != degree_bits.len() || instances.len() != degree_bits.len() {
+        return Err(VerificationError::InvalidProofShape);
+    }
+    let tables = izip!(instances, degree_bits, opened_values)
+        .map(|(instance, &degree_bits, opened_values)| VerifierTable {
+            air,
+            verifying_key,
+            instance,
+            degree_bits,
+            opened_values,
+            cumulative_sum: None,
+        })
+        .collect_vec();
+    verify_tables(
+        config,
+        challenger,
+        commitments,
+        *zeta_pow_witness,
+        opening_proof,
+        &tables,
+        InteractionScope::Table,
+    )
+}
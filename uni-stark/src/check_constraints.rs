@@ -1,63 +1,194 @@
+use alloc::vec;
 use alloc::vec::Vec;
+use core::fmt;
+use core::panic::Location;
 
-use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues};
-use p3_field::Field;
+use p3_air::{
+    Air, AirBuilder, AirBuilderWithExtensionPublicValues, AirBuilderWithPublicValues,
+    ExtensionBuilder, ExtensionMainBuilder, PairBuilder, PeriodicAirBuilder, RowSelector,
+};
+use p3_field::{ExtensionField, Field};
 use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixView};
-use p3_matrix::stack::VerticalPair;
 use p3_matrix::Matrix;
 use tracing::instrument;
 
-#[instrument(name = "check constraints", skip_all)]
-pub(crate) fn check_constraints<F, A>(air: &A, main: &RowMajorMatrix<F>, public_values: &Vec<F>)
+use crate::window::{assert_periodic_columns_fit, assert_row_selectors_fit, select_row};
+use crate::{check_periodic_columns, window_size};
+
+/// A constraint which does not vanish on some row of a trace.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConstraintFailure<F> {
+    /// The index of the row on which the constraint failed.
+    pub row: usize,
+    /// The index of the constraint, in the order in which `Air::eval` asserts them.
+    pub constraint_index: usize,
+    /// The location of the `assert_*` call which asserted the constraint.
+    pub location: &'static Location<'static>,
+    /// The value of the constraint on the row, which should have been zero.
+    pub value: F,
+}
+
+impl<F: fmt::Display> fmt::Display for ConstraintFailure<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "constraint {} at {} had nonzero value {} on row {}",
+            self.constraint_index, self.location, self.value, self.row
+        )
+    }
+}
+
+/// Evaluate the constraints of `air` on every row of `main`, returning those which do not vanish.
+///
+/// Like the prover, this wraps around the end of the trace to fill the evaluation window of the
+/// last rows. The constraints of the LogUp argument are not checked.
+pub fn check_constraints<F, A>(
+    air: &A,
+    main: &RowMajorMatrix<F>,
+    public_values: &[F],
+) -> Vec<ConstraintFailure<F>>
 where
     F: Field,
     A: for<'a> Air<DebugConstraintBuilder<'a, F>>,
+{
+    check_constraints_with_extension(air, main, None, public_values, &[])
+}
+
+/// Evaluate the constraints of `air` on every row of `main` and of its extension field columns
+/// `main_ext`, with extension field public values `extension_public_values`, returning those which
+/// do not vanish.
+#[instrument(name = "check constraints", skip_all)]
+pub fn check_constraints_with_extension<F, EF, A>(
+    air: &A,
+    main: &RowMajorMatrix<F>,
+    main_ext: Option<&RowMajorMatrix<EF>>,
+    public_values: &[F],
+    extension_public_values: &[EF],
+) -> Vec<ConstraintFailure<EF>>
+where
+    F: Field,
+    EF: ExtensionField<F>,
+    A: for<'a> Air<DebugConstraintBuilder<'a, F, EF>>,
 {
     let height = main.height();
+    let preprocessed = air.preprocessed_trace();
+    let row_offsets = air.row_offsets();
+    let window_size = window_size(&row_offsets);
+    let row_selectors = air.row_selectors();
+    assert_row_selectors_fit(&row_selectors, height);
+    let periodic_columns = air.periodic_columns();
+    check_periodic_columns(&periodic_columns);
+    assert_periodic_columns_fit(&periodic_columns, height);
 
-    (0..height).for_each(|i| {
-        let i_next = (i + 1) % height;
-
-        let local = main.row_slice(i);
-        let next = main.row_slice(i_next);
-        let main = VerticalPair::new(
-            RowMajorMatrixView::new_row(&*local),
-            RowMajorMatrixView::new_row(&*next),
-        );
+    let mut failures = vec![];
+    for i in 0..height {
+        let main = window(main, i, &row_offsets);
+        let main_ext = main_ext.map_or(RowMajorMatrix::new(vec![], 0), |main_ext| {
+            window(main_ext, i, &row_offsets)
+        });
+        let preprocessed = preprocessed
+            .as_ref()
+            .map_or(RowMajorMatrix::new(vec![], 0), |prep| {
+                window(prep, i, &row_offsets)
+            });
 
         let mut builder = DebugConstraintBuilder {
             row_index: i,
-            main,
+            preprocessed: preprocessed.as_view(),
+            main: main.as_view(),
+            main_ext: main_ext.as_view(),
             public_values,
+            extension_public_values,
             is_first_row: F::from_bool(i == 0),
             is_last_row: F::from_bool(i == height - 1),
-            is_transition: F::from_bool(i != height - 1),
+            is_transition_windows: (2..=window_size)
+                .map(|size| F::from_bool(i + size - 1 < height))
+                .collect(),
+            row_selectors: row_selectors
+                .iter()
+                .map(|&selector| (selector, F::from_bool(selector.contains(i))))
+                .collect(),
+            periodic_values: periodic_columns
+                .iter()
+                .map(|column| column[i % column.len()])
+                .collect(),
+            constraint_index: 0,
+            failures: &mut failures,
         };
 
         air.eval(&mut builder);
-    });
+    }
+    failures
 }
 
-/// An `AirBuilder` which asserts that each constraint is zero, allowing any failed constraints to
-/// be detected early.
+/// Panic if any constraint failed, reporting the first failure.
+#[cfg(debug_assertions)]
+pub(crate) fn assert_no_failures<F: fmt::Display>(failures: &[ConstraintFailure<F>]) {
+    if let Some(failure) = failures.first() {
+        panic!(
+            "constraints had nonzero value: {failure} ({} failures in total)",
+            failures.len()
+        );
+    }
+}
+
+/// The rows of `trace` at each of `row_offsets` from row `i`, wrapping around.
+fn window<T: Clone + Send + Sync>(
+    trace: &RowMajorMatrix<T>,
+    i: usize,
+    row_offsets: &[usize],
+) -> RowMajorMatrix<T> {
+    let height = trace.height();
+    let values = row_offsets
+        .iter()
+        .flat_map(|&offset| trace.row_slice((i + offset) % height).to_vec())
+        .collect();
+    RowMajorMatrix::new(values, trace.width())
+}
+
+/// An `AirBuilder` which evaluates each constraint on a single row, recording those which are
+/// nonzero.
 #[derive(Debug)]
-pub struct DebugConstraintBuilder<'a, F: Field> {
+pub struct DebugConstraintBuilder<'a, F: Field, EF = F> {
     row_index: usize,
-    main: VerticalPair<RowMajorMatrixView<'a, F>, RowMajorMatrixView<'a, F>>,
+    preprocessed: RowMajorMatrixView<'a, F>,
+    main: RowMajorMatrixView<'a, F>,
+    main_ext: RowMajorMatrixView<'a, EF>,
     public_values: &'a [F],
+    extension_public_values: &'a [EF],
     is_first_row: F,
     is_last_row: F,
-    is_transition: F,
+    is_transition_windows: Vec<F>,
+    row_selectors: Vec<(RowSelector, F)>,
+    periodic_values: Vec<F>,
+    constraint_index: usize,
+    failures: &'a mut Vec<ConstraintFailure<EF>>,
+}
+
+impl<F: Field, EF: ExtensionField<F>> DebugConstraintBuilder<'_, F, EF> {
+    #[track_caller]
+    fn record(&mut self, value: EF) {
+        if !value.is_zero() {
+            self.failures.push(ConstraintFailure {
+                row: self.row_index,
+                constraint_index: self.constraint_index,
+                location: Location::caller(),
+                value,
+            });
+        }
+        self.constraint_index += 1;
+    }
 }
 
-impl<'a, F> AirBuilder for DebugConstraintBuilder<'a, F>
+impl<'a, F, EF> AirBuilder for DebugConstraintBuilder<'a, F, EF>
 where
     F: Field,
+    EF: ExtensionField<F>,
 {
     type F = F;
     type Expr = F;
     type Var = F;
-    type M = VerticalPair<RowMajorMatrixView<'a, F>, RowMajorMatrixView<'a, F>>;
+    type M = RowMajorMatrixView<'a, F>;
 
     fn main(&self) -> Self::M {
         self.main
@@ -72,37 +203,77 @@ where
     }
 
     fn is_transition_window(&self, size: usize) -> Self::Expr {
-        if size == 2 {
-            self.is_transition
-        } else {
-            panic!("only supports a window size of 2")
-        }
+        assert!(
+            (2..self.is_transition_windows.len() + 2).contains(&size),
+            "no transition selector for a window of {size} rows; the AIR's row offsets are too short"
+        );
+        self.is_transition_windows[size - 2]
     }
 
-    fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
-        assert_eq!(
-            x.into(),
-            F::ZERO,
-            "constraints had nonzero value on row {}",
-            self.row_index
-        );
+    fn row_selector(&self, selector: RowSelector) -> Self::Expr {
+        select_row(&self.row_selectors, selector)
     }
 
-    fn assert_eq<I1: Into<Self::Expr>, I2: Into<Self::Expr>>(&mut self, x: I1, y: I2) {
-        let x = x.into();
-        let y = y.into();
-        assert_eq!(
-            x, y,
-            "values didn't match on row {}: {} != {}",
-            self.row_index, x, y
-        );
+    #[track_caller]
+    fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
+        self.record(EF::from_base(x.into()));
     }
 }
 
-impl<'a, F: Field> AirBuilderWithPublicValues for DebugConstraintBuilder<'a, F> {
+impl<F: Field, EF: ExtensionField<F>> AirBuilderWithPublicValues
+    for DebugConstraintBuilder<'_, F, EF>
+{
     type PublicVar = Self::F;
 
     fn public_values(&self) -> &[Self::F] {
         self.public_values
     }
 }
+
+impl<F: Field, EF: ExtensionField<F>> PeriodicAirBuilder for DebugConstraintBuilder<'_, F, EF> {
+    type PeriodicVar = F;
+
+    fn periodic_values(&self) -> &[F] {
+        &self.periodic_values
+    }
+}
+
+impl<F: Field, EF: ExtensionField<F>> PairBuilder for DebugConstraintBuilder<'_, F, EF> {
+    fn preprocessed(&self) -> Self::M {
+        self.preprocessed
+    }
+}
+
+impl<F: Field, EF: ExtensionField<F>> ExtensionBuilder for DebugConstraintBuilder<'_, F, EF> {
+    type EF = EF;
+    type ExprEF = EF;
+    type VarEF = EF;
+
+    #[track_caller]
+    fn assert_zero_ext<I>(&mut self, x: I)
+    where
+        I: Into<Self::ExprEF>,
+    {
+        self.record(x.into());
+    }
+}
+
+impl<F: Field, EF: ExtensionField<F>> AirBuilderWithExtensionPublicValues
+    for DebugConstraintBuilder<'_, F, EF>
+{
+    type PublicVarEF = EF;
+
+    fn extension_public_values(&self) -> &[EF] {
+        self.extension_public_values
+    }
+}
+
+impl<'a, F: Field, EF: ExtensionField<F>> ExtensionMainBuilder
+    for DebugConstraintBuilder<'a, F, EF>
+{
+    type MEF = RowMajorMatrixView<'a, EF>;
+
+    fn main_ext(&self) -> Self::MEF {
+        self.main_ext
+    }
+}
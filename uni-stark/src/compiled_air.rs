@@ -0,0 +1,615 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::{Add, Mul, Sub};
+
+use p3_air::{
+    Air, AirBuilderWithExtensionPublicValues, AirBuilderWithPublicValues, BaseAir,
+    ExtensionBuilder, ExtensionMainBuilder, PairBuilder, PeriodicAirBuilder, RowSelector,
+};
+use p3_field::Field;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use crate::{
+    check_periodic_columns, check_row_offsets, check_row_selectors, get_symbolic_constraints,
+    window_size, Entry, SymbolicAirBuilder, SymbolicExpression,
+};
+
+/// A register of a `CompiledAir`, holding either a base field or an extension field value.
+///
+/// Base and extension field registers are numbered separately, in the order in which the
+/// instructions writing them appear.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Register {
+    Base(usize),
+    Ext(usize),
+}
+
+impl Register {
+    pub const fn is_ext(&self) -> bool {
+        matches!(self, Self::Ext(_))
+    }
+
+    /// Whether this register is among the first `num_base` base field and `num_ext` extension
+    /// field registers.
+    const fn is_written(&self, num_base: usize, num_ext: usize) -> bool {
+        match *self {
+            Self::Base(x) => x < num_base,
+            Self::Ext(x) => x < num_ext,
+        }
+    }
+}
+
+/// An instruction of a `CompiledAir`, which writes its result to the next register.
+///
+/// The result is an extension field value if the instruction reads an extension field column,
+/// public value or register, and a base field value otherwise.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Instruction {
+    /// Column `index` of the preprocessed trace, on row `row` of the evaluation window.
+    Preprocessed {
+        row: usize,
+        index: usize,
+    },
+    /// Column `index` of the main trace, on row `row` of the evaluation window.
+    Main {
+        row: usize,
+        index: usize,
+    },
+    /// Extension field column `index` of the main trace, on row `row` of the evaluation window.
+    MainExtension {
+        row: usize,
+        index: usize,
+    },
+    /// Public value `index`.
+    Public {
+        index: usize,
+    },
+    /// Extension field public value `index`.
+    ExtensionPublic {
+        index: usize,
+    },
+    /// Periodic column `index`, on the current row.
+    Periodic {
+        index: usize,
+    },
+    IsFirstRow,
+    IsLastRow,
+    /// The transition selector of a window of the given number of rows.
+    IsTransitionWindow(usize),
+    /// The selector of a set of rows, which must be one of the AIR's row selectors.
+    RowSelector(RowSelector),
+    /// Constant `index` of the constant pool.
+    Constant(usize),
+    Add(Register, Register),
+    Sub(Register, Register),
+    Neg(Register),
+    Mul(Register, Register),
+}
+
+impl Instruction {
+    /// Whether the result of this instruction is an extension field value.
+    pub const fn is_ext(&self) -> bool {
+        match self {
+            Self::MainExtension { .. } | Self::ExtensionPublic { .. } => true,
+            Self::Add(x, y) | Self::Sub(x, y) | Self::Mul(x, y) => x.is_ext() || y.is_ext(),
+            Self::Neg(x) => x.is_ext(),
+            _ => false,
+        }
+    }
+}
+
+/// The constraints of an AIR, compiled from their symbolic DAG into a flat list of instructions.
+///
+/// Common subexpressions are computed once, whether or not they were shared in the DAG, so
+/// interpreting a `CompiledAir` over the rows of the quotient domain can be cheaper than running
+/// the original `Air::eval`; see `StarkProvingKey::compile_constraints`. A `CompiledAir` is itself
+/// an AIR, whose constraints are equivalent to those it was compiled from, which makes it possible
+/// to prove and verify AIRs that only exist as data.
+///
+/// This is also the serialized form of a set of constraints, and is checked to be well formed when
+/// deserialized: see `DataAir` for a complete AIR.
+///
+/// The constraints of a `CompiledAir` are asserted in the order of the original ones, so its
+/// folded constraints match those of the original AIR. So does its fingerprint in `setup_keys`,
+/// unless the original AIR has interactions, since the fingerprint also merges equal
+/// subexpressions and orders the operands of additions and multiplications.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "F: Field", try_from = "CompiledAirParts<F>")]
+pub struct CompiledAir<F> {
+    width: usize,
+    extension_width: usize,
+    preprocessed_width: usize,
+    num_public_values: usize,
+    num_extension_public_values: usize,
+    row_offsets: Vec<usize>,
+    row_selectors: Vec<RowSelector>,
+    periodic_columns: Vec<Vec<F>>,
+    constants: Vec<F>,
+    instructions: Vec<Instruction>,
+    #[serde(skip_serializing)]
+    num_base_registers: usize,
+    #[serde(skip_serializing)]
+    num_ext_registers: usize,
+    constraints: Vec<Register>,
+}
+
+/// A deserialized `CompiledAir`, which has not been checked yet.
+#[derive(Deserialize)]
+#[serde(bound = "F: Field")]
+struct CompiledAirParts<F> {
+    width: usize,
+    extension_width: usize,
+    preprocessed_width: usize,
+    num_public_values: usize,
+    #[serde(default)]
+    num_extension_public_values: usize,
+    row_offsets: Vec<usize>,
+    #[serde(default)]
+    row_selectors: Vec<RowSelector>,
+    #[serde(default)]
+    periodic_columns: Vec<Vec<F>>,
+    constants: Vec<F>,
+    instructions: Vec<Instruction>,
+    constraints: Vec<Register>,
+}
+
+impl<F: Field> TryFrom<CompiledAirParts<F>> for CompiledAir<F> {
+    type Error = InvalidAirError;
+
+    fn try_from(parts: CompiledAirParts<F>) -> Result<Self, Self::Error> {
+        let row_offsets = &parts.row_offsets;
+        if row_offsets.first() != Some(&0) || row_offsets.windows(2).any(|pair| pair[0] >= pair[1])
+        {
+            return Err(InvalidAirError::RowOffsets);
+        }
+        let window_size = window_size(row_offsets);
+        let valid_selector = |selector: &RowSelector| match *selector {
+            RowSelector::Row(_) => true,
+            RowSelector::Periodic { period, offset } => period.is_power_of_two() && offset < period,
+        };
+        if !parts.row_selectors.iter().all(valid_selector) {
+            return Err(InvalidAirError::RowSelectors);
+        }
+        if !parts
+            .periodic_columns
+            .iter()
+            .all(|column| column.len().is_power_of_two())
+        {
+            return Err(InvalidAirError::PeriodicColumns);
+        }
+
+        let (mut num_base_registers, mut num_ext_registers) = (0, 0);
+        for (i, &instruction) in parts.instructions.iter().enumerate() {
+            let written =
+                |register: Register| register.is_written(num_base_registers, num_ext_registers);
+            let valid = match instruction {
+                Instruction::Preprocessed { row, index } => {
+                    row < row_offsets.len() && index < parts.preprocessed_width
+                }
+                Instruction::Main { row, index } => row < row_offsets.len() && index < parts.width,
+                Instruction::MainExtension { row, index } => {
+                    row < row_offsets.len() && index < parts.extension_width
+                }
+                Instruction::Public { index } => index < parts.num_public_values,
+                Instruction::ExtensionPublic { index } => index < parts.num_extension_public_values,
+                Instruction::Periodic { index } => index < parts.periodic_columns.len(),
+                Instruction::IsFirstRow | Instruction::IsLastRow => true,
+                Instruction::IsTransitionWindow(size) => (2..=window_size).contains(&size),
+                Instruction::RowSelector(selector) => parts.row_selectors.contains(&selector),
+                Instruction::Constant(index) => index < parts.constants.len(),
+                Instruction::Add(x, y) | Instruction::Sub(x, y) | Instruction::Mul(x, y) => {
+                    written(x) && written(y)
+                }
+                Instruction::Neg(x) => written(x),
+            };
+            if !valid {
+                return Err(InvalidAirError::Instruction(i));
+            }
+            if instruction.is_ext() {
+                num_ext_registers += 1;
+            } else {
+                num_base_registers += 1;
+            }
+        }
+
+        if let Some(i) = parts
+            .constraints
+            .iter()
+            .position(|c| !c.is_written(num_base_registers, num_ext_registers))
+        {
+            return Err(InvalidAirError::Constraint(i));
+        }
+
+        Ok(Self {
+            width: parts.width,
+            extension_width: parts.extension_width,
+            preprocessed_width: parts.preprocessed_width,
+            num_public_values: parts.num_public_values,
+            num_extension_public_values: parts.num_extension_public_values,
+            row_offsets: parts.row_offsets,
+            row_selectors: parts.row_selectors,
+            periodic_columns: parts.periodic_columns,
+            constants: parts.constants,
+            instructions: parts.instructions,
+            num_base_registers,
+            num_ext_registers,
+            constraints: parts.constraints,
+        })
+    }
+}
+
+/// The reason an AIR given as data is malformed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvalidAirError {
+    /// The row offsets are not strictly increasing from zero.
+    RowOffsets,
+    /// A periodic row selector's period is not a power of two greater than its offset.
+    RowSelectors,
+    /// A periodic column's period is not a power of two.
+    PeriodicColumns,
+    /// The instruction with the given index reads a register which has not been written yet, or a
+    /// column, row, public value, periodic column, constant, transition selector or row selector
+    /// which does not exist.
+    Instruction(usize),
+    /// The constraint with the given index refers to a register which is never written.
+    Constraint(usize),
+    /// The preprocessed trace does not have as many columns as the constraints refer to.
+    PreprocessedWidth,
+}
+
+impl fmt::Display for InvalidAirError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RowOffsets => write!(f, "row offsets must be strictly increasing from zero"),
+            Self::RowSelectors => {
+                write!(
+                    f,
+                    "periodic row selectors must have a power of two period greater than their \
+                     offset"
+                )
+            }
+            Self::PeriodicColumns => {
+                write!(f, "periodic columns must have a power of two period")
+            }
+            Self::Instruction(i) => write!(f, "instruction {i} has an invalid operand"),
+            Self::Constraint(i) => write!(f, "constraint {i} refers to an unwritten register"),
+            Self::PreprocessedWidth => {
+                write!(
+                    f,
+                    "the preprocessed trace width does not match the constraints"
+                )
+            }
+        }
+    }
+}
+
+impl<F: Field> CompiledAir<F> {
+    /// Compile the constraints of `air`.
+    pub fn new<A>(air: &A, preprocessed_width: usize, num_public_values: usize) -> Self
+    where
+        A: Air<SymbolicAirBuilder<F>> + ?Sized,
+    {
+        let constraints = get_symbolic_constraints(air, preprocessed_width, num_public_values);
+        Self::from_constraints(
+            &constraints,
+            air.width(),
+            air.extension_width(),
+            preprocessed_width,
+            num_public_values,
+            air.num_extension_public_values(),
+            air.row_offsets(),
+            air.row_selectors(),
+            air.periodic_columns(),
+        )
+    }
+
+    /// Compile `constraints`, as returned by `get_symbolic_constraints` for an AIR with the given
+    /// dimensions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a constraint refers to a permutation column or challenge, which only appear in
+    /// the LogUp constraints, or to a row not in `row_offsets`.
+    #[instrument(name = "compile constraints", skip_all)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_constraints(
+        constraints: &[SymbolicExpression<F>],
+        width: usize,
+        extension_width: usize,
+        preprocessed_width: usize,
+        num_public_values: usize,
+        num_extension_public_values: usize,
+        row_offsets: Vec<usize>,
+        row_selectors: Vec<RowSelector>,
+        periodic_columns: Vec<Vec<F>>,
+    ) -> Self {
+        check_row_offsets(&row_offsets);
+        check_row_selectors(&row_selectors);
+        check_periodic_columns(&periodic_columns);
+        let mut compiler = Compiler {
+            air: Self {
+                width,
+                extension_width,
+                preprocessed_width,
+                num_public_values,
+                num_extension_public_values,
+                row_offsets,
+                row_selectors,
+                periodic_columns,
+                constants: Vec::new(),
+                instructions: Vec::new(),
+                num_base_registers: 0,
+                num_ext_registers: 0,
+                constraints: Vec::new(),
+            },
+            visited: BTreeMap::new(),
+            registers: BTreeMap::new(),
+        };
+        for constraint in constraints {
+            let register = compiler.compile_shared(constraint);
+            compiler.air.constraints.push(register);
+        }
+        compiler.air
+    }
+
+    pub const fn preprocessed_width(&self) -> usize {
+        self.preprocessed_width
+    }
+
+    pub const fn num_public_values(&self) -> usize {
+        self.num_public_values
+    }
+
+    /// The constant pool, which `Instruction::Constant` refers to.
+    pub fn constants(&self) -> &[F] {
+        &self.constants
+    }
+
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+
+    /// The register holding the value of each constraint, in the order of the original ones.
+    pub fn constraints(&self) -> &[Register] {
+        &self.constraints
+    }
+}
+
+impl<F: Clone + Sync> BaseAir<F> for CompiledAir<F> {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn extension_width(&self) -> usize {
+        self.extension_width
+    }
+
+    fn row_offsets(&self) -> Vec<usize> {
+        self.row_offsets.clone()
+    }
+
+    fn row_selectors(&self) -> Vec<RowSelector> {
+        self.row_selectors.clone()
+    }
+
+    fn periodic_columns(&self) -> Vec<Vec<F>> {
+        self.periodic_columns.clone()
+    }
+
+    fn num_extension_public_values(&self) -> usize {
+        self.num_extension_public_values
+    }
+}
+
+impl<F, AB> Air<AB> for CompiledAir<F>
+where
+    F: Field,
+    AB: PairBuilder<F = F>
+        + AirBuilderWithPublicValues
+        + AirBuilderWithExtensionPublicValues
+        + ExtensionMainBuilder
+        + PeriodicAirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let preprocessed = builder.preprocessed();
+        let main = builder.main();
+        let main_ext = builder.main_ext();
+        let public_values = builder.public_values();
+        let extension_public_values = builder.extension_public_values();
+        let periodic_values = builder.periodic_values();
+
+        let mut registers = Registers::<AB> {
+            base: Vec::with_capacity(self.num_base_registers),
+            ext: Vec::with_capacity(self.num_ext_registers),
+        };
+        for &instruction in &self.instructions {
+            match instruction {
+                Instruction::Preprocessed { row, index } => {
+                    registers.base.push(preprocessed.get(row, index).into());
+                }
+                Instruction::Main { row, index } => {
+                    registers.base.push(main.get(row, index).into());
+                }
+                Instruction::MainExtension { row, index } => {
+                    registers.ext.push(main_ext.get(row, index).into());
+                }
+                Instruction::Public { index } => {
+                    registers.base.push(public_values[index].into());
+                }
+                Instruction::ExtensionPublic { index } => {
+                    registers.ext.push(extension_public_values[index].into());
+                }
+                Instruction::Periodic { index } => {
+                    registers.base.push(periodic_values[index].into());
+                }
+                Instruction::IsFirstRow => registers.base.push(builder.is_first_row()),
+                Instruction::IsLastRow => registers.base.push(builder.is_last_row()),
+                Instruction::RowSelector(selector) => {
+                    registers.base.push(builder.row_selector(selector));
+                }
+                Instruction::IsTransitionWindow(size) => {
+                    registers.base.push(builder.is_transition_window(size));
+                }
+                Instruction::Constant(index) => {
+                    registers.base.push(self.constants[index].into());
+                }
+                Instruction::Add(x, y) => registers.binary(x, y, Add::add, Add::add),
+                Instruction::Sub(x, y) => registers.binary(x, y, Sub::sub, Sub::sub),
+                Instruction::Neg(Register::Base(x)) => {
+                    registers.base.push(-registers.base[x].clone());
+                }
+                Instruction::Neg(Register::Ext(x)) => {
+                    registers.ext.push(-registers.ext[x].clone());
+                }
+                Instruction::Mul(x, y) => registers.binary(x, y, Mul::mul, Mul::mul),
+            }
+        }
+
+        for &constraint in &self.constraints {
+            match constraint {
+                Register::Base(x) => builder.assert_zero(registers.base[x].clone()),
+                Register::Ext(_) => builder.assert_zero_ext(registers.ext(constraint)),
+            }
+        }
+    }
+}
+
+/// The register files of a `CompiledAir` being interpreted.
+struct Registers<AB: ExtensionBuilder> {
+    base: Vec<AB::Expr>,
+    ext: Vec<AB::ExprEF>,
+}
+
+impl<AB: ExtensionBuilder> Registers<AB> {
+    /// The value of `register`, as an extension field value.
+    fn ext(&self, register: Register) -> AB::ExprEF {
+        match register {
+            Register::Base(x) => self.base[x].clone().into(),
+            Register::Ext(x) => self.ext[x].clone(),
+        }
+    }
+
+    fn binary(
+        &mut self,
+        x: Register,
+        y: Register,
+        base_op: fn(AB::Expr, AB::Expr) -> AB::Expr,
+        ext_op: fn(AB::ExprEF, AB::ExprEF) -> AB::ExprEF,
+    ) {
+        if let (Register::Base(x), Register::Base(y)) = (x, y) {
+            let value = base_op(self.base[x].clone(), self.base[y].clone());
+            self.base.push(value);
+        } else {
+            let value = ext_op(self.ext(x), self.ext(y));
+            self.ext.push(value);
+        }
+    }
+}
+
+struct Compiler<F> {
+    air: CompiledAir<F>,
+    /// The registers of the nodes compiled so far, by address. The constraints outlive the
+    /// compiler, so addresses are not reused.
+    visited: BTreeMap<*const SymbolicExpression<F>, Register>,
+    /// The register written by each instruction, to compute identical instructions only once.
+    registers: BTreeMap<Instruction, Register>,
+}
+
+impl<F: Field> Compiler<F> {
+    fn compile_shared(&mut self, expr: &SymbolicExpression<F>) -> Register {
+        let key = expr as *const _;
+        if let Some(&register) = self.visited.get(&key) {
+            return register;
+        }
+        let register = self.compile(expr);
+        self.visited.insert(key, register);
+        register
+    }
+
+    fn compile(&mut self, expr: &SymbolicExpression<F>) -> Register {
+        let instruction = match expr {
+            SymbolicExpression::Variable(v) => match v.entry {
+                Entry::Preprocessed { offset } => Instruction::Preprocessed {
+                    row: self.window_row(offset),
+                    index: v.index,
+                },
+                Entry::Main { offset } => Instruction::Main {
+                    row: self.window_row(offset),
+                    index: v.index,
+                },
+                Entry::MainExtension { offset } => Instruction::MainExtension {
+                    row: self.window_row(offset),
+                    index: v.index,
+                },
+                Entry::Public => Instruction::Public { index: v.index },
+                Entry::ExtensionPublic => Instruction::ExtensionPublic { index: v.index },
+                Entry::Periodic => Instruction::Periodic { index: v.index },
+                Entry::Permutation { .. } | Entry::Challenge => {
+                    panic!("AIR constraints cannot refer to the LogUp trace or its challenges")
+                }
+            },
+            SymbolicExpression::IsFirstRow => Instruction::IsFirstRow,
+            SymbolicExpression::IsLastRow => Instruction::IsLastRow,
+            SymbolicExpression::IsTransition => Instruction::IsTransitionWindow(2),
+            SymbolicExpression::IsTransitionWindow(size) => Instruction::IsTransitionWindow(*size),
+            SymbolicExpression::RowSelector(selector) => Instruction::RowSelector(*selector),
+            SymbolicExpression::Constant(c) => Instruction::Constant(self.constant(*c)),
+            SymbolicExpression::Add { x, y, .. } => {
+                let (x, y) = (self.compile_shared(x), self.compile_shared(y));
+                // Order the operands of commutative operations, so that `x + y` and `y + x` are
+                // computed once.
+                Instruction::Add(x.min(y), x.max(y))
+            }
+            SymbolicExpression::Sub { x, y, .. } => {
+                Instruction::Sub(self.compile_shared(x), self.compile_shared(y))
+            }
+            SymbolicExpression::Neg { x, .. } => Instruction::Neg(self.compile_shared(x)),
+            SymbolicExpression::Mul { x, y, .. } => {
+                let (x, y) = (self.compile_shared(x), self.compile_shared(y));
+                Instruction::Mul(x.min(y), x.max(y))
+            }
+        };
+        self.push(instruction)
+    }
+
+    fn push(&mut self, instruction: Instruction) -> Register {
+        if let Some(&register) = self.registers.get(&instruction) {
+            return register;
+        }
+        let air = &mut self.air;
+        let register = if instruction.is_ext() {
+            air.num_ext_registers += 1;
+            Register::Ext(air.num_ext_registers - 1)
+        } else {
+            air.num_base_registers += 1;
+            Register::Base(air.num_base_registers - 1)
+        };
+        air.instructions.push(instruction);
+        self.registers.insert(instruction, register);
+        register
+    }
+
+    /// The index of `constant` in the constant pool, which is small enough to search linearly.
+    fn constant(&mut self, constant: F) -> usize {
+        let constants = &mut self.air.constants;
+        constants
+            .iter()
+            .position(|&c| c == constant)
+            .unwrap_or_else(|| {
+                constants.push(constant);
+                constants.len() - 1
+            })
+    }
+
+    /// The position in the evaluation window of the row at `offset`.
+    fn window_row(&self, offset: usize) -> usize {
+        self.air
+            .row_offsets
+            .iter()
+            .position(|&o| o == offset)
+            .unwrap_or_else(|| panic!("row offset {offset} is not in the evaluation window"))
+    }
+}
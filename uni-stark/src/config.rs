@@ -1,14 +1,24 @@
 use core::marker::PhantomData;
 
-use p3_challenger::{CanObserve, CanSample, FieldChallenger};
+use p3_challenger::{CanObserve, CanSample, FieldChallenger, GrindingChallenger};
 use p3_commit::{Pcs, PolynomialSpace};
-use p3_field::{ExtensionField, Field};
+use p3_field::{ExtensionField, Field, PrimeField64};
 
 pub type PcsError<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
     <SC as StarkGenericConfig>::Challenge,
     <SC as StarkGenericConfig>::Challenger,
 >>::Error;
 
+pub type Com<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
+    <SC as StarkGenericConfig>::Challenge,
+    <SC as StarkGenericConfig>::Challenger,
+>>::Commitment;
+
+pub type PcsProverData<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
+    <SC as StarkGenericConfig>::Challenge,
+    <SC as StarkGenericConfig>::Challenger,
+>>::ProverData;
+
 pub type Domain<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
     <SC as StarkGenericConfig>::Challenge,
     <SC as StarkGenericConfig>::Challenger,
@@ -18,27 +28,79 @@ pub type Val<SC> = <Domain<SC> as PolynomialSpace>::Val;
 
 pub type PackedVal<SC> = <Val<SC> as Field>::Packing;
 
+pub type PowWitness<SC> = <<SC as StarkGenericConfig>::Challenger as GrindingChallenger>::Witness;
+
 pub type PackedChallenge<SC> =
     <<SC as StarkGenericConfig>::Challenge as ExtensionField<Val<SC>>>::ExtensionPacking;
 
 pub trait StarkGenericConfig {
-    /// The PCS used to commit to trace polynomials.
-    type Pcs: Pcs<Self::Challenge, Self::Challenger>;
+    /// The PCS used to commit to trace polynomials. Its field must fit in a `u64`, as the
+    /// fingerprints of AIRs encode field elements by their canonical values.
+    type Pcs: Pcs<Self::Challenge, Self::Challenger, Domain: PolynomialSpace<Val: PrimeField64>>;
 
     /// The field from which most random challenges are drawn.
     type Challenge: ExtensionField<Val<Self>>;
 
     /// The challenger (Fiat-Shamir) implementation used.
     type Challenger: FieldChallenger<Val<Self>>
+        + GrindingChallenger
         + CanObserve<<Self::Pcs as Pcs<Self::Challenge, Self::Challenger>>::Commitment>
         + CanSample<Self::Challenge>;
 
     fn pcs(&self) -> &Self::Pcs;
+
+    /// The proof of work bits the prover grinds before the out-of-domain point `zeta` is sampled.
+    /// Zero for none.
+    fn zeta_proof_of_work_bits(&self) -> usize {
+        0
+    }
+}
+
+/// Whether proofs under `SC` are zero knowledge, which is the case when its PCS is hiding.
+pub const fn is_zk<SC: StarkGenericConfig>() -> bool {
+    <SC::Pcs as Pcs<SC::Challenge, SC::Challenger>>::ZK
+}
+
+/// Whether the PCS of `config` supports the domains of a trace of `2^degree_bits` rows whose
+/// quotient is split into `2^log_quotient_degree` chunks. Verifiers check this before building
+/// any domain from the `degree_bits` claimed by a proof.
+pub fn degree_bits_supported<SC: StarkGenericConfig>(
+    config: &SC,
+    degree_bits: usize,
+    log_quotient_degree: usize,
+) -> bool {
+    // A zero knowledge trace is committed over a domain twice its size.
+    let log_extension = log_quotient_degree.max(is_zk::<SC>() as usize);
+    degree_bits.saturating_add(log_extension) <= config.pcs().log_max_domain_size()
+}
+
+/// Grind for the proof of work `config` asks for before `zeta` is sampled, if any.
+pub fn grind_for_zeta<SC: StarkGenericConfig>(
+    config: &SC,
+    challenger: &mut SC::Challenger,
+) -> Option<PowWitness<SC>> {
+    let bits = config.zeta_proof_of_work_bits();
+    (bits > 0).then(|| challenger.grind(bits))
+}
+
+/// Check the proof of work ground by `grind_for_zeta`, which must be present exactly when `config`
+/// asks for one.
+pub fn check_zeta_witness<SC: StarkGenericConfig>(
+    config: &SC,
+    challenger: &mut SC::Challenger,
+    witness: Option<PowWitness<SC>>,
+) -> bool {
+    match (config.zeta_proof_of_work_bits(), witness) {
+        (0, None) => true,
+        (0, Some(_)) | (_, None) => false,
+        (bits, Some(witness)) => challenger.check_witness(bits, witness),
+    }
 }
 
 #[derive(Debug)]
 pub struct StarkConfig<Pcs, Challenge, Challenger> {
     pcs: Pcs,
+    zeta_proof_of_work_bits: usize,
     _phantom: PhantomData<(Challenge, Challenger)>,
 }
 
@@ -46,16 +108,25 @@ impl<Pcs, Challenge, Challenger> StarkConfig<Pcs, Challenge, Challenger> {
     pub const fn new(pcs: Pcs) -> Self {
         Self {
             pcs,
+            zeta_proof_of_work_bits: 0,
             _phantom: PhantomData,
         }
     }
+
+    /// Grind for `bits` of proof of work before sampling the out-of-domain point `zeta`.
+    pub const fn with_zeta_proof_of_work_bits(mut self, bits: usize) -> Self {
+        self.zeta_proof_of_work_bits = bits;
+        self
+    }
 }
 
 impl<Pcs, Challenge, Challenger> StarkGenericConfig for StarkConfig<Pcs, Challenge, Challenger>
 where
     Challenge: ExtensionField<<Pcs::Domain as PolynomialSpace>::Val>,
     Pcs: p3_commit::Pcs<Challenge, Challenger>,
+    <Pcs::Domain as PolynomialSpace>::Val: PrimeField64,
     Challenger: FieldChallenger<<Pcs::Domain as PolynomialSpace>::Val>
+        + GrindingChallenger
         + CanObserve<<Pcs as p3_commit::Pcs<Challenge, Challenger>>::Commitment>
         + CanSample<Challenge>,
 {
@@ -66,4 +137,8 @@ where
     fn pcs(&self) -> &Self::Pcs {
         &self.pcs
     }
+
+    fn zeta_proof_of_work_bits(&self) -> usize {
+        self.zeta_proof_of_work_bits
+    }
 }
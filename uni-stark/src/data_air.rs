@@ -0,0 +1,123 @@
+use alloc::vec::Vec;
+
+use p3_air::{
+    Air, AirBuilderWithExtensionPublicValues, AirBuilderWithPublicValues, BaseAir,
+    ExtensionMainBuilder, PairBuilder, PeriodicAirBuilder, RowSelector,
+};
+use p3_field::Field;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+
+use crate::{CompiledAir, InvalidAirError, SymbolicAirBuilder};
+
+/// An AIR defined entirely by data: its compiled constraints, along with its preprocessed trace.
+///
+/// A `DataAir` can be serialized, e.g. by a circuit compiler, and deserialized elsewhere to prove
+/// and verify with `prove` and `verify` like any other AIR, without generating Rust code.
+/// Deserialization checks that the AIR is well formed. Interactions are not supported.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "F: Field", try_from = "DataAirParts<F>")]
+pub struct DataAir<F> {
+    constraints: CompiledAir<F>,
+    preprocessed: Option<RowMajorMatrix<F>>,
+}
+
+/// A deserialized `DataAir`, which has not been checked yet.
+#[derive(Deserialize)]
+#[serde(bound = "F: Field")]
+struct DataAirParts<F> {
+    constraints: CompiledAir<F>,
+    preprocessed: Option<RowMajorMatrix<F>>,
+}
+
+impl<F: Field> TryFrom<DataAirParts<F>> for DataAir<F> {
+    type Error = InvalidAirError;
+
+    fn try_from(parts: DataAirParts<F>) -> Result<Self, Self::Error> {
+        Self::new(parts.constraints, parts.preprocessed)
+    }
+}
+
+impl<F: Field> DataAir<F> {
+    /// An AIR with the given constraints and preprocessed trace, whose width must be that of the
+    /// preprocessed columns the constraints refer to.
+    pub fn new(
+        constraints: CompiledAir<F>,
+        preprocessed: Option<RowMajorMatrix<F>>,
+    ) -> Result<Self, InvalidAirError> {
+        let preprocessed_width = preprocessed.as_ref().map_or(0, Matrix::width);
+        if preprocessed_width != constraints.preprocessed_width() {
+            return Err(InvalidAirError::PreprocessedWidth);
+        }
+        Ok(Self {
+            constraints,
+            preprocessed,
+        })
+    }
+
+    /// The data of `air`, which has `num_public_values` public values.
+    pub fn from_air<A>(air: &A, num_public_values: usize) -> Self
+    where
+        A: Air<SymbolicAirBuilder<F>> + ?Sized,
+    {
+        let preprocessed = air.preprocessed_trace();
+        let preprocessed_width = preprocessed.as_ref().map_or(0, Matrix::width);
+        Self {
+            constraints: CompiledAir::new(air, preprocessed_width, num_public_values),
+            preprocessed,
+        }
+    }
+
+    pub const fn constraints(&self) -> &CompiledAir<F> {
+        &self.constraints
+    }
+
+    pub const fn num_public_values(&self) -> usize {
+        self.constraints.num_public_values()
+    }
+}
+
+impl<F: Field> BaseAir<F> for DataAir<F> {
+    fn width(&self) -> usize {
+        self.constraints.width()
+    }
+
+    fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
+        self.preprocessed.clone()
+    }
+
+    fn extension_width(&self) -> usize {
+        self.constraints.extension_width()
+    }
+
+    fn row_offsets(&self) -> Vec<usize> {
+        self.constraints.row_offsets()
+    }
+
+    fn row_selectors(&self) -> Vec<RowSelector> {
+        self.constraints.row_selectors()
+    }
+
+    fn periodic_columns(&self) -> Vec<Vec<F>> {
+        self.constraints.periodic_columns()
+    }
+
+    fn num_extension_public_values(&self) -> usize {
+        self.constraints.num_extension_public_values()
+    }
+}
+
+impl<F, AB> Air<AB> for DataAir<F>
+where
+    F: Field,
+    AB: PairBuilder<F = F>
+        + AirBuilderWithPublicValues
+        + AirBuilderWithExtensionPublicValues
+        + ExtensionMainBuilder
+        + PeriodicAirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        self.constraints.eval(builder);
+    }
+}
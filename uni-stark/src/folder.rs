@@ -1,19 +1,33 @@
 use alloc::vec::Vec;
 
-use p3_air::{AirBuilder, AirBuilderWithPublicValues};
+use p3_air::{
+    AirBuilder, AirBuilderWithExtensionPublicValues, AirBuilderWithPublicValues, ExtensionBuilder,
+    ExtensionMainBuilder, PairBuilder, PeriodicAirBuilder, PermutationAirBuilder, RowSelector,
+};
 use p3_field::FieldAlgebra;
 use p3_matrix::dense::RowMajorMatrixView;
 use p3_matrix::stack::VerticalPair;
 
+use crate::window::select_row;
 use crate::{PackedChallenge, PackedVal, StarkGenericConfig, Val};
 
 #[derive(Debug)]
 pub struct ProverConstraintFolder<'a, SC: StarkGenericConfig> {
+    pub preprocessed: RowMajorMatrixView<'a, PackedVal<SC>>,
     pub main: RowMajorMatrixView<'a, PackedVal<SC>>,
+    pub main_ext: RowMajorMatrixView<'a, PackedChallenge<SC>>,
+    pub permutation: RowMajorMatrixView<'a, PackedChallenge<SC>>,
+    pub permutation_challenges: &'a [PackedChallenge<SC>],
     pub public_values: &'a Vec<Val<SC>>,
+    pub extension_public_values: &'a [PackedChallenge<SC>],
     pub is_first_row: PackedVal<SC>,
     pub is_last_row: PackedVal<SC>,
-    pub is_transition: PackedVal<SC>,
+    /// The transition selectors for windows of 2, 3, ... rows; see `transition_window_selectors`.
+    pub is_transition_windows: &'a [PackedVal<SC>],
+    /// Each of the AIR's declared row selectors, with its value.
+    pub row_selectors: &'a [(RowSelector, PackedVal<SC>)],
+    /// The value of each of the AIR's periodic columns.
+    pub periodic_values: &'a [PackedVal<SC>],
     pub alpha_powers: &'a [SC::Challenge],
     pub accumulator: PackedChallenge<SC>,
     pub constraint_index: usize,
@@ -23,11 +37,24 @@ type ViewPair<'a, T> = VerticalPair<RowMajorMatrixView<'a, T>, RowMajorMatrixVie
 
 #[derive(Debug)]
 pub struct VerifierConstraintFolder<'a, SC: StarkGenericConfig> {
-    pub main: ViewPair<'a, SC::Challenge>,
+    /// The openings of the preprocessed trace at each of the AIR's row offsets.
+    pub preprocessed: RowMajorMatrixView<'a, SC::Challenge>,
+    /// The openings of the main trace at each of the AIR's row offsets.
+    pub main: RowMajorMatrixView<'a, SC::Challenge>,
+    /// The openings of the main trace's extension field columns at each of the AIR's row offsets.
+    pub main_ext: RowMajorMatrixView<'a, SC::Challenge>,
+    pub permutation: ViewPair<'a, SC::Challenge>,
+    pub permutation_challenges: &'a [SC::Challenge],
     pub public_values: &'a Vec<Val<SC>>,
+    pub extension_public_values: &'a [SC::Challenge],
     pub is_first_row: SC::Challenge,
     pub is_last_row: SC::Challenge,
-    pub is_transition: SC::Challenge,
+    /// The transition selectors for windows of 2, 3, ... rows; see `transition_window_selectors`.
+    pub is_transition_windows: &'a [SC::Challenge],
+    /// Each of the AIR's declared row selectors, with its value.
+    pub row_selectors: &'a [(RowSelector, SC::Challenge)],
+    /// The value of each of the AIR's periodic columns.
+    pub periodic_values: &'a [SC::Challenge],
     pub alpha: SC::Challenge,
     pub accumulator: SC::Challenge,
 }
@@ -55,11 +82,12 @@ impl<'a, SC: StarkGenericConfig> AirBuilder for ProverConstraintFolder<'a, SC> {
 
     #[inline]
     fn is_transition_window(&self, size: usize) -> Self::Expr {
-        if size == 2 {
-            self.is_transition
-        } else {
-            panic!("uni-stark only supports a window size of 2")
-        }
+        select_transition_window(self.is_transition_windows, size)
+    }
+
+    #[inline]
+    fn row_selector(&self, selector: RowSelector) -> Self::Expr {
+        select_row(self.row_selectors, selector)
     }
 
     #[inline]
@@ -80,11 +108,79 @@ impl<'a, SC: StarkGenericConfig> AirBuilderWithPublicValues for ProverConstraint
     }
 }
 
+impl<SC: StarkGenericConfig> PeriodicAirBuilder for ProverConstraintFolder<'_, SC> {
+    type PeriodicVar = PackedVal<SC>;
+
+    #[inline]
+    fn periodic_values(&self) -> &[Self::PeriodicVar] {
+        self.periodic_values
+    }
+}
+
+impl<SC: StarkGenericConfig> PairBuilder for ProverConstraintFolder<'_, SC> {
+    #[inline]
+    fn preprocessed(&self) -> Self::M {
+        self.preprocessed
+    }
+}
+
+impl<SC: StarkGenericConfig> ExtensionBuilder for ProverConstraintFolder<'_, SC> {
+    type EF = SC::Challenge;
+    type ExprEF = PackedChallenge<SC>;
+    type VarEF = PackedChallenge<SC>;
+
+    #[inline]
+    fn assert_zero_ext<I>(&mut self, x: I)
+    where
+        I: Into<Self::ExprEF>,
+    {
+        let x: PackedChallenge<SC> = x.into();
+        let alpha_power = self.alpha_powers[self.constraint_index];
+        self.accumulator += PackedChallenge::<SC>::from_f(alpha_power) * x;
+        self.constraint_index += 1;
+    }
+}
+
+impl<SC: StarkGenericConfig> AirBuilderWithExtensionPublicValues
+    for ProverConstraintFolder<'_, SC>
+{
+    type PublicVarEF = PackedChallenge<SC>;
+
+    #[inline]
+    fn extension_public_values(&self) -> &[Self::PublicVarEF] {
+        self.extension_public_values
+    }
+}
+
+impl<'a, SC: StarkGenericConfig> ExtensionMainBuilder for ProverConstraintFolder<'a, SC> {
+    type MEF = RowMajorMatrixView<'a, PackedChallenge<SC>>;
+
+    #[inline]
+    fn main_ext(&self) -> Self::MEF {
+        self.main_ext
+    }
+}
+
+impl<'a, SC: StarkGenericConfig> PermutationAirBuilder for ProverConstraintFolder<'a, SC> {
+    type MP = RowMajorMatrixView<'a, PackedChallenge<SC>>;
+    type RandomVar = PackedChallenge<SC>;
+
+    #[inline]
+    fn permutation(&self) -> Self::MP {
+        self.permutation
+    }
+
+    #[inline]
+    fn permutation_randomness(&self) -> &[Self::RandomVar] {
+        self.permutation_challenges
+    }
+}
+
 impl<'a, SC: StarkGenericConfig> AirBuilder for VerifierConstraintFolder<'a, SC> {
     type F = Val<SC>;
     type Expr = SC::Challenge;
     type Var = SC::Challenge;
-    type M = ViewPair<'a, SC::Challenge>;
+    type M = RowMajorMatrixView<'a, SC::Challenge>;
 
     fn main(&self) -> Self::M {
         self.main
@@ -99,11 +195,11 @@ impl<'a, SC: StarkGenericConfig> AirBuilder for VerifierConstraintFolder<'a, SC>
     }
 
     fn is_transition_window(&self, size: usize) -> Self::Expr {
-        if size == 2 {
-            self.is_transition
-        } else {
-            panic!("uni-stark only supports a window size of 2")
-        }
+        select_transition_window(self.is_transition_windows, size)
+    }
+
+    fn row_selector(&self, selector: RowSelector) -> Self::Expr {
+        select_row(self.row_selectors, selector)
     }
 
     fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
@@ -120,3 +216,72 @@ impl<'a, SC: StarkGenericConfig> AirBuilderWithPublicValues for VerifierConstrai
         self.public_values
     }
 }
+
+impl<SC: StarkGenericConfig> PeriodicAirBuilder for VerifierConstraintFolder<'_, SC> {
+    type PeriodicVar = SC::Challenge;
+
+    fn periodic_values(&self) -> &[Self::PeriodicVar] {
+        self.periodic_values
+    }
+}
+
+impl<SC: StarkGenericConfig> PairBuilder for VerifierConstraintFolder<'_, SC> {
+    fn preprocessed(&self) -> Self::M {
+        self.preprocessed
+    }
+}
+
+impl<SC: StarkGenericConfig> ExtensionBuilder for VerifierConstraintFolder<'_, SC> {
+    type EF = SC::Challenge;
+    type ExprEF = SC::Challenge;
+    type VarEF = SC::Challenge;
+
+    fn assert_zero_ext<I>(&mut self, x: I)
+    where
+        I: Into<Self::ExprEF>,
+    {
+        self.assert_zero(x);
+    }
+}
+
+impl<SC: StarkGenericConfig> AirBuilderWithExtensionPublicValues
+    for VerifierConstraintFolder<'_, SC>
+{
+    type PublicVarEF = SC::Challenge;
+
+    fn extension_public_values(&self) -> &[Self::PublicVarEF] {
+        self.extension_public_values
+    }
+}
+
+impl<'a, SC: StarkGenericConfig> ExtensionMainBuilder for VerifierConstraintFolder<'a, SC> {
+    type MEF = RowMajorMatrixView<'a, SC::Challenge>;
+
+    fn main_ext(&self) -> Self::MEF {
+        self.main_ext
+    }
+}
+
+impl<'a, SC: StarkGenericConfig> PermutationAirBuilder for VerifierConstraintFolder<'a, SC> {
+    type MP = ViewPair<'a, SC::Challenge>;
+    type RandomVar = SC::Challenge;
+
+    fn permutation(&self) -> Self::MP {
+        self.permutation
+    }
+
+    fn permutation_randomness(&self) -> &[Self::RandomVar] {
+        self.permutation_challenges
+    }
+}
+
+/// Pick the transition selector for a window of `size` rows from those for windows of 2, 3, ...
+/// rows.
+#[inline]
+fn select_transition_window<T: Copy>(is_transition_windows: &[T], size: usize) -> T {
+    assert!(
+        (2..is_transition_windows.len() + 2).contains(&size),
+        "no transition selector for a window of {size} rows; the AIR's row offsets are too short"
+    );
+    is_transition_windows[size - 2]
+}
@@ -0,0 +1,59 @@
+use alloc::vec::Vec;
+
+use p3_challenger::{CanObserve, FieldChallenger};
+use p3_field::FieldAlgebra;
+use serde::{Deserialize, Serialize};
+
+use crate::{StarkGenericConfig, Val};
+
+/// The public inputs of a single proof: the values the AIR's constraints may refer to, and any
+/// other data the proof should be bound to.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Instance<SC: StarkGenericConfig> {
+    /// The base field public values, read through `AirBuilderWithPublicValues`.
+    pub public_values: Vec<Val<SC>>,
+    /// The extension field public values, read through `AirBuilderWithExtensionPublicValues`,
+    /// such as challenges passed in from an outer protocol.
+    pub extension_public_values: Vec<SC::Challenge>,
+    /// Records which the constraints do not refer to, but which are observed before the trace
+    /// commitment, so that every challenge depends on them.
+    pub data: Vec<Vec<Val<SC>>>,
+}
+
+impl<SC: StarkGenericConfig> Instance<SC> {
+    /// An instance with only base field public values.
+    pub const fn new(public_values: Vec<Val<SC>>) -> Self {
+        Self {
+            public_values,
+            extension_public_values: Vec::new(),
+            data: Vec::new(),
+        }
+    }
+}
+
+impl<SC: StarkGenericConfig> Clone for Instance<SC> {
+    fn clone(&self) -> Self {
+        Self {
+            public_values: self.public_values.clone(),
+            extension_public_values: self.extension_public_values.clone(),
+            data: self.data.clone(),
+        }
+    }
+}
+
+/// Absorb the parts of `instance` which precede the trace commitment into the challenger: each
+/// record of its data prefixed by its length, then its extension field public values.
+pub(crate) fn observe_instance<SC: StarkGenericConfig>(
+    challenger: &mut SC::Challenger,
+    instance: &Instance<SC>,
+) {
+    challenger.observe(Val::<SC>::from_canonical_usize(instance.data.len()));
+    for record in &instance.data {
+        challenger.observe(Val::<SC>::from_canonical_usize(record.len()));
+        challenger.observe_slice(record);
+    }
+    for &value in &instance.extension_public_values {
+        challenger.observe_ext_element(value);
+    }
+}
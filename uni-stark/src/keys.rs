@@ -0,0 +1,404 @@
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use p3_air::{Air, Interaction, PairCol, RowSelector, VirtualPairCol};
+use p3_challenger::CanObserve;
+use p3_field::{Field, FieldAlgebra, PrimeField64};
+use p3_keccak::Keccak256Hash;
+use p3_symmetric::CryptographicHasher;
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use crate::symbolic_builder::log_quotient_degree;
+use crate::{
+    get_symbolic_constraints, is_zk, num_permutation_constraints, setup_preprocessed, CompiledAir,
+    Entry, PreprocessedProverData, PreprocessedVerifierKey, StarkGenericConfig, SymbolicAirBuilder,
+    SymbolicExpression, Val,
+};
+
+/// Everything the prover needs to know about an AIR, computed once by `setup_keys`.
+pub struct StarkProvingKey<SC: StarkGenericConfig> {
+    pub(crate) width: usize,
+    pub(crate) extension_width: usize,
+    pub(crate) row_selectors: Vec<RowSelector>,
+    pub(crate) periodic_columns: Vec<Vec<Val<SC>>>,
+    pub(crate) constraints: Vec<SymbolicExpression<Val<SC>>>,
+    pub(crate) constraint_count: usize,
+    pub(crate) compiled: Option<CompiledAir<Val<SC>>>,
+    pub(crate) preprocessed: Option<PreprocessedProverData<SC>>,
+    pub(crate) verifying_key: StarkVerifyingKey<SC>,
+}
+
+impl<SC: StarkGenericConfig> StarkProvingKey<SC> {
+    /// The AIR's constraints, evaluated symbolically.
+    pub fn constraints(&self) -> &[SymbolicExpression<Val<SC>>] {
+        &self.constraints
+    }
+
+    /// The total number of constraints, including those of the LogUp argument.
+    pub const fn constraint_count(&self) -> usize {
+        self.constraint_count
+    }
+
+    /// Compile the AIR's constraints, so that the prover evaluates them on the quotient domain by
+    /// interpreting the resulting `CompiledAir` rather than by running `Air::eval`.
+    ///
+    /// This does not affect the proof, so the verifier is unaware of it.
+    pub fn compile_constraints(&mut self) {
+        self.compiled = Some(CompiledAir::from_constraints(
+            &self.constraints,
+            self.width,
+            self.extension_width,
+            self.preprocessed
+                .as_ref()
+                .map_or(0, PreprocessedProverData::width),
+            self.verifying_key.num_public_values,
+            self.verifying_key.num_extension_public_values,
+            self.verifying_key.row_offsets.clone(),
+            self.row_selectors.clone(),
+            self.periodic_columns.clone(),
+        ));
+    }
+
+    /// The compiled constraints, if `compile_constraints` has been called.
+    pub const fn compiled_constraints(&self) -> Option<&CompiledAir<Val<SC>>> {
+        self.compiled.as_ref()
+    }
+
+    pub const fn preprocessed(&self) -> Option<&PreprocessedProverData<SC>> {
+        self.preprocessed.as_ref()
+    }
+
+    pub const fn verifying_key(&self) -> &StarkVerifyingKey<SC> {
+        &self.verifying_key
+    }
+}
+
+/// Everything the verifier needs to know about an AIR, computed once by `setup_keys`.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct StarkVerifyingKey<SC: StarkGenericConfig> {
+    pub(crate) row_offsets: Vec<usize>,
+    pub(crate) num_public_values: usize,
+    pub(crate) num_extension_public_values: usize,
+    pub(crate) log_quotient_degree: usize,
+    pub(crate) preprocessed: Option<PreprocessedVerifierKey<SC>>,
+    pub(crate) fingerprint: [u8; 32],
+}
+
+impl<SC: StarkGenericConfig> StarkVerifyingKey<SC> {
+    /// The offsets of the rows in the AIR's evaluation window.
+    pub fn row_offsets(&self) -> &[usize] {
+        &self.row_offsets
+    }
+
+    pub const fn num_public_values(&self) -> usize {
+        self.num_public_values
+    }
+
+    /// The number of extension field public values, from `BaseAir::num_extension_public_values`.
+    pub const fn num_extension_public_values(&self) -> usize {
+        self.num_extension_public_values
+    }
+
+    /// The log2 of the number of quotient chunks.
+    pub const fn log_quotient_degree(&self) -> usize {
+        self.log_quotient_degree
+    }
+
+    pub const fn preprocessed(&self) -> Option<&PreprocessedVerifierKey<SC>> {
+        self.preprocessed.as_ref()
+    }
+
+    /// The Keccak-256 hash of a canonical encoding of the AIR: its dimensions, its constraint DAG
+    /// and its interactions, as a sequence of field elements hashed as little-endian `u64`s of
+    /// their canonical values.
+    ///
+    /// The prover and verifier absorb this into the challenger before anything else, so that a
+    /// proof for one AIR cannot be replayed against another.
+    pub const fn fingerprint(&self) -> &[u8; 32] {
+        &self.fingerprint
+    }
+}
+
+impl<SC: StarkGenericConfig> Clone for StarkVerifyingKey<SC> {
+    fn clone(&self) -> Self {
+        Self {
+            row_offsets: self.row_offsets.clone(),
+            num_public_values: self.num_public_values,
+            num_extension_public_values: self.num_extension_public_values,
+            log_quotient_degree: self.log_quotient_degree,
+            preprocessed: self.preprocessed.clone(),
+            fingerprint: self.fingerprint,
+        }
+    }
+}
+
+/// Compute the proving and verifying keys of `air`, committing to its preprocessed trace if it
+/// has one.
+#[instrument(skip_all)]
+pub fn setup_keys<SC, A>(
+    config: &SC,
+    air: &A,
+    num_public_values: usize,
+) -> (StarkProvingKey<SC>, StarkVerifyingKey<SC>)
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + ?Sized,
+{
+    let (preprocessed, preprocessed_vk) = setup_preprocessed(config, air).unzip();
+    let preprocessed_width = preprocessed_vk
+        .as_ref()
+        .map_or(0, PreprocessedVerifierKey::width);
+
+    let row_offsets = air.row_offsets();
+    let row_selectors = air.row_selectors();
+    let periodic_columns = air.periodic_columns();
+    let sends = air.sends();
+    let receives = air.receives();
+    let constraints =
+        get_symbolic_constraints::<Val<SC>, A>(air, preprocessed_width, num_public_values);
+    let constraint_count =
+        constraints.len() + num_permutation_constraints(sends.len() + receives.len());
+    // The LogUp constraints have degree 2, which the quotient degree is always padded to.
+    let constraint_degree = constraints
+        .iter()
+        .map(SymbolicExpression::constraint_degree)
+        .max()
+        .unwrap_or(0);
+    let log_quotient_degree = log_quotient_degree(constraint_degree, is_zk::<SC>());
+
+    let mut encoding = [
+        air.width(),
+        air.extension_width(),
+        preprocessed_width,
+        num_public_values,
+        log_quotient_degree,
+        air.num_extension_public_values(),
+    ]
+    .map(Val::<SC>::from_canonical_usize)
+    .to_vec();
+    encoding.push(Val::<SC>::from_canonical_usize(row_offsets.len()));
+    encoding.extend(
+        row_offsets
+            .iter()
+            .map(|&o| Val::<SC>::from_canonical_usize(o)),
+    );
+    encoding.push(Val::<SC>::from_canonical_usize(row_selectors.len()));
+    for &selector in &row_selectors {
+        encoding.extend(
+            encode_row_selector(selector)
+                .into_iter()
+                .map(Val::<SC>::from_canonical_usize),
+        );
+    }
+    encoding.push(Val::<SC>::from_canonical_usize(periodic_columns.len()));
+    for column in &periodic_columns {
+        encoding.push(Val::<SC>::from_canonical_usize(column.len()));
+        encoding.extend_from_slice(column);
+    }
+    encode_constraints(&constraints, &mut encoding);
+    for interactions in [&sends, &receives] {
+        encode_interactions(interactions, &mut encoding);
+    }
+    let fingerprint = Keccak256Hash.hash_iter(
+        encoding
+            .iter()
+            .flat_map(|x| x.as_canonical_u64().to_le_bytes()),
+    );
+
+    let verifying_key = StarkVerifyingKey {
+        row_offsets,
+        num_public_values,
+        num_extension_public_values: air.num_extension_public_values(),
+        log_quotient_degree,
+        preprocessed: preprocessed_vk,
+        fingerprint,
+    };
+    let proving_key = StarkProvingKey {
+        width: air.width(),
+        extension_width: air.extension_width(),
+        row_selectors,
+        periodic_columns,
+        constraints,
+        constraint_count,
+        compiled: None,
+        preprocessed,
+        verifying_key: verifying_key.clone(),
+    };
+    (proving_key, verifying_key)
+}
+
+/// Absorb the fingerprint of an AIR into the challenger, a byte at a time.
+pub(crate) fn observe_fingerprint<SC: StarkGenericConfig>(
+    challenger: &mut SC::Challenger,
+    fingerprint: &[u8; 32],
+) {
+    challenger.observe_slice(&fingerprint.map(Val::<SC>::from_canonical_u8));
+}
+
+const TAG_VARIABLE: usize = 0;
+const TAG_IS_FIRST_ROW: usize = 1;
+const TAG_IS_LAST_ROW: usize = 2;
+const TAG_IS_TRANSITION: usize = 3;
+const TAG_CONSTANT: usize = 4;
+const TAG_ADD: usize = 5;
+const TAG_SUB: usize = 6;
+const TAG_NEG: usize = 7;
+const TAG_MUL: usize = 8;
+const TAG_IS_TRANSITION_WINDOW: usize = 9;
+const TAG_ROW_SELECTOR: usize = 10;
+
+/// An encoding of `selector`: a tag followed by its row, or its period and offset.
+fn encode_row_selector(selector: RowSelector) -> Vec<usize> {
+    match selector {
+        RowSelector::Row(row) => vec![0, row],
+        RowSelector::Periodic { period, offset } => vec![1, period, offset],
+    }
+}
+
+/// Append an encoding of the DAG of `constraints` to `out`.
+///
+/// Nodes are numbered in the order in which a post-order traversal first reaches them, and equal
+/// subexpressions are encoded once and referred to by number, whether or not they are shared in
+/// memory. The encoding is a count of nodes, followed by each node's tag and operands, followed by
+/// a count of constraints and the number of each constraint's root node.
+fn encode_constraints<F: Field>(constraints: &[SymbolicExpression<F>], out: &mut Vec<F>) {
+    let mut encoder = DagEncoder {
+        nodes: Vec::new(),
+        visited: BTreeMap::new(),
+        numbers: BTreeMap::new(),
+        constants: Vec::new(),
+    };
+    let roots: Vec<usize> = constraints
+        .iter()
+        .map(|constraint| encoder.encode_shared(constraint))
+        .collect();
+
+    out.push(F::from_canonical_usize(encoder.numbers.len()));
+    out.extend(encoder.nodes);
+    out.push(F::from_canonical_usize(roots.len()));
+    out.extend(roots.into_iter().map(F::from_canonical_usize));
+}
+
+struct DagEncoder<F> {
+    nodes: Vec<F>,
+    /// The numbers of the subexpressions encoded so far, by address, so that a subexpression
+    /// shared in memory is traversed once. The constraints outlive the encoder, so addresses are
+    /// not reused.
+    visited: BTreeMap<*const SymbolicExpression<F>, usize>,
+    /// The number of each node encoded so far, by its tag and operands, where a constant operand
+    /// is its position in `constants`.
+    numbers: BTreeMap<(usize, Vec<usize>), usize>,
+    constants: Vec<F>,
+}
+
+impl<F: Field> DagEncoder<F> {
+    fn encode_shared(&mut self, expr: &SymbolicExpression<F>) -> usize {
+        let key = expr as *const _;
+        if let Some(&node) = self.visited.get(&key) {
+            return node;
+        }
+        let node = self.encode(expr);
+        self.visited.insert(key, node);
+        node
+    }
+
+    fn encode(&mut self, expr: &SymbolicExpression<F>) -> usize {
+        let (tag, operands): (_, Vec<usize>) = match expr {
+            SymbolicExpression::Variable(v) => {
+                let (entry, offset) = match v.entry {
+                    Entry::Preprocessed { offset } => (0, offset),
+                    Entry::Main { offset } => (1, offset),
+                    Entry::Permutation { offset } => (2, offset),
+                    Entry::Public => (3, 0),
+                    Entry::Challenge => (4, 0),
+                    Entry::MainExtension { offset } => (5, offset),
+                    Entry::ExtensionPublic => (6, 0),
+                    Entry::Periodic => (7, 0),
+                };
+                (TAG_VARIABLE, vec![entry, offset, v.index])
+            }
+            SymbolicExpression::IsFirstRow => (TAG_IS_FIRST_ROW, Vec::new()),
+            SymbolicExpression::IsLastRow => (TAG_IS_LAST_ROW, Vec::new()),
+            SymbolicExpression::IsTransition => (TAG_IS_TRANSITION, Vec::new()),
+            SymbolicExpression::IsTransitionWindow(size) => (TAG_IS_TRANSITION_WINDOW, vec![*size]),
+            SymbolicExpression::RowSelector(selector) => {
+                (TAG_ROW_SELECTOR, encode_row_selector(*selector))
+            }
+            SymbolicExpression::Constant(c) => (TAG_CONSTANT, vec![self.constant(*c)]),
+            SymbolicExpression::Add { x, y, .. } => (TAG_ADD, self.encode_commutative(x, y)),
+            SymbolicExpression::Sub { x, y, .. } => {
+                (TAG_SUB, vec![self.encode_shared(x), self.encode_shared(y)])
+            }
+            SymbolicExpression::Neg { x, .. } => (TAG_NEG, vec![self.encode_shared(x)]),
+            SymbolicExpression::Mul { x, y, .. } => (TAG_MUL, self.encode_commutative(x, y)),
+        };
+        if let Some(&node) = self.numbers.get(&(tag, operands.clone())) {
+            return node;
+        }
+        self.nodes.push(F::from_canonical_usize(tag));
+        if tag == TAG_CONSTANT {
+            self.nodes.push(self.constants[operands[0]]);
+        } else {
+            self.nodes.extend(
+                operands
+                    .iter()
+                    .map(|&operand| F::from_canonical_usize(operand)),
+            );
+        }
+        let node = self.numbers.len();
+        self.numbers.insert((tag, operands), node);
+        node
+    }
+
+    /// The operands of a commutative operation, in order of number, so that `x + y` and `y + x`
+    /// are the same node.
+    fn encode_commutative(
+        &mut self,
+        x: &SymbolicExpression<F>,
+        y: &SymbolicExpression<F>,
+    ) -> Vec<usize> {
+        let (x, y) = (self.encode_shared(x), self.encode_shared(y));
+        vec![x.min(y), x.max(y)]
+    }
+
+    /// The position of `constant` in the constants encoded so far, adding it if it is new.
+    fn constant(&mut self, constant: F) -> usize {
+        self.constants
+            .iter()
+            .position(|&c| c == constant)
+            .unwrap_or_else(|| {
+                self.constants.push(constant);
+                self.constants.len() - 1
+            })
+    }
+}
+
+/// Append an encoding of `interactions` to `out`.
+fn encode_interactions<F: Field>(interactions: &[Interaction<F>], out: &mut Vec<F>) {
+    out.push(F::from_canonical_usize(interactions.len()));
+    for interaction in interactions {
+        out.push(F::from_canonical_usize(interaction.argument_index));
+        out.push(F::from_canonical_usize(interaction.fields.len()));
+        for field in &interaction.fields {
+            encode_virtual_column(field, out);
+        }
+        encode_virtual_column(&interaction.count, out);
+    }
+}
+
+fn encode_virtual_column<F: Field>(column: &VirtualPairCol<F>, out: &mut Vec<F>) {
+    out.push(F::from_canonical_usize(column.column_weights().len()));
+    for &(col, weight) in column.column_weights() {
+        let (kind, index) = match col {
+            PairCol::Preprocessed(index) => (0, index),
+            PairCol::Main(index) => (1, index),
+        };
+        out.push(F::from_canonical_usize(kind));
+        out.push(F::from_canonical_usize(index));
+        out.push(weight);
+    }
+    out.push(column.constant_term());
+}
@@ -4,27 +4,46 @@
 
 extern crate alloc;
 
+mod air_stats;
+mod batch_prover;
+mod batch_verifier;
+mod check_constraints;
+mod compiled_air;
 mod config;
+mod data_air;
 mod folder;
+mod instance;
+mod keys;
+mod permutation;
+mod preprocessed;
 mod proof;
 mod prover;
 mod symbolic_builder;
 mod symbolic_expression;
 mod symbolic_variable;
+mod tables;
 mod verifier;
+mod window;
 mod zerofier_coset;
 
-#[cfg(debug_assertions)]
-mod check_constraints;
-
-#[cfg(debug_assertions)]
+pub use air_stats::*;
+pub use batch_prover::*;
+pub use batch_verifier::*;
 pub use check_constraints::*;
+pub use compiled_air::*;
 pub use config::*;
+pub use data_air::*;
 pub use folder::*;
+pub use instance::*;
+pub use keys::*;
+pub use permutation::*;
+pub use preprocessed::*;
 pub use proof::*;
 pub use prover::*;
 pub use symbolic_builder::*;
 pub use symbolic_expression::*;
 pub use symbolic_variable::*;
+pub use tables::*;
 pub use verifier::*;
+pub use window::*;
 pub use zerofier_coset::*;
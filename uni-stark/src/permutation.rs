@@ -0,0 +1,166 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use itertools::{chain, Itertools};
+use p3_air::{ExtensionBuilder, Interaction, PairBuilder, PermutationAirBuilder};
+use p3_field::{
+    batch_multiplicative_inverse, ExtensionField, Field, FieldAlgebra, FieldExtensionAlgebra,
+};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use tracing::instrument;
+
+/// The number of challenges used by the LogUp argument: `alpha` shifts the denominators, and
+/// `beta` combines the fields of an interaction into a single fingerprint.
+pub const NUM_PERMUTATION_CHALLENGES: usize = 2;
+
+/// The number of constraints added by `eval_permutation_constraints`.
+pub const fn num_permutation_constraints(num_interactions: usize) -> usize {
+    if num_interactions == 0 {
+        0
+    } else {
+        num_interactions + 3
+    }
+}
+
+/// Generate the LogUp trace for the given interactions.
+///
+/// The trace has one column per interaction (sends first, then receives), holding
+/// `±count / (alpha - fingerprint)` on each row, where sends are positive and receives negative.
+/// The final column is the running sum of all interaction columns up to and including the row,
+/// so its value on the last row is the cumulative sum of the table.
+#[instrument(name = "generate permutation trace", skip_all)]
+pub fn generate_permutation_trace<F: Field, EF: ExtensionField<F>>(
+    preprocessed: Option<&RowMajorMatrix<F>>,
+    main: &RowMajorMatrix<F>,
+    sends: &[Interaction<F>],
+    receives: &[Interaction<F>],
+    random_elements: &[EF],
+) -> RowMajorMatrix<EF> {
+    let [alpha, beta] = random_elements.try_into().unwrap();
+    let height = main.height();
+    let num_interactions = sends.len() + receives.len();
+    let interactions = chain(sends, receives).collect_vec();
+
+    let mut denominators = Vec::with_capacity(height * num_interactions);
+    let mut counts = Vec::with_capacity(height * num_interactions);
+    for row in 0..height {
+        let preprocessed_row = preprocessed.map_or(vec![], |prep| prep.row(row).collect_vec());
+        let main_row = main.row(row).collect_vec();
+        for (i, interaction) in interactions.iter().enumerate() {
+            let fields = interaction
+                .fields
+                .iter()
+                .map(|field| field.apply::<F, F>(&preprocessed_row, &main_row));
+            denominators.push(alpha - fingerprint(interaction.argument_index, fields, beta));
+            let count: F = interaction.count.apply(&preprocessed_row, &main_row);
+            counts.push(if i < sends.len() { count } else { -count });
+        }
+    }
+    let inverses = batch_multiplicative_inverse(&denominators);
+
+    let width = num_interactions + 1;
+    let mut values = Vec::with_capacity(height * width);
+    let mut running_sum = EF::ZERO;
+    for (row_inverses, row_counts) in inverses
+        .chunks_exact(num_interactions)
+        .zip(counts.chunks_exact(num_interactions))
+    {
+        for (&inverse, &count) in row_inverses.iter().zip(row_counts) {
+            let value = inverse * count;
+            running_sum += value;
+            values.push(value);
+        }
+        values.push(running_sum);
+    }
+    RowMajorMatrix::new(values, width)
+}
+
+/// Combine the fields of an interaction on the bus `argument_index` into a single fingerprint,
+/// `argument_index + sum_j beta^(j + 1) field_j`.
+fn fingerprint<F, EF, I>(argument_index: usize, fields: I, beta: EF) -> EF
+where
+    F: FieldAlgebra,
+    EF: FieldExtensionAlgebra<F>,
+    I: IntoIterator<Item = F>,
+{
+    let mut result = EF::from_base(F::from_canonical_usize(argument_index));
+    let mut beta_power = beta.clone();
+    for field in fields {
+        result += beta_power.clone() * field;
+        beta_power *= beta.clone();
+    }
+    result
+}
+
+/// Evaluate the LogUp constraints for the given interactions, which must match those the
+/// permutation trace was generated from.
+///
+/// `cumulative_sum` is the claimed value of the running sum on the last row.
+pub fn eval_permutation_constraints<AB>(
+    builder: &mut AB,
+    sends: &[Interaction<AB::F>],
+    receives: &[Interaction<AB::F>],
+    cumulative_sum: AB::ExprEF,
+) where
+    AB: PermutationAirBuilder + PairBuilder,
+    AB::F: Field,
+{
+    let num_interactions = sends.len() + receives.len();
+    if num_interactions == 0 {
+        return;
+    }
+
+    let randomness = builder.permutation_randomness();
+    let alpha: AB::ExprEF = randomness[0].into();
+    let beta: AB::ExprEF = randomness[1].into();
+
+    let preprocessed = builder.preprocessed();
+    let main = builder.main();
+    let perm = builder.permutation();
+
+    let preprocessed_local = preprocessed.row_slice(0);
+    let main_local = main.row_slice(0);
+    let perm_local = perm.row_slice(0);
+    let perm_next = perm.row_slice(1);
+    let perm_local: &[AB::VarEF] = &perm_local;
+    let perm_next: &[AB::VarEF] = &perm_next;
+
+    for (i, interaction) in chain(sends, receives).enumerate() {
+        let fields = interaction
+            .fields
+            .iter()
+            .map(|field| field.apply::<AB::Expr, AB::Var>(&preprocessed_local, &main_local));
+        let denominator =
+            alpha.clone() - fingerprint(interaction.argument_index, fields, beta.clone());
+        let count: AB::Expr = interaction
+            .count
+            .apply::<AB::Expr, AB::Var>(&preprocessed_local, &main_local);
+        let count = if i < sends.len() { count } else { -count };
+        builder.assert_eq_ext(
+            perm_local[i].into() * denominator,
+            AB::ExprEF::from_base(count),
+        );
+    }
+
+    let sum_local: AB::ExprEF = perm_local[..num_interactions]
+        .iter()
+        .map(|&value| value.into())
+        .sum();
+    let sum_next: AB::ExprEF = perm_next[..num_interactions]
+        .iter()
+        .map(|&value| value.into())
+        .sum();
+    let phi_local: AB::ExprEF = perm_local[num_interactions].into();
+    let phi_next: AB::ExprEF = perm_next[num_interactions].into();
+
+    builder
+        .when_first_row()
+        .assert_eq_ext(phi_local.clone(), sum_local);
+    builder
+        .when_transition()
+        .assert_eq_ext(phi_next - phi_local.clone(), sum_next);
+    builder
+        .when_last_row()
+        .assert_eq_ext(phi_local, cumulative_sum);
+}
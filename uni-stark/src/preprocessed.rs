@@ -0,0 +1,114 @@
+use alloc::vec;
+
+use p3_air::BaseAir;
+use p3_commit::Pcs;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_util::log2_strict_usize;
+use serde::{Deserialize, Serialize};
+use tracing::{info_span, instrument};
+
+use crate::{Com, PcsProverData, StarkGenericConfig, Val};
+
+/// The prover's half of a preprocessed trace setup: the commitment to the fixed columns of an AIR,
+/// along with the PCS data needed to open them.
+///
+/// This is computed once per AIR (and trace height) by `setup_preprocessed`, and can be reused
+/// across any number of proofs.
+pub struct PreprocessedProverData<SC: StarkGenericConfig> {
+    pub(crate) width: usize,
+    pub(crate) degree_bits: usize,
+    pub(crate) commitment: Com<SC>,
+    pub(crate) prover_data: PcsProverData<SC>,
+    /// The preprocessed trace itself, which the LogUp trace of an AIR with interactions may read.
+    pub(crate) trace: RowMajorMatrix<Val<SC>>,
+}
+
+impl<SC: StarkGenericConfig> PreprocessedProverData<SC> {
+    /// The number of preprocessed columns.
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The log2 of the preprocessed trace height, which must match the main trace height.
+    pub const fn degree_bits(&self) -> usize {
+        self.degree_bits
+    }
+
+    /// The verifier's view of this setup.
+    pub fn verifier_key(&self) -> PreprocessedVerifierKey<SC> {
+        PreprocessedVerifierKey {
+            width: self.width,
+            degree_bits: self.degree_bits,
+            commitment: self.commitment.clone(),
+        }
+    }
+}
+
+/// The verifier's half of a preprocessed trace setup.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct PreprocessedVerifierKey<SC: StarkGenericConfig> {
+    pub(crate) width: usize,
+    pub(crate) degree_bits: usize,
+    pub(crate) commitment: Com<SC>,
+}
+
+impl<SC: StarkGenericConfig> PreprocessedVerifierKey<SC> {
+    /// The number of preprocessed columns.
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The log2 of the preprocessed trace height, which must match the main trace height.
+    pub const fn degree_bits(&self) -> usize {
+        self.degree_bits
+    }
+
+    pub const fn commitment(&self) -> &Com<SC> {
+        &self.commitment
+    }
+}
+
+impl<SC: StarkGenericConfig> Clone for PreprocessedVerifierKey<SC> {
+    fn clone(&self) -> Self {
+        Self {
+            width: self.width,
+            degree_bits: self.degree_bits,
+            commitment: self.commitment.clone(),
+        }
+    }
+}
+
+/// Commit to the preprocessed trace of `air`, if it has one.
+///
+/// Returns `None` if `air.preprocessed_trace()` is `None`.
+#[instrument(skip_all)]
+pub fn setup_preprocessed<SC, A>(
+    config: &SC,
+    air: &A,
+) -> Option<(PreprocessedProverData<SC>, PreprocessedVerifierKey<SC>)>
+where
+    SC: StarkGenericConfig,
+    A: BaseAir<Val<SC>> + ?Sized,
+{
+    let preprocessed = air.preprocessed_trace()?;
+    let width = preprocessed.width();
+    let degree = preprocessed.height();
+    let degree_bits = log2_strict_usize(degree);
+
+    let pcs = config.pcs();
+    let domain = pcs.natural_domain_for_degree(degree);
+    let (commitment, prover_data) = info_span!("commit to preprocessed trace")
+        .in_scope(|| pcs.commit(vec![(domain, preprocessed.clone())]));
+
+    let prover_data = PreprocessedProverData {
+        width,
+        degree_bits,
+        commitment,
+        prover_data,
+        trace: preprocessed,
+    };
+    let verifier_key = prover_data.verifier_key();
+    Some((prover_data, verifier_key))
+}
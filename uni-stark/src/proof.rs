@@ -1,15 +1,12 @@
+use alloc::string::String;
 use alloc::vec::Vec;
 
-use p3_commit::Pcs;
+use p3_commit::{decode_proof, encode_proof, encoded_size_by_component, Pcs, ProofDecodeError};
 use serde::{Deserialize, Serialize};
 
-use crate::StarkGenericConfig;
+use crate::{Com, PowWitness, StarkGenericConfig};
 
-type Com<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
-    <SC as StarkGenericConfig>::Challenge,
-    <SC as StarkGenericConfig>::Challenger,
->>::Commitment;
-type PcsProof<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
+pub type PcsProof<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
     <SC as StarkGenericConfig>::Challenge,
     <SC as StarkGenericConfig>::Challenger,
 >>::Proof;
@@ -18,20 +15,96 @@ type PcsProof<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
 #[serde(bound = "")]
 pub struct Proof<SC: StarkGenericConfig> {
     pub(crate) commitments: Commitments<Com<SC>>,
+    /// The proof of work witness ground before `zeta` was sampled, if the config asks for one.
+    pub(crate) zeta_pow_witness: Option<PowWitness<SC>>,
     pub(crate) opened_values: OpenedValues<SC::Challenge>,
     pub(crate) opening_proof: PcsProof<SC>,
     pub(crate) degree_bits: usize,
 }
 
+impl<SC: StarkGenericConfig> Proof<SC> {
+    pub const fn opened_values(&self) -> &OpenedValues<SC::Challenge> {
+        &self.opened_values
+    }
+
+    /// Encode the proof in the compact, canonical encoding of `p3_commit::encode_proof`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encode_proof(self)
+    }
+
+    /// Decode a proof encoded by `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofDecodeError> {
+        decode_proof(bytes)
+    }
+
+    /// The number of bytes each component of the proof takes in its encoding, down to the fields
+    /// of the opening proof, such as `opening_proof.query_proofs` for a FRI based PCS.
+    pub fn size_by_component(&self) -> Vec<(String, usize)> {
+        encoded_size_by_component(self, 2)
+    }
+}
+
+/// A proof that each of several traces satisfies the same AIR, generated by `prove_batch`.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct BatchProof<SC: StarkGenericConfig> {
+    /// Commitments shared by all instances, each covering the matrices of every instance.
+    pub(crate) commitments: Commitments<Com<SC>>,
+    /// The proof of work witness ground before `zeta` was sampled, if the config asks for one.
+    pub(crate) zeta_pow_witness: Option<PowWitness<SC>>,
+    /// The opened values of each instance, in the order the traces were given.
+    pub(crate) opened_values: Vec<OpenedValues<SC::Challenge>>,
+    pub(crate) opening_proof: PcsProof<SC>,
+    /// The log2 of each instance's trace height.
+    pub(crate) degree_bits: Vec<usize>,
+}
+
+impl<SC: StarkGenericConfig> BatchProof<SC> {
+    /// The opened values of each instance.
+    pub fn opened_values(&self) -> &[OpenedValues<SC::Challenge>] {
+        &self.opened_values
+    }
+
+    /// The log2 of each instance's trace height.
+    pub fn degree_bits(&self) -> &[usize] {
+        &self.degree_bits
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Commitments<Com> {
     pub(crate) trace: Com,
+    /// Commitment to the LogUp trace, present only if the AIR has interactions.
+    pub(crate) permutation: Option<Com>,
     pub(crate) quotient_chunks: Com,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OpenedValues<Challenge> {
-    pub(crate) trace_local: Vec<Challenge>,
-    pub(crate) trace_next: Vec<Challenge>,
+    /// Openings of the preprocessed trace at each of the AIR's row offsets; empty if the AIR has
+    /// no preprocessed columns.
+    pub(crate) preprocessed: Vec<Vec<Challenge>>,
+    /// Openings of the main trace at each of the AIR's row offsets. Each row holds the base field
+    /// columns, followed by the extension field columns flattened to base field columns.
+    pub(crate) trace: Vec<Vec<Challenge>>,
+    /// Openings of the LogUp trace, flattened to base field columns; empty if the AIR has no
+    /// interactions.
+    pub(crate) permutation_local: Vec<Challenge>,
+    pub(crate) permutation_next: Vec<Challenge>,
     pub(crate) quotient_chunks: Vec<Vec<Challenge>>,
+    /// In zero-knowledge mode, the opening of the random polynomial added to the quotient, flattened
+    /// to base field columns; empty otherwise.
+    pub(crate) quotient_randomizer: Vec<Challenge>,
+}
+
+impl<Challenge> OpenedValues<Challenge> {
+    /// The openings of the main trace at `zeta` shifted by each of the AIR's row offsets.
+    pub fn trace(&self) -> &[Vec<Challenge>] {
+        &self.trace
+    }
+
+    /// The openings of each quotient chunk at `zeta`, flattened to base field columns.
+    pub fn quotient_chunks(&self) -> &[Vec<Challenge>] {
+        &self.quotient_chunks
+    }
 }
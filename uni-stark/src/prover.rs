@@ -1,27 +1,40 @@
 use alloc::vec;
 use alloc::vec::Vec;
+use core::ops::Range;
 
 use itertools::{izip, Itertools};
-use p3_air::Air;
-use p3_challenger::{CanObserve, CanSample, FieldChallenger};
-use p3_commit::{Pcs, PolynomialSpace};
+use p3_air::{Air, Interaction};
+use p3_commit::{Pcs, PolynomialSpace, ProverObserver, ProverPhase};
 use p3_field::{FieldAlgebra, FieldExtensionAlgebra, PackedValue};
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
 use p3_maybe_rayon::prelude::*;
-use p3_util::{log2_ceil_usize, log2_strict_usize};
-use tracing::{info_span, instrument};
+use p3_util::log2_strict_usize;
+use tracing::instrument;
 
+use crate::tables::{check_extension_trace, prove_tables_with_trace_commit, CommittedTable};
+use crate::window::{assert_periodic_columns_fit, assert_row_selectors_fit};
 use crate::{
-    get_symbolic_constraints, Commitments, Domain, OpenedValues, PackedChallenge, PackedVal, Proof,
-    ProverConstraintFolder, StarkGenericConfig, SymbolicAirBuilder, SymbolicExpression, Val,
+    check_periodic_columns, eval_permutation_constraints, periodic_columns_on_coset, prove_tables,
+    row_selectors_on_coset, setup_keys, transition_window_selectors_on_coset, window_size, Domain,
+    Instance, InteractionScope, PackedChallenge, PackedVal, Proof, ProverConstraintFolder,
+    ProverTable, StarkGenericConfig, StarkProvingKey, SymbolicAirBuilder, TablesProof, Val,
 };
 
+/// Prove that `trace` satisfies `air`.
+///
+/// The AIR's keys are computed on the fly; use `prove_with_key` to reuse a proving key computed
+/// once by `setup_keys`.
+///
+/// If the PCS is hiding, the proof is zero knowledge: the traces are randomized to polynomials of
+/// twice their degree, and the quotient is masked by a random polynomial. The quotient then has
+/// `2 * constraint_degree - 1` chunks, rounded up to a power of two, which the PCS's blowup factor
+/// must be at least half of (or all of, if the AIR has a preprocessed trace).
 #[instrument(skip_all)]
 #[allow(clippy::multiple_bound_locations)] // cfg not supported in where clauses?
 pub fn prove<
     SC,
-    #[cfg(debug_assertions)] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, Val<SC>>>,
+    #[cfg(debug_assertions)] A: for<'a> Air<crate::DebugConstraintBuilder<'a, Val<SC>, SC::Challenge>>,
     #[cfg(not(debug_assertions))] A,
 >(
     config: &SC,
@@ -34,124 +47,417 @@ where
     SC: StarkGenericConfig,
     A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<ProverConstraintFolder<'a, SC>>,
 {
-    #[cfg(debug_assertions)]
-    crate::check_constraints::check_constraints(air, &trace, public_values);
-
-    let degree = trace.height();
-    let log_degree = log2_strict_usize(degree);
+    let (proving_key, _) = setup_keys(config, air, public_values.len());
+    prove_with_key(config, &proving_key, air, challenger, trace, public_values)
+}
 
-    let symbolic_constraints = get_symbolic_constraints::<Val<SC>, A>(air, 0, public_values.len());
-    let constraint_count = symbolic_constraints.len();
-    let constraint_degree = symbolic_constraints
-        .iter()
-        .map(SymbolicExpression::degree_multiple)
-        .max()
-        .unwrap_or(0);
-    let log_quotient_degree = log2_ceil_usize(constraint_degree - 1);
-    let quotient_degree = 1 << log_quotient_degree;
+/// Prove that `trace` satisfies `air`, given the proving key of `air` from `setup_keys`.
+#[instrument(skip_all)]
+#[allow(clippy::multiple_bound_locations)] // cfg not supported in where clauses?
+pub fn prove_with_key<
+    SC,
+    #[cfg(debug_assertions)] A: for<'a> Air<crate::DebugConstraintBuilder<'a, Val<SC>, SC::Challenge>>,
+    #[cfg(not(debug_assertions))] A,
+>(
+    config: &SC,
+    proving_key: &StarkProvingKey<SC>,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    trace: RowMajorMatrix<Val<SC>>,
+    public_values: &Vec<Val<SC>>,
+) -> Proof<SC>
+where
+    SC: StarkGenericConfig,
+    A: for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    prove_with_extension_trace(
+        config,
+        proving_key,
+        air,
+        challenger,
+        trace,
+        None,
+        public_values,
+    )
+}
 
-    let pcs = config.pcs();
-    let trace_domain = pcs.natural_domain_for_degree(degree);
+/// Prove that `trace` and `extension_trace` satisfy `air`, given the proving key of `air` from
+/// `setup_keys`.
+///
+/// `extension_trace` holds the AIR's `extension_width()` main trace columns over the extension
+/// field, and must be given if and only if there are any. It is committed to alongside `trace`,
+/// flattened to base field columns.
+#[instrument(skip_all)]
+#[allow(clippy::multiple_bound_locations)] // cfg not supported in where clauses?
+#[allow(clippy::ptr_arg)] // Takes a `Vec` like the other entry points.
+pub fn prove_with_extension_trace<
+    SC,
+    #[cfg(debug_assertions)] A: for<'a> Air<crate::DebugConstraintBuilder<'a, Val<SC>, SC::Challenge>>,
+    #[cfg(not(debug_assertions))] A,
+>(
+    config: &SC,
+    proving_key: &StarkProvingKey<SC>,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    trace: RowMajorMatrix<Val<SC>>,
+    extension_trace: Option<RowMajorMatrix<SC::Challenge>>,
+    public_values: &Vec<Val<SC>>,
+) -> Proof<SC>
+where
+    SC: StarkGenericConfig,
+    A: for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    prove_with_instance(
+        config,
+        proving_key,
+        air,
+        challenger,
+        trace,
+        extension_trace,
+        &Instance::new(public_values.clone()),
+    )
+}
 
-    let (trace_commit, trace_data) =
-        info_span!("commit to trace data").in_scope(|| pcs.commit(vec![(trace_domain, trace)]));
+/// Prove that `trace` and `extension_trace` satisfy `air` for the public inputs `instance`, given
+/// the proving key of `air` from `setup_keys`.
+///
+/// The instance's data and extension field public values are observed before the trace
+/// commitment, and its base field public values after it.
+#[instrument(skip_all)]
+#[allow(clippy::multiple_bound_locations)] // cfg not supported in where clauses?
+pub fn prove_with_instance<
+    SC,
+    #[cfg(debug_assertions)] A: for<'a> Air<crate::DebugConstraintBuilder<'a, Val<SC>, SC::Challenge>>,
+    #[cfg(not(debug_assertions))] A,
+>(
+    config: &SC,
+    proving_key: &StarkProvingKey<SC>,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    trace: RowMajorMatrix<Val<SC>>,
+    extension_trace: Option<RowMajorMatrix<SC::Challenge>>,
+    instance: &Instance<SC>,
+) -> Proof<SC>
+where
+    SC: StarkGenericConfig,
+    A: for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    let proof = prove_observed(
+        config,
+        proving_key,
+        air,
+        challenger,
+        trace,
+        extension_trace,
+        instance,
+        &mut (),
+    );
+    unwrap_proof(proof)
+}
 
-    // Observe the instance.
-    challenger.observe(Val::<SC>::from_canonical_usize(log_degree));
-    // TODO: Might be best practice to include other instance data here; see verifier comment.
+/// As `prove_with_instance`, reporting each phase of proving to `observer`, and stopping with
+/// `ProverError::Cancelled` before the next phase once the observer cancels.
+#[instrument(skip_all)]
+#[allow(clippy::multiple_bound_locations)] // cfg not supported in where clauses?
+#[allow(clippy::too_many_arguments)]
+pub fn prove_observed<
+    SC,
+    #[cfg(debug_assertions)] A: for<'a> Air<crate::DebugConstraintBuilder<'a, Val<SC>, SC::Challenge>>,
+    #[cfg(not(debug_assertions))] A,
+>(
+    config: &SC,
+    proving_key: &StarkProvingKey<SC>,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    trace: RowMajorMatrix<Val<SC>>,
+    extension_trace: Option<RowMajorMatrix<SC::Challenge>>,
+    instance: &Instance<SC>,
+    observer: &mut impl ProverObserver,
+) -> Result<Proof<SC>, ProverError>
+where
+    SC: StarkGenericConfig,
+    A: for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    check_extension_trace(air, &trace, extension_trace.as_ref());
 
-    challenger.observe(trace_commit.clone());
-    challenger.observe_slice(public_values);
-    let alpha: SC::Challenge = challenger.sample_ext_element();
+    #[cfg(debug_assertions)]
+    crate::check_constraints::assert_no_failures(&crate::check_constraints_with_extension(
+        air,
+        &trace,
+        extension_trace.as_ref(),
+        &instance.public_values,
+        &instance.extension_public_values,
+    ));
 
-    let quotient_domain =
-        trace_domain.create_disjoint_domain(1 << (log_degree + log_quotient_degree));
+    let table = ProverTable {
+        air,
+        proving_key,
+        trace,
+        extension_trace,
+        instance,
+    };
+    prove_tables(
+        config,
+        challenger,
+        vec![table],
+        InteractionScope::Table,
+        observer,
+    )
+    .map(single_table_proof)
+}
 
-    let trace_on_quotient_domain = pcs.get_evaluations_on_domain(&trace_data, 0, quotient_domain);
+/// Prove that the main trace of `air` with `height` rows, whose columns are given by `columns`,
+/// satisfies `air` for the public inputs `instance`, given the proving key of `air` from
+/// `setup_keys`.
+///
+/// `columns` returns the values of the trace columns in a range, and is called once for each of a
+/// sequence of consecutive ranges covering all the columns, so the trace can be generated a chunk
+/// at a time and is never held whole. The PCS encodes each chunk as it is returned, and keeps the
+/// committed LDE of the whole trace as prover data. With `TwoAdicFriPcs::with_chunk_budget`, the
+/// DFT working memory is that of a chunk, and with `TwoAdicFriPcs::with_lde_dir`, the committed LDE
+/// is held in a memory-mapped file rather than in memory.
+///
+/// Unlike `prove_with_instance`, this does not check the constraints on the trace in debug builds,
+/// and the AIR may have neither extension field columns nor interactions, both of which need the
+/// whole trace.
+///
+/// As in `prove_observed`, each phase of proving is reported to `observer`, which may cancel the
+/// proof.
+#[instrument(skip_all)]
+#[allow(clippy::too_many_arguments)]
+pub fn prove_streaming<SC, A>(
+    config: &SC,
+    proving_key: &StarkProvingKey<SC>,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    height: usize,
+    mut columns: impl FnMut(Range<usize>) -> RowMajorMatrix<Val<SC>>,
+    instance: &Instance<SC>,
+    observer: &mut impl ProverObserver,
+) -> Result<Proof<SC>, ProverError>
+where
+    SC: StarkGenericConfig,
+    A: for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    assert_eq!(
+        air.extension_width(),
+        0,
+        "streaming proofs do not support extension field columns"
+    );
+    assert!(
+        air.sends().is_empty() && air.receives().is_empty(),
+        "streaming proofs do not support interactions"
+    );
+    let width = air.width();
 
-    let quotient_values = quotient_values(
+    let table = CommittedTable {
         air,
-        public_values,
-        trace_domain,
-        quotient_domain,
-        trace_on_quotient_domain,
-        alpha,
-        constraint_count,
-    );
-    let quotient_flat = RowMajorMatrix::new_col(quotient_values).flatten_to_base();
-    let quotient_chunks = quotient_domain.split_evals(quotient_degree, quotient_flat);
-    let qc_domains = quotient_domain.split_domains(quotient_degree);
+        proving_key,
+        instance,
+        degree: height,
+        main_trace: None,
+    };
+    prove_tables_with_trace_commit(
+        config,
+        challenger,
+        vec![table],
+        InteractionScope::Table,
+        observer,
+        |domains, others| {
+            let pcs = config.pcs();
+            let trace_columns = |range: Range<usize>| {
+                let chunk = columns(range.clone());
+                assert_eq!(
+                    chunk.width(),
+                    range.len(),
+                    "the trace chunk does not have the requested columns"
+                );
+                assert_eq!(
+                    chunk.height(),
+                    height,
+                    "the trace chunk height does not match the trace height"
+                );
+                randomize_trace::<SC>(pcs, chunk)
+            };
+            pcs.commit_columns(domains[0], width, trace_columns, others)
+        },
+    )
+    .map(single_table_proof)
+}
 
-    let (quotient_commit, quotient_data) = info_span!("commit to quotient poly chunks")
-        .in_scope(|| pcs.commit(izip!(qc_domains, quotient_chunks).collect_vec()));
-    challenger.observe(quotient_commit.clone());
+/// Why a prover stopped without producing a proof.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProverError {
+    /// The prover's observer cancelled the proof.
+    Cancelled,
+    /// The sends and receives of the traces' interactions do not balance, so the LogUp argument
+    /// cannot be satisfied.
+    UnbalancedInteractions,
+}
 
-    let commitments = Commitments {
-        trace: trace_commit,
-        quotient_chunks: quotient_commit,
-    };
+/// Report the start of `phase` to `observer`, unless it has cancelled the proof.
+pub(crate) fn start_phase(
+    observer: &mut impl ProverObserver,
+    phase: ProverPhase,
+) -> Result<(), ProverError> {
+    if observer.is_cancelled() {
+        return Err(ProverError::Cancelled);
+    }
+    observer.phase_started(phase);
+    Ok(())
+}
 
-    let zeta: SC::Challenge = challenger.sample();
-    let zeta_next = trace_domain.next_point(zeta).unwrap();
+/// The result of a prover whose observer never cancels, such as `prove_tables` with the observer
+/// `()`, for the entry points which return a proof rather than a `Result`.
+///
+/// # Panics
+///
+/// Panics if the interactions of the traces do not balance.
+pub fn unwrap_proof<T>(proof: Result<T, ProverError>) -> T {
+    match proof {
+        Ok(proof) => proof,
+        Err(ProverError::Cancelled) => unreachable!("the proof has no observer to cancel it"),
+        Err(ProverError::UnbalancedInteractions) => {
+            panic!("the sends and receives of the traces do not balance")
+        }
+    }
+}
 
-    let (opened_values, opening_proof) = info_span!("open").in_scope(|| {
-        pcs.open(
-            vec![
-                (&trace_data, vec![vec![zeta, zeta_next]]),
-                (
-                    &quotient_data,
-                    // open every chunk at zeta
-                    (0..quotient_degree).map(|_| vec![zeta]).collect_vec(),
-                ),
-            ],
-            challenger,
-        )
-    });
-    let trace_local = opened_values[0][0][0].clone();
-    let trace_next = opened_values[0][0][1].clone();
-    let quotient_chunks = opened_values[1].iter().map(|v| v[0].clone()).collect_vec();
-    let opened_values = OpenedValues {
-        trace_local,
-        trace_next,
-        quotient_chunks,
-    };
-    Proof {
+/// The proof of the only table of `proof`.
+fn single_table_proof<SC: StarkGenericConfig>(proof: TablesProof<SC>) -> Proof<SC> {
+    let TablesProof {
         commitments,
+        zeta_pow_witness,
         opened_values,
         opening_proof,
-        degree_bits: log_degree,
+        degree_bits,
+        cumulative_sums: _,
+    } = proof;
+    Proof {
+        commitments,
+        zeta_pow_witness,
+        opened_values: opened_values.into_iter().next().unwrap(),
+        opening_proof,
+        degree_bits: degree_bits[0],
     }
 }
 
+/// In zero-knowledge mode, interleave the rows of `trace` with uniformly random rows.
+///
+/// Every other point of the natural domain of size `2n` lies in the trace domain of size `n`, so the
+/// result interpolates a polynomial which agrees with the trace on the trace domain, offset by a
+/// uniformly random multiple of the trace domain's vanishing polynomial. Its openings outside the
+/// trace domain thus reveal nothing about the trace.
+pub(crate) fn randomize_trace<SC: StarkGenericConfig>(
+    pcs: &SC::Pcs,
+    trace: RowMajorMatrix<Val<SC>>,
+) -> RowMajorMatrix<Val<SC>> {
+    let width = trace.width();
+    let Some(randomizer) = pcs.random_matrix(trace.height(), width) else {
+        return trace;
+    };
+    let values = trace
+        .row_slices()
+        .zip(randomizer.row_slices())
+        .flat_map(|(row, random_row)| row.iter().chain(random_row).copied())
+        .collect();
+    RowMajorMatrix::new(values, width)
+}
+
+/// The LogUp data needed to evaluate the permutation constraints of a table on its quotient domain.
+pub struct PermutationQuotientData<'a, SC: StarkGenericConfig, Mat> {
+    pub sends: &'a [Interaction<Val<SC>>],
+    pub receives: &'a [Interaction<Val<SC>>],
+    pub challenges: &'a [SC::Challenge],
+    /// The claimed value of the running sum on the last row.
+    pub cumulative_sum: SC::Challenge,
+    /// The permutation trace on the quotient domain, flattened to base field columns.
+    pub trace_on_quotient_domain: Mat,
+}
+
+/// Evaluate the folded constraints of `air`, divided by the trace domain's vanishing polynomial,
+/// over `quotient_domain`.
+///
+/// `trace_on_quotient_domain` holds the AIR's base field columns, followed by its extension field
+/// columns flattened to base field columns.
+///
+/// If `permutation` is given, the LogUp constraints of its interactions are folded in after those
+/// of `air`; `constraint_count` must include them.
 #[instrument(name = "compute quotient polynomial", skip_all)]
-fn quotient_values<SC, A, Mat>(
+#[allow(clippy::too_many_arguments)]
+pub fn quotient_values<SC, A, PrepMat, Mat, PermMat>(
     air: &A,
     public_values: &Vec<Val<SC>>,
+    extension_public_values: &[SC::Challenge],
     trace_domain: Domain<SC>,
     quotient_domain: Domain<SC>,
+    preprocessed_on_quotient_domain: Option<PrepMat>,
     trace_on_quotient_domain: Mat,
+    permutation: Option<PermutationQuotientData<'_, SC, PermMat>>,
     alpha: SC::Challenge,
     constraint_count: usize,
 ) -> Vec<SC::Challenge>
 where
     SC: StarkGenericConfig,
-    A: for<'a> Air<ProverConstraintFolder<'a, SC>>,
+    A: for<'a> Air<ProverConstraintFolder<'a, SC>> + ?Sized,
+    PrepMat: Matrix<Val<SC>> + Sync,
     Mat: Matrix<Val<SC>> + Sync,
+    PermMat: Matrix<Val<SC>> + Sync,
 {
+    let ext_degree = <SC::Challenge as FieldExtensionAlgebra<Val<SC>>>::D;
     let quotient_size = quotient_domain.size();
-    let width = trace_on_quotient_domain.width();
+    let width = air.width();
+    let extension_width = air.extension_width();
+    let preprocessed_width = preprocessed_on_quotient_domain
+        .as_ref()
+        .map_or(0, Matrix::width);
+    let permutation_width = permutation
+        .as_ref()
+        .map_or(0, |perm| perm.trace_on_quotient_domain.width() / ext_degree);
+    let permutation_challenges = permutation.as_ref().map_or(vec![], |perm| {
+        perm.challenges
+            .iter()
+            .map(|&challenge| PackedChallenge::<SC>::from_f(challenge))
+            .collect_vec()
+    });
+    let extension_public_values = extension_public_values
+        .iter()
+        .map(|&value| PackedChallenge::<SC>::from_f(value))
+        .collect_vec();
+    let row_offsets = air.row_offsets();
     let mut sels = trace_domain.selectors_on_coset(quotient_domain);
 
     let qdb = log2_strict_usize(quotient_domain.size()) - log2_strict_usize(trace_domain.size());
     let next_step = 1 << qdb;
 
+    let mut transition_windows = transition_window_selectors_on_coset(
+        &sels.is_transition,
+        next_step,
+        window_size(&row_offsets),
+    );
+    let row_selectors = air.row_selectors();
+    assert_row_selectors_fit(&row_selectors, trace_domain.size());
+    let mut row_selector_evals =
+        row_selectors_on_coset(&trace_domain, quotient_domain, &row_selectors);
+    let periodic_columns = air.periodic_columns();
+    check_periodic_columns(&periodic_columns);
+    assert_periodic_columns_fit(&periodic_columns, trace_domain.size());
+    let mut periodic_evals =
+        periodic_columns_on_coset(&trace_domain, quotient_domain, &periodic_columns);
+
     // We take PackedVal::<SC>::WIDTH worth of values at a time from a quotient_size slice, so we need to
     // pad with default values in the case where quotient_size is smaller than PackedVal::<SC>::WIDTH.
     for _ in quotient_size..PackedVal::<SC>::WIDTH {
         sels.is_first_row.push(Val::<SC>::default());
         sels.is_last_row.push(Val::<SC>::default());
-        sels.is_transition.push(Val::<SC>::default());
         sels.inv_zeroifier.push(Val::<SC>::default());
+        for evals in transition_windows
+            .iter_mut()
+            .chain(&mut row_selector_evals)
+            .chain(&mut periodic_evals)
+        {
+            evals.push(Val::<SC>::default());
+        }
     }
 
     let mut alpha_powers = alpha.powers().take(constraint_count).collect_vec();
@@ -165,26 +471,95 @@ where
 
             let is_first_row = *PackedVal::<SC>::from_slice(&sels.is_first_row[i_range.clone()]);
             let is_last_row = *PackedVal::<SC>::from_slice(&sels.is_last_row[i_range.clone()]);
-            let is_transition = *PackedVal::<SC>::from_slice(&sels.is_transition[i_range.clone()]);
+            let is_transition_windows = transition_windows
+                .iter()
+                .map(|selector| *PackedVal::<SC>::from_slice(&selector[i_range.clone()]))
+                .collect_vec();
+            let packed_row_selectors = izip!(&row_selectors, &row_selector_evals)
+                .map(|(&selector, evals)| {
+                    (
+                        selector,
+                        *PackedVal::<SC>::from_slice(&evals[i_range.clone()]),
+                    )
+                })
+                .collect_vec();
+            let periodic_values = periodic_evals
+                .iter()
+                .map(|evals| *PackedVal::<SC>::from_slice(&evals[i_range.clone()]))
+                .collect_vec();
             let inv_zeroifier = *PackedVal::<SC>::from_slice(&sels.inv_zeroifier[i_range.clone()]);
 
-            let main = RowMajorMatrix::new(
-                trace_on_quotient_domain.vertically_packed_row_pair(i_start, next_step),
-                width,
+            let preprocessed = RowMajorMatrix::new(
+                preprocessed_on_quotient_domain
+                    .as_ref()
+                    .map_or(vec![], |prep| {
+                        prep.vertically_packed_row_window(i_start, &row_offsets, next_step)
+                    }),
+                preprocessed_width,
+            );
+            let trace_window: Vec<PackedVal<SC>> = trace_on_quotient_domain
+                .vertically_packed_row_window(i_start, &row_offsets, next_step);
+            // The extension field columns, if any, follow the base field columns of each row.
+            let (main, main_ext) = if extension_width == 0 {
+                (
+                    RowMajorMatrix::new(trace_window, width),
+                    RowMajorMatrix::new(vec![], 0),
+                )
+            } else {
+                let trace_width = trace_on_quotient_domain.width();
+                let mut main_values = Vec::with_capacity(row_offsets.len() * width);
+                let mut main_ext_values = Vec::with_capacity(row_offsets.len() * extension_width);
+                for row in trace_window.chunks_exact(trace_width) {
+                    main_values.extend_from_slice(&row[..width]);
+                    main_ext_values.extend(
+                        row[width..]
+                            .chunks_exact(ext_degree)
+                            .map(|coeffs| PackedChallenge::<SC>::from_base_fn(|k| coeffs[k])),
+                    );
+                }
+                (
+                    RowMajorMatrix::new(main_values, width),
+                    RowMajorMatrix::new(main_ext_values, extension_width),
+                )
+            };
+            let permutation_trace = RowMajorMatrix::new(
+                permutation.as_ref().map_or(vec![], |perm| {
+                    perm.trace_on_quotient_domain
+                        .vertically_packed_row_pair::<PackedVal<SC>>(i_start, next_step)
+                        .chunks_exact(ext_degree)
+                        .map(|coeffs| PackedChallenge::<SC>::from_base_fn(|k| coeffs[k]))
+                        .collect_vec()
+                }),
+                permutation_width,
             );
 
             let accumulator = PackedChallenge::<SC>::ZERO;
             let mut folder = ProverConstraintFolder {
+                preprocessed: preprocessed.as_view(),
                 main: main.as_view(),
+                main_ext: main_ext.as_view(),
+                permutation: permutation_trace.as_view(),
+                permutation_challenges: &permutation_challenges,
                 public_values,
+                extension_public_values: &extension_public_values,
                 is_first_row,
                 is_last_row,
-                is_transition,
+                is_transition_windows: &is_transition_windows,
+                row_selectors: &packed_row_selectors,
+                periodic_values: &periodic_values,
                 alpha_powers: &alpha_powers,
                 accumulator,
                 constraint_index: 0,
             };
             air.eval(&mut folder);
+            if let Some(perm) = &permutation {
+                eval_permutation_constraints(
+                    &mut folder,
+                    perm.sends,
+                    perm.receives,
+                    PackedChallenge::<SC>::from_f(perm.cumulative_sum),
+                );
+            }
 
             // quotient(x) = constraints(x) / Z_H(x)
             let quotient = folder.accumulator * inv_zeroifier;
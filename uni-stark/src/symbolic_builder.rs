@@ -1,7 +1,11 @@
 use alloc::vec;
 use alloc::vec::Vec;
+use core::panic::Location;
 
-use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, PairBuilder};
+use p3_air::{
+    Air, AirBuilder, AirBuilderWithExtensionPublicValues, AirBuilderWithPublicValues,
+    ExtensionBuilder, ExtensionMainBuilder, PairBuilder, PeriodicAirBuilder, RowSelector,
+};
 use p3_field::Field;
 use p3_matrix::dense::RowMajorMatrix;
 use p3_util::log2_ceil_usize;
@@ -9,26 +13,37 @@ use tracing::instrument;
 
 use crate::symbolic_expression::SymbolicExpression;
 use crate::symbolic_variable::SymbolicVariable;
-use crate::Entry;
+use crate::{check_periodic_columns, check_row_offsets, check_row_selectors, Entry};
 
 #[instrument(name = "infer log of constraint degree", skip_all)]
 pub fn get_log_quotient_degree<F, A>(
     air: &A,
     preprocessed_width: usize,
     num_public_values: usize,
+    is_zk: bool,
 ) -> usize
 where
     F: Field,
-    A: Air<SymbolicAirBuilder<F>>,
+    A: Air<SymbolicAirBuilder<F>> + ?Sized,
 {
+    let constraint_degree = get_max_constraint_degree(air, preprocessed_width, num_public_values);
+    log_quotient_degree(constraint_degree, is_zk)
+}
+
+/// The log2 of the number of quotient chunks for constraints of degree `constraint_degree`.
+pub(crate) fn log_quotient_degree(constraint_degree: usize, is_zk: bool) -> usize {
     // We pad to at least degree 2, since a quotient argument doesn't make sense with smaller degrees.
-    let constraint_degree =
-        get_max_constraint_degree(air, preprocessed_width, num_public_values).max(2);
+    let constraint_degree = constraint_degree.max(2);
 
     // The quotient's actual degree is approximately (max_constraint_degree - 1) n,
-    // where subtracting 1 comes from division by the zerofier.
+    // where subtracting 1 comes from division by the zerofier. In zero-knowledge mode the trace
+    // polynomials are randomized to degree 2n, so it is approximately (2 max_constraint_degree - 1) n.
     // But we pad it to a power of two so that we can efficiently decompose the quotient.
-    log2_ceil_usize(constraint_degree - 1)
+    if is_zk {
+        log2_ceil_usize(2 * constraint_degree - 1)
+    } else {
+        log2_ceil_usize(constraint_degree - 1)
+    }
 }
 
 #[instrument(name = "infer constraint degree", skip_all, level = "debug")]
@@ -39,11 +54,11 @@ pub fn get_max_constraint_degree<F, A>(
 ) -> usize
 where
     F: Field,
-    A: Air<SymbolicAirBuilder<F>>,
+    A: Air<SymbolicAirBuilder<F>> + ?Sized,
 {
     get_symbolic_constraints(air, preprocessed_width, num_public_values)
         .iter()
-        .map(|c| c.degree_multiple())
+        .map(SymbolicExpression::constraint_degree)
         .max()
         .unwrap_or(0)
 }
@@ -56,51 +71,129 @@ pub fn get_symbolic_constraints<F, A>(
 ) -> Vec<SymbolicExpression<F>>
 where
     F: Field,
-    A: Air<SymbolicAirBuilder<F>>,
+    A: Air<SymbolicAirBuilder<F>> + ?Sized,
 {
-    let mut builder = SymbolicAirBuilder::new(preprocessed_width, air.width(), num_public_values);
+    symbolic_builder(air, preprocessed_width, num_public_values).constraints()
+}
+
+/// A `SymbolicAirBuilder` in which the constraints of `air` have been evaluated.
+pub(crate) fn symbolic_builder<F, A>(
+    air: &A,
+    preprocessed_width: usize,
+    num_public_values: usize,
+) -> SymbolicAirBuilder<F>
+where
+    F: Field,
+    A: Air<SymbolicAirBuilder<F>> + ?Sized,
+{
+    let row_offsets = air.row_offsets();
+    check_row_offsets(&row_offsets);
+    let row_selectors = air.row_selectors();
+    check_row_selectors(&row_selectors);
+    let periodic_columns = air.periodic_columns();
+    check_periodic_columns(&periodic_columns);
+    let mut builder = SymbolicAirBuilder::new(
+        preprocessed_width,
+        air.width(),
+        air.extension_width(),
+        num_public_values,
+        air.num_extension_public_values(),
+        &row_offsets,
+        row_selectors,
+        periodic_columns.len(),
+    );
     air.eval(&mut builder);
-    builder.constraints()
+    builder
 }
 
 /// An `AirBuilder` for evaluating constraints symbolically, and recording them for later use.
+///
+/// Extension field columns and constraints are represented by the same symbolic expressions as
+/// base field ones: an extension field constraint counts as a single constraint, of the same
+/// degree.
 #[derive(Debug)]
 pub struct SymbolicAirBuilder<F: Field> {
     preprocessed: RowMajorMatrix<SymbolicVariable<F>>,
     main: RowMajorMatrix<SymbolicVariable<F>>,
+    main_ext: RowMajorMatrix<SymbolicVariable<F>>,
     public_values: Vec<SymbolicVariable<F>>,
+    extension_public_values: Vec<SymbolicVariable<F>>,
+    row_selectors: Vec<RowSelector>,
+    periodic_values: Vec<SymbolicVariable<F>>,
     constraints: Vec<SymbolicExpression<F>>,
+    /// The location of the `assert_*` call which asserted each constraint.
+    locations: Vec<&'static Location<'static>>,
 }
 
 impl<F: Field> SymbolicAirBuilder<F> {
-    pub(crate) fn new(preprocessed_width: usize, width: usize, num_public_values: usize) -> Self {
-        let prep_values = [0, 1]
-            .into_iter()
-            .flat_map(|offset| {
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        preprocessed_width: usize,
+        width: usize,
+        extension_width: usize,
+        num_public_values: usize,
+        num_extension_public_values: usize,
+        row_offsets: &[usize],
+        row_selectors: Vec<RowSelector>,
+        num_periodic_columns: usize,
+    ) -> Self {
+        let prep_values = row_offsets
+            .iter()
+            .flat_map(|&offset| {
                 (0..preprocessed_width)
                     .map(move |index| SymbolicVariable::new(Entry::Preprocessed { offset }, index))
             })
             .collect();
-        let main_values = [0, 1]
-            .into_iter()
-            .flat_map(|offset| {
+        let main_values = row_offsets
+            .iter()
+            .flat_map(|&offset| {
                 (0..width).map(move |index| SymbolicVariable::new(Entry::Main { offset }, index))
             })
             .collect();
+        let main_ext_values = row_offsets
+            .iter()
+            .flat_map(|&offset| {
+                (0..extension_width)
+                    .map(move |index| SymbolicVariable::new(Entry::MainExtension { offset }, index))
+            })
+            .collect();
         let public_values = (0..num_public_values)
             .map(move |index| SymbolicVariable::new(Entry::Public, index))
             .collect();
+        let extension_public_values = (0..num_extension_public_values)
+            .map(move |index| SymbolicVariable::new(Entry::ExtensionPublic, index))
+            .collect();
+        let periodic_values = (0..num_periodic_columns)
+            .map(move |index| SymbolicVariable::new(Entry::Periodic, index))
+            .collect();
         Self {
             preprocessed: RowMajorMatrix::new(prep_values, preprocessed_width),
             main: RowMajorMatrix::new(main_values, width),
+            main_ext: RowMajorMatrix::new(main_ext_values, extension_width),
             public_values,
+            extension_public_values,
+            row_selectors,
+            periodic_values,
             constraints: vec![],
+            locations: vec![],
         }
     }
 
     pub(crate) fn constraints(self) -> Vec<SymbolicExpression<F>> {
         self.constraints
     }
+
+    pub(crate) fn constraints_with_locations(
+        self,
+    ) -> (Vec<SymbolicExpression<F>>, Vec<&'static Location<'static>>) {
+        (self.constraints, self.locations)
+    }
+
+    #[track_caller]
+    fn push_constraint(&mut self, constraint: SymbolicExpression<F>) {
+        self.constraints.push(constraint);
+        self.locations.push(Location::caller());
+    }
 }
 
 impl<F: Field> AirBuilder for SymbolicAirBuilder<F> {
@@ -122,15 +215,24 @@ impl<F: Field> AirBuilder for SymbolicAirBuilder<F> {
     }
 
     fn is_transition_window(&self, size: usize) -> Self::Expr {
-        if size == 2 {
-            SymbolicExpression::IsTransition
-        } else {
-            panic!("uni-stark only supports a window size of 2")
+        match size {
+            2 => SymbolicExpression::IsTransition,
+            3.. => SymbolicExpression::IsTransitionWindow(size),
+            _ => panic!("a transition window must have at least 2 rows"),
         }
     }
 
+    fn row_selector(&self, selector: RowSelector) -> Self::Expr {
+        assert!(
+            self.row_selectors.contains(&selector),
+            "the row selector {selector:?} is not declared in the AIR's row selectors"
+        );
+        SymbolicExpression::RowSelector(selector)
+    }
+
+    #[track_caller]
     fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
-        self.constraints.push(x.into());
+        self.push_constraint(x.into());
     }
 }
 
@@ -141,8 +243,46 @@ impl<F: Field> AirBuilderWithPublicValues for SymbolicAirBuilder<F> {
     }
 }
 
+impl<F: Field> PeriodicAirBuilder for SymbolicAirBuilder<F> {
+    type PeriodicVar = SymbolicVariable<F>;
+
+    fn periodic_values(&self) -> &[Self::PeriodicVar] {
+        &self.periodic_values
+    }
+}
+
 impl<F: Field> PairBuilder for SymbolicAirBuilder<F> {
     fn preprocessed(&self) -> Self::M {
         self.preprocessed.clone()
     }
 }
+
+impl<F: Field> ExtensionBuilder for SymbolicAirBuilder<F> {
+    type EF = F;
+    type ExprEF = SymbolicExpression<F>;
+    type VarEF = SymbolicVariable<F>;
+
+    #[track_caller]
+    fn assert_zero_ext<I>(&mut self, x: I)
+    where
+        I: Into<Self::ExprEF>,
+    {
+        self.push_constraint(x.into());
+    }
+}
+
+impl<F: Field> AirBuilderWithExtensionPublicValues for SymbolicAirBuilder<F> {
+    type PublicVarEF = SymbolicVariable<F>;
+
+    fn extension_public_values(&self) -> &[Self::PublicVarEF] {
+        &self.extension_public_values
+    }
+}
+
+impl<F: Field> ExtensionMainBuilder for SymbolicAirBuilder<F> {
+    type MEF = RowMajorMatrix<Self::VarEF>;
+
+    fn main_ext(&self) -> Self::MEF {
+        self.main_ext.clone()
+    }
+}
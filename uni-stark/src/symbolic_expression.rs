@@ -4,6 +4,7 @@ use core::fmt::Debug;
 use core::iter::{Product, Sum};
 use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 
+use p3_air::RowSelector;
 use p3_field::{Field, FieldAlgebra};
 
 use crate::symbolic_variable::SymbolicVariable;
@@ -15,36 +16,50 @@ pub enum SymbolicExpression<F> {
     IsFirstRow,
     IsLastRow,
     IsTransition,
+    /// The transition selector of a window of the given number of rows, which vanishes on the
+    /// last `size - 1` rows.
+    IsTransitionWindow(usize),
+    /// The selector of a set of rows declared in `BaseAir::row_selectors`.
+    RowSelector(RowSelector),
     Constant(F),
     Add {
         x: Rc<Self>,
         y: Rc<Self>,
         degree_multiple: usize,
+        selector_degree: usize,
     },
     Sub {
         x: Rc<Self>,
         y: Rc<Self>,
         degree_multiple: usize,
+        selector_degree: usize,
     },
     Neg {
         x: Rc<Self>,
         degree_multiple: usize,
+        selector_degree: usize,
     },
     Mul {
         x: Rc<Self>,
         y: Rc<Self>,
         degree_multiple: usize,
+        selector_degree: usize,
     },
 }
 
 impl<F> SymbolicExpression<F> {
     /// Returns the multiple of `n` (the trace length) in this expression's degree.
+    ///
+    /// Transition selectors have a degree which doesn't grow with `n`, so they don't count here; see
+    /// `selector_degree`.
     pub const fn degree_multiple(&self) -> usize {
         match self {
             SymbolicExpression::Variable(v) => v.degree_multiple(),
             SymbolicExpression::IsFirstRow => 1,
             SymbolicExpression::IsLastRow => 1,
             SymbolicExpression::IsTransition => 0,
+            SymbolicExpression::IsTransitionWindow(_) => 0,
+            SymbolicExpression::RowSelector(_) => 1,
             SymbolicExpression::Constant(_) => 0,
             SymbolicExpression::Add {
                 degree_multiple, ..
@@ -60,6 +75,49 @@ impl<F> SymbolicExpression<F> {
             } => *degree_multiple,
         }
     }
+
+    /// Returns the part of this expression's degree which comes from transition selectors, whose
+    /// degree doesn't depend on `n`: the expression's degree is at most
+    /// `degree_multiple() * (n - 1) + selector_degree()`.
+    pub const fn selector_degree(&self) -> usize {
+        match self {
+            SymbolicExpression::IsTransition => 1,
+            SymbolicExpression::IsTransitionWindow(size) => *size - 1,
+            SymbolicExpression::Variable(_)
+            | SymbolicExpression::IsFirstRow
+            | SymbolicExpression::IsLastRow
+            | SymbolicExpression::RowSelector(_)
+            | SymbolicExpression::Constant(_) => 0,
+            SymbolicExpression::Add {
+                selector_degree, ..
+            }
+            | SymbolicExpression::Sub {
+                selector_degree, ..
+            }
+            | SymbolicExpression::Neg {
+                selector_degree, ..
+            }
+            | SymbolicExpression::Mul {
+                selector_degree, ..
+            } => *selector_degree,
+        }
+    }
+
+    /// Returns the degree of this expression as a constraint, as a multiple of `n`, which bounds
+    /// the degree of its quotient by the vanishing polynomial of the trace domain.
+    ///
+    /// A constraint of degree `d (n - 1) + s` has a quotient of degree `(d - 1) n + s - d`, which
+    /// is below `(d - 1) n` only if `s < d`. Otherwise the quotient needs another multiple of `n`,
+    /// as long as the selectors' windows are shorter than the trace. Constraints of degree below
+    /// two need no more, as the quotient degree is padded to two anyway.
+    pub const fn constraint_degree(&self) -> usize {
+        let degree_multiple = self.degree_multiple();
+        if degree_multiple >= 2 && self.selector_degree() >= degree_multiple {
+            degree_multiple + 1
+        } else {
+            degree_multiple
+        }
+    }
 }
 
 impl<F: Field> Default for SymbolicExpression<F> {
@@ -132,10 +190,12 @@ where
             (Self::Constant(lhs), Self::Constant(rhs)) => Self::Constant(lhs + rhs),
             (lhs, rhs) => {
                 let degree_multiple = cmp::max(lhs.degree_multiple(), rhs.degree_multiple());
+                let selector_degree = cmp::max(lhs.selector_degree(), rhs.selector_degree());
                 Self::Add {
                     x: Rc::new(lhs),
                     y: Rc::new(rhs),
                     degree_multiple,
+                    selector_degree,
                 }
             }
         }
@@ -174,10 +234,12 @@ where
             (Self::Constant(lhs), Self::Constant(rhs)) => Self::Constant(lhs - rhs),
             (lhs, rhs) => {
                 let degree_multiple = cmp::max(lhs.degree_multiple(), rhs.degree_multiple());
+                let selector_degree = cmp::max(lhs.selector_degree(), rhs.selector_degree());
                 Self::Sub {
                     x: Rc::new(lhs),
                     y: Rc::new(rhs),
                     degree_multiple,
+                    selector_degree,
                 }
             }
         }
@@ -201,9 +263,11 @@ impl<F: Field> Neg for SymbolicExpression<F> {
             Self::Constant(c) => Self::Constant(-c),
             expr => {
                 let degree_multiple = expr.degree_multiple();
+                let selector_degree = expr.selector_degree();
                 Self::Neg {
                     x: Rc::new(expr),
                     degree_multiple,
+                    selector_degree,
                 }
             }
         }
@@ -223,10 +287,13 @@ where
             (lhs, rhs) => {
                 #[allow(clippy::suspicious_arithmetic_impl)]
                 let degree_multiple = lhs.degree_multiple() + rhs.degree_multiple();
+                #[allow(clippy::suspicious_arithmetic_impl)]
+                let selector_degree = lhs.selector_degree() + rhs.selector_degree();
                 Self::Mul {
                     x: Rc::new(lhs),
                     y: Rc::new(rhs),
                     degree_multiple,
+                    selector_degree,
                 }
             }
         }
@@ -7,10 +7,24 @@ use crate::symbolic_expression::SymbolicExpression;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Entry {
-    Preprocessed { offset: usize },
-    Main { offset: usize },
-    Permutation { offset: usize },
+    Preprocessed {
+        offset: usize,
+    },
+    Main {
+        offset: usize,
+    },
+    /// An extension field column of the main trace.
+    MainExtension {
+        offset: usize,
+    },
+    Permutation {
+        offset: usize,
+    },
     Public,
+    /// A public value over the extension field.
+    ExtensionPublic,
+    /// A periodic column, on the current row.
+    Periodic,
     Challenge,
 }
 
@@ -33,8 +47,12 @@ impl<F> SymbolicVariable<F> {
 
     pub const fn degree_multiple(&self) -> usize {
         match self.entry {
-            Entry::Preprocessed { .. } | Entry::Main { .. } | Entry::Permutation { .. } => 1,
-            Entry::Public | Entry::Challenge => 0,
+            Entry::Preprocessed { .. }
+            | Entry::Main { .. }
+            | Entry::MainExtension { .. }
+            | Entry::Permutation { .. }
+            | Entry::Periodic => 1,
+            Entry::Public | Entry::ExtensionPublic | Entry::Challenge => 0,
         }
     }
 }
@@ -0,0 +1,950 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ptr;
+
+use itertools::{izip, Itertools};
+use p3_air::{Air, BaseAir};
+use p3_challenger::{CanObserve, CanSample, FieldChallenger};
+use p3_commit::{Pcs, PolynomialSpace, ProverObserver, ProverPhase};
+use p3_field::{ExtensionField, Field, FieldAlgebra, FieldExtensionAlgebra};
+use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixView};
+use p3_matrix::extension::FlatMatrixView;
+use p3_matrix::stack::VerticalPair;
+use p3_matrix::Matrix;
+use p3_util::log2_strict_usize;
+use tracing::{info_span, instrument};
+
+use crate::instance::observe_instance;
+use crate::keys::observe_fingerprint;
+use crate::prover::{randomize_trace, start_phase};
+use crate::{
+    check_zeta_witness, degree_bits_supported, eval_permutation_constraints,
+    generate_permutation_trace, grind_for_zeta, is_zk, periodic_columns_fit,
+    periodic_values_at_point, quotient_values, recompose_quotient_from_chunks,
+    row_selectors_at_point, transition_window_selectors_at_point, unflatten_ext_openings,
+    window_points, window_size, Com, Commitments, Domain, Instance, OpenedValues, PcsError,
+    PcsProof, PcsProverData, PermutationQuotientData, PowWitness, ProverConstraintFolder,
+    ProverError, StarkGenericConfig, StarkProvingKey, StarkVerifyingKey, Val, VerificationError,
+    VerifierConstraintFolder, NUM_PERMUTATION_CHALLENGES,
+};
+
+/// One table of a proof by `prove_tables`: an AIR with its proving key, a trace, and the public
+/// inputs the trace satisfies the AIR for.
+pub struct ProverTable<'a, SC: StarkGenericConfig, A: ?Sized> {
+    pub air: &'a A,
+    pub proving_key: &'a StarkProvingKey<SC>,
+    pub trace: RowMajorMatrix<Val<SC>>,
+    /// The AIR's `extension_width()` main trace columns over the extension field, which must be
+    /// given if and only if there are any.
+    pub extension_trace: Option<RowMajorMatrix<SC::Challenge>>,
+    pub instance: &'a Instance<SC>,
+}
+
+/// One table of a proof checked by `verify_tables`: an AIR with its verifying key, the public
+/// inputs, and the table's part of the proof.
+pub struct VerifierTable<'a, SC: StarkGenericConfig, A: ?Sized> {
+    pub air: &'a A,
+    pub verifying_key: &'a StarkVerifyingKey<SC>,
+    pub instance: &'a Instance<SC>,
+    /// The log2 of the trace height claimed by the proof.
+    pub degree_bits: usize,
+    pub opened_values: &'a OpenedValues<SC::Challenge>,
+    /// The LogUp sum claimed by the proof, in `InteractionScope::Global`; ignored otherwise.
+    pub cumulative_sum: Option<SC::Challenge>,
+}
+
+/// Which sends and receives of the tables of a proof must balance.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InteractionScope {
+    /// Those of each table on its own, so every table's LogUp sum is zero.
+    Table,
+    /// Those of all tables together. Each table's LogUp sum is then part of the proof, and the sums
+    /// must add up to zero.
+    Global,
+}
+
+/// The output of `prove_tables`, which front ends such as `prove_batch` repackage as their proofs.
+pub struct TablesProof<SC: StarkGenericConfig> {
+    pub commitments: Commitments<Com<SC>>,
+    pub zeta_pow_witness: Option<PowWitness<SC>>,
+    /// The opened values of each table, in the order the tables were given.
+    pub opened_values: Vec<OpenedValues<SC::Challenge>>,
+    pub opening_proof: PcsProof<SC>,
+    /// The log2 of each table's trace height.
+    pub degree_bits: Vec<usize>,
+    /// The final value of each table's LogUp running sum, for tables with interactions.
+    pub cumulative_sums: Vec<Option<SC::Challenge>>,
+}
+
+/// A table whose main trace `prove_tables_with_trace_commit` commits to through a callback.
+pub(crate) struct CommittedTable<'a, SC: StarkGenericConfig, A: ?Sized> {
+    pub(crate) air: &'a A,
+    pub(crate) proving_key: &'a StarkProvingKey<SC>,
+    pub(crate) instance: &'a Instance<SC>,
+    /// The height of the main trace.
+    pub(crate) degree: usize,
+    /// The base field columns of the main trace, which the LogUp trace is generated from if the
+    /// AIR has interactions.
+    pub(crate) main_trace: Option<RowMajorMatrix<Val<SC>>>,
+}
+
+/// Prove that the trace of each of `tables` satisfies its AIR, in a single proof.
+///
+/// This is the core of every prover: the traces of all tables are committed to in one PCS round,
+/// their LogUp traces in another and their quotient chunks in a third, so the tables share their
+/// challenges and a single opening argument. Tables may have different AIRs and heights, and
+/// tables sharing a proving key share the opening of its preprocessed trace. The constraints are
+/// not checked in debug builds; the front ends do that where their AIRs allow it.
+///
+/// If the PCS is hiding, the proof is zero knowledge, apart from the LogUp sums in
+/// `InteractionScope::Global`.
+#[instrument(skip_all)]
+pub fn prove_tables<SC, A>(
+    config: &SC,
+    challenger: &mut SC::Challenger,
+    tables: Vec<ProverTable<'_, SC, A>>,
+    scope: InteractionScope,
+    observer: &mut impl ProverObserver,
+) -> Result<TablesProof<SC>, ProverError>
+where
+    SC: StarkGenericConfig,
+    A: for<'a> Air<ProverConstraintFolder<'a, SC>> + ?Sized,
+{
+    let mut traces = vec![];
+    let tables = tables
+        .into_iter()
+        .map(|table| {
+            let ProverTable {
+                air,
+                proving_key,
+                trace,
+                extension_trace,
+                instance,
+            } = table;
+            check_extension_trace(air, &trace, extension_trace.as_ref());
+            let degree = trace.height();
+            // The LogUp trace is generated from the main trace after it has been committed to.
+            let has_interactions = !air.sends().is_empty() || !air.receives().is_empty();
+            let main_trace = has_interactions.then(|| trace.clone());
+            traces.push(match extension_trace {
+                Some(extension_trace) => append_extension_columns(trace, extension_trace),
+                None => trace,
+            });
+            CommittedTable {
+                air,
+                proving_key,
+                instance,
+                degree,
+                main_trace,
+            }
+        })
+        .collect_vec();
+
+    prove_tables_with_trace_commit(
+        config,
+        challenger,
+        tables,
+        scope,
+        observer,
+        |domains, others| {
+            let pcs = config.pcs();
+            let mut evaluations = izip!(domains.iter().copied(), traces)
+                .map(|(domain, trace)| (domain, randomize_trace::<SC>(pcs, trace)))
+                .collect_vec();
+            evaluations.extend(others);
+            pcs.commit(evaluations)
+        },
+    )
+}
+
+/// As `prove_tables`, with `commit_traces` committing to the main trace of each table, randomized
+/// in zero-knowledge mode, over the given domain, followed by the other given evaluations.
+#[allow(clippy::type_complexity)]
+pub(crate) fn prove_tables_with_trace_commit<SC, A>(
+    config: &SC,
+    challenger: &mut SC::Challenger,
+    tables: Vec<CommittedTable<'_, SC, A>>,
+    scope: InteractionScope,
+    observer: &mut impl ProverObserver,
+    commit_traces: impl FnOnce(
+        &[Domain<SC>],
+        Vec<(Domain<SC>, RowMajorMatrix<Val<SC>>)>,
+    ) -> (Com<SC>, PcsProverData<SC>),
+) -> Result<TablesProof<SC>, ProverError>
+where
+    SC: StarkGenericConfig,
+    A: for<'a> Air<ProverConstraintFolder<'a, SC>> + ?Sized,
+{
+    assert!(!tables.is_empty(), "expected at least one table");
+    for table in &tables {
+        let verifying_key = &table.proving_key.verifying_key;
+        assert_eq!(
+            table.instance.public_values.len(),
+            verifying_key.num_public_values,
+            "the number of public values does not match the proving key"
+        );
+        assert_eq!(
+            table.instance.extension_public_values.len(),
+            verifying_key.num_extension_public_values,
+            "the number of extension field public values does not match the proving key"
+        );
+        if let Some(prep) = &table.proving_key.preprocessed {
+            assert_eq!(
+                1 << prep.degree_bits,
+                table.degree,
+                "preprocessed trace height must match the main trace height"
+            );
+        }
+    }
+
+    let pcs = config.pcs();
+    let is_zk = is_zk::<SC>();
+    let ext_degree = <SC::Challenge as FieldExtensionAlgebra<Val<SC>>>::D;
+
+    let degree_bits = tables
+        .iter()
+        .map(|table| log2_strict_usize(table.degree))
+        .collect_vec();
+    let quotient_degrees = tables
+        .iter()
+        .map(|table| 1 << table.proving_key.verifying_key.log_quotient_degree)
+        .collect_vec();
+    let trace_domains = tables
+        .iter()
+        .map(|table| pcs.natural_domain_for_degree(table.degree))
+        .collect_vec();
+    let quotient_domains = izip!(&trace_domains, &degree_bits, &quotient_degrees)
+        .map(|(trace_domain, &bits, &quotient_degree)| {
+            trace_domain.create_disjoint_domain(quotient_degree << bits)
+        })
+        .collect_vec();
+    // In zero-knowledge mode, traces are randomized to polynomials of twice their degree.
+    let trace_commit_domains = izip!(&tables, &trace_domains)
+        .map(|(table, &trace_domain)| {
+            if is_zk {
+                pcs.natural_domain_for_degree(2 * table.degree)
+            } else {
+                trace_domain
+            }
+        })
+        .collect_vec();
+    let sends = tables.iter().map(|table| table.air.sends()).collect_vec();
+    let receives = tables
+        .iter()
+        .map(|table| table.air.receives())
+        .collect_vec();
+    let has_interactions = izip!(&sends, &receives)
+        .map(|(sends, receives)| !sends.is_empty() || !receives.is_empty())
+        .collect_vec();
+    let preprocessed_owners = preprocessed_owners(
+        tables
+            .iter()
+            .map(|table| table.proving_key.preprocessed.as_ref()),
+    );
+
+    // In zero-knowledge mode we also commit to a random polynomial over each quotient domain, after
+    // the traces, which is added to that table's quotient to mask it.
+    let quotient_randomizers = quotient_domains
+        .iter()
+        .map(|domain| pcs.random_matrix(domain.size(), ext_degree))
+        .collect_vec();
+
+    let phase = ProverPhase::TraceCommit {
+        height: tables.iter().map(|table| table.degree).max().unwrap(),
+        width: tables
+            .iter()
+            .map(|table| table.air.width() + table.air.extension_width() * ext_degree)
+            .sum(),
+    };
+    start_phase(observer, phase)?;
+    let (trace_commit, trace_data) = info_span!("commit to trace data").in_scope(|| {
+        let others = izip!(&quotient_domains, &quotient_randomizers)
+            .filter_map(|(&domain, randomizer)| Some((domain, randomizer.clone()?)))
+            .collect();
+        commit_traces(&trace_commit_domains, others)
+    });
+    observer.phase_finished(phase);
+
+    // Observe the instances, starting with each AIR itself so that transcripts of distinct AIRs
+    // diverge immediately.
+    for (table, &bits) in izip!(&tables, &degree_bits) {
+        observe_fingerprint::<SC>(challenger, &table.proving_key.verifying_key.fingerprint);
+        challenger.observe(Val::<SC>::from_canonical_usize(bits));
+        if let Some(prep) = &table.proving_key.preprocessed {
+            challenger.observe(prep.commitment.clone());
+        }
+        observe_instance::<SC>(challenger, table.instance);
+    }
+    challenger.observe(trace_commit.clone());
+    for table in &tables {
+        challenger.observe_slice(&table.instance.public_values);
+    }
+
+    let mut permutation_challenges = vec![];
+    let mut cumulative_sums = vec![None; tables.len()];
+    // The index of each table's LogUp trace in the permutation commitment, if it has one.
+    let mut permutation_indices = vec![None; tables.len()];
+    let permutation = if has_interactions.contains(&true) {
+        if observer.is_cancelled() {
+            return Err(ProverError::Cancelled);
+        }
+        permutation_challenges = (0..NUM_PERMUTATION_CHALLENGES)
+            .map(|_| challenger.sample_ext_element())
+            .collect_vec();
+        let mut permutation_traces = vec![];
+        for (i, table) in tables.iter().enumerate() {
+            if !has_interactions[i] {
+                continue;
+            }
+            let main_trace = table
+                .main_trace
+                .as_ref()
+                .expect("the main trace is needed for the AIR's interactions");
+            let permutation_trace = generate_permutation_trace(
+                table
+                    .proving_key
+                    .preprocessed
+                    .as_ref()
+                    .map(|prep| &prep.trace),
+                main_trace,
+                &sends[i],
+                &receives[i],
+                &permutation_challenges,
+            );
+            let cumulative_sum = *permutation_trace.values.last().unwrap();
+            if scope == InteractionScope::Table && cumulative_sum != SC::Challenge::ZERO {
+                return Err(ProverError::UnbalancedInteractions);
+            }
+            cumulative_sums[i] = Some(cumulative_sum);
+            permutation_indices[i] = Some(permutation_traces.len());
+            permutation_traces.push((
+                trace_commit_domains[i],
+                randomize_trace::<SC>(pcs, permutation_trace.flatten_to_base()),
+            ));
+        }
+        if cumulative_sums
+            .iter()
+            .flatten()
+            .copied()
+            .sum::<SC::Challenge>()
+            != SC::Challenge::ZERO
+        {
+            return Err(ProverError::UnbalancedInteractions);
+        }
+        let phase = ProverPhase::PermutationCommit {
+            height: izip!(&tables, &has_interactions)
+                .filter(|(_, &has_interactions)| has_interactions)
+                .map(|(table, _)| table.degree)
+                .max()
+                .unwrap(),
+            width: permutation_traces
+                .iter()
+                .map(|(_, trace)| trace.width())
+                .sum(),
+        };
+        observer.phase_started(phase);
+        let (permutation_commit, permutation_data) =
+            info_span!("commit to permutation traces").in_scope(|| pcs.commit(permutation_traces));
+        observer.phase_finished(phase);
+        challenger.observe(permutation_commit.clone());
+        if scope == InteractionScope::Global {
+            for &cumulative_sum in cumulative_sums.iter().flatten() {
+                challenger.observe_ext_element(cumulative_sum);
+            }
+        }
+        Some((permutation_commit, permutation_data))
+    } else {
+        None
+    };
+
+    let alpha: SC::Challenge = challenger.sample_ext_element();
+
+    let phase = ProverPhase::Quotient {
+        size: quotient_domains.iter().map(PolynomialSpace::size).sum(),
+    };
+    start_phase(observer, phase)?;
+    let mut quotient_chunk_evals = vec![];
+    for (i, table) in tables.iter().enumerate() {
+        let quotient_domain = quotient_domains[i];
+        let preprocessed_on_quotient_domain = table
+            .proving_key
+            .preprocessed
+            .as_ref()
+            .map(|prep| pcs.get_evaluations_on_domain(&prep.prover_data, 0, quotient_domain));
+        let trace_on_quotient_domain =
+            pcs.get_evaluations_on_domain(&trace_data, i, quotient_domain);
+        let permutation_quotient_data = permutation.as_ref().and_then(|(_, permutation_data)| {
+            Some(PermutationQuotientData {
+                sends: &sends[i],
+                receives: &receives[i],
+                challenges: &permutation_challenges,
+                cumulative_sum: cumulative_sums[i]?,
+                trace_on_quotient_domain: pcs.get_evaluations_on_domain(
+                    permutation_data,
+                    permutation_indices[i]?,
+                    quotient_domain,
+                ),
+            })
+        });
+        let instance = table.instance;
+        let constraint_count = table.proving_key.constraint_count;
+        // The compiled constraints, if any, are evaluated in place of the AIR's.
+        let mut quotient_values = match &table.proving_key.compiled {
+            Some(compiled) => quotient_values(
+                compiled,
+                &instance.public_values,
+                &instance.extension_public_values,
+                trace_domains[i],
+                quotient_domain,
+                preprocessed_on_quotient_domain,
+                trace_on_quotient_domain,
+                permutation_quotient_data,
+                alpha,
+                constraint_count,
+            ),
+            None => quotient_values(
+                table.air,
+                &instance.public_values,
+                &instance.extension_public_values,
+                trace_domains[i],
+                quotient_domain,
+                preprocessed_on_quotient_domain,
+                trace_on_quotient_domain,
+                permutation_quotient_data,
+                alpha,
+                constraint_count,
+            ),
+        };
+        if let Some(randomizer) = &quotient_randomizers[i] {
+            for (value, random_coeffs) in quotient_values.iter_mut().zip(randomizer.row_slices()) {
+                *value += SC::Challenge::from_base_slice(random_coeffs);
+            }
+        }
+        let quotient_flat = RowMajorMatrix::new_col(quotient_values).flatten_to_base();
+        let quotient_chunks = quotient_domain.split_evals(quotient_degrees[i], quotient_flat);
+        let qc_domains = quotient_domain.split_domains(quotient_degrees[i]);
+        quotient_chunk_evals.extend(izip!(qc_domains, quotient_chunks));
+    }
+    observer.phase_finished(phase);
+
+    let phase = ProverPhase::QuotientCommit {
+        num_chunks: quotient_chunk_evals.len(),
+        height: tables.iter().map(|table| table.degree).max().unwrap(),
+    };
+    start_phase(observer, phase)?;
+    let (quotient_commit, quotient_data) =
+        info_span!("commit to quotient poly chunks").in_scope(|| pcs.commit(quotient_chunk_evals));
+    observer.phase_finished(phase);
+    challenger.observe(quotient_commit.clone());
+
+    let commitments = Commitments {
+        trace: trace_commit,
+        permutation: permutation
+            .as_ref()
+            .map(|(permutation_commit, _)| permutation_commit.clone()),
+        quotient_chunks: quotient_commit,
+    };
+
+    let zeta_pow_witness = if config.zeta_proof_of_work_bits() > 0 {
+        let phase = ProverPhase::ProofOfWork {
+            bits: config.zeta_proof_of_work_bits(),
+        };
+        start_phase(observer, phase)?;
+        let witness = grind_for_zeta(config, challenger);
+        observer.phase_finished(phase);
+        witness
+    } else {
+        None
+    };
+    let zeta: SC::Challenge = challenger.sample();
+
+    let window_points = izip!(&tables, &trace_domains)
+        .map(|(table, domain)| {
+            window_points(domain, zeta, &table.proving_key.verifying_key.row_offsets)
+        })
+        .collect_vec();
+    let mut trace_points = window_points.clone();
+    if is_zk {
+        trace_points.extend(tables.iter().map(|_| vec![zeta]));
+    }
+    let quotient_points = quotient_degrees
+        .iter()
+        .flat_map(|&quotient_degree| (0..quotient_degree).map(|_| vec![zeta]))
+        .collect_vec();
+    let mut rounds = vec![
+        (&trace_data, trace_points),
+        (&quotient_data, quotient_points),
+    ];
+    for (i, table) in tables.iter().enumerate() {
+        if preprocessed_owners[i] == Some(i) {
+            let prep = table.proving_key.preprocessed.as_ref().unwrap();
+            rounds.push((&prep.prover_data, vec![window_points[i].clone()]));
+        }
+    }
+    if let Some((_, permutation_data)) = &permutation {
+        let permutation_points = izip!(&trace_domains, &has_interactions)
+            .filter(|(_, &has_interactions)| has_interactions)
+            .map(|(domain, _)| vec![zeta, domain.next_point(zeta).unwrap()])
+            .collect_vec();
+        rounds.push((permutation_data, permutation_points));
+    }
+
+    let phase = ProverPhase::Open {
+        num_rounds: rounds.len(),
+    };
+    start_phase(observer, phase)?;
+    let (opened_values, opening_proof) =
+        info_span!("open").in_scope(|| pcs.open_observed(rounds, challenger, observer));
+    observer.phase_finished(phase);
+
+    let num_tables = tables.len();
+    let mut quotient_openings = opened_values[1].iter();
+    let mut extra_rounds = opened_values[2..].iter();
+    let mut preprocessed_openings = (0..num_tables)
+        .map(|i| {
+            if preprocessed_owners[i] == Some(i) {
+                extra_rounds.next().unwrap()[0].clone()
+            } else {
+                vec![]
+            }
+        })
+        .collect_vec()
+        .into_iter();
+    let mut permutation_openings = extra_rounds.next().into_iter().flatten();
+    let opened_values = (0..num_tables)
+        .map(|i| {
+            let (permutation_local, permutation_next) = if has_interactions[i] {
+                let openings = permutation_openings.next().unwrap();
+                (openings[0].clone(), openings[1].clone())
+            } else {
+                (vec![], vec![])
+            };
+            OpenedValues {
+                preprocessed: preprocessed_openings.next().unwrap(),
+                trace: opened_values[0][i].clone(),
+                permutation_local,
+                permutation_next,
+                quotient_chunks: quotient_openings
+                    .by_ref()
+                    .take(quotient_degrees[i])
+                    .map(|chunk| chunk[0].clone())
+                    .collect(),
+                quotient_randomizer: if is_zk {
+                    opened_values[0][num_tables + i][0].clone()
+                } else {
+                    vec![]
+                },
+            }
+        })
+        .collect();
+
+    Ok(TablesProof {
+        commitments,
+        zeta_pow_witness,
+        opened_values,
+        opening_proof,
+        degree_bits,
+        cumulative_sums,
+    })
+}
+
+/// Verify the part of a proof generated by `prove_tables` which is shared by `tables`: their
+/// commitments, the proof of work ground before `zeta` and the opening proof.
+#[instrument(skip_all)]
+pub fn verify_tables<SC, A>(
+    config: &SC,
+    challenger: &mut SC::Challenger,
+    commitments: &Commitments<Com<SC>>,
+    zeta_pow_witness: Option<PowWitness<SC>>,
+    opening_proof: &PcsProof<SC>,
+    tables: &[VerifierTable<'_, SC, A>],
+    scope: InteractionScope,
+) -> Result<(), VerificationError<PcsError<SC>>>
+where
+    SC: StarkGenericConfig,
+    A: for<'a> Air<VerifierConstraintFolder<'a, SC>> + ?Sized,
+{
+    let pcs = config.pcs();
+    let is_zk = is_zk::<SC>();
+    let ext_degree = <SC::Challenge as FieldExtensionAlgebra<Val<SC>>>::D;
+
+    let sends = tables
+        .iter()
+        .map(|table| <A as BaseAir<Val<SC>>>::sends(table.air))
+        .collect_vec();
+    let receives = tables
+        .iter()
+        .map(|table| <A as BaseAir<Val<SC>>>::receives(table.air))
+        .collect_vec();
+    let has_interactions = izip!(&sends, &receives)
+        .map(|(sends, receives)| !sends.is_empty() || !receives.is_empty())
+        .collect_vec();
+    let preprocessed_owners = preprocessed_owners(
+        tables
+            .iter()
+            .map(|table| table.verifying_key.preprocessed.as_ref()),
+    );
+    // Each table's LogUp sum, which is zero unless the proof claims otherwise.
+    let cumulative_sums = izip!(tables, &has_interactions)
+        .map(|(table, &has_interactions)| match scope {
+            InteractionScope::Table => has_interactions.then_some(SC::Challenge::ZERO),
+            InteractionScope::Global => table.cumulative_sum,
+        })
+        .collect_vec();
+
+    let valid_shape = !tables.is_empty()
+        && commitments.permutation.is_some() == has_interactions.contains(&true)
+        && zeta_pow_witness.is_some() == (config.zeta_proof_of_work_bits() > 0)
+        && izip!(tables, &sends, &receives, &cumulative_sums)
+            .enumerate()
+            .all(|(i, (table, sends, receives, cumulative_sum))| {
+                let verifying_key = table.verifying_key;
+                let opened_values = table.opened_values;
+                let degree_bits = table.degree_bits;
+                if !degree_bits_supported(config, degree_bits, verifying_key.log_quotient_degree) {
+                    return false;
+                }
+                let num_interactions = sends.len() + receives.len();
+                let permutation_width = if num_interactions == 0 {
+                    0
+                } else {
+                    (num_interactions + 1) * ext_degree
+                };
+                let row_offsets = &verifying_key.row_offsets;
+                // Extension field columns are committed to after the base field columns, flattened.
+                let trace_width = <A as BaseAir<Val<SC>>>::width(table.air)
+                    + <A as BaseAir<Val<SC>>>::extension_width(table.air) * ext_degree;
+                let valid_window = |rows: &[Vec<SC::Challenge>], width: usize| {
+                    rows.len() == row_offsets.len() && rows.iter().all(|row| row.len() == width)
+                };
+                table.instance.public_values.len() == verifying_key.num_public_values
+                    && table.instance.extension_public_values.len()
+                        == verifying_key.num_extension_public_values
+                    && <A as BaseAir<Val<SC>>>::row_selectors(table.air)
+                        .iter()
+                        .all(|selector| selector.is_valid_for_height(1 << degree_bits))
+                    && periodic_columns_fit(
+                        &<A as BaseAir<Val<SC>>>::periodic_columns(table.air),
+                        1 << degree_bits,
+                    )
+                    && match &verifying_key.preprocessed {
+                        // The openings of a shared preprocessed trace are with its first table.
+                        Some(prep) if preprocessed_owners[i] == Some(i) => {
+                            prep.degree_bits == degree_bits
+                                && valid_window(&opened_values.preprocessed, prep.width)
+                        }
+                        Some(prep) => {
+                            prep.degree_bits == degree_bits && opened_values.preprocessed.is_empty()
+                        }
+                        None => opened_values.preprocessed.is_empty(),
+                    }
+                    && valid_window(&opened_values.trace, trace_width)
+                    && opened_values.permutation_local.len() == permutation_width
+                    && opened_values.permutation_next.len() == permutation_width
+                    && cumulative_sum.is_some() == (num_interactions != 0)
+                    && opened_values.quotient_chunks.len() == 1 << verifying_key.log_quotient_degree
+                    && opened_values
+                        .quotient_chunks
+                        .iter()
+                        .all(|qc| qc.len() == ext_degree)
+                    && opened_values.quotient_randomizer.len() == if is_zk { ext_degree } else { 0 }
+            });
+    if !valid_shape {
+        return Err(VerificationError::InvalidProofShape);
+    }
+
+    // The sends and receives of all tables must balance.
+    if cumulative_sums
+        .iter()
+        .flatten()
+        .copied()
+        .sum::<SC::Challenge>()
+        != SC::Challenge::ZERO
+    {
+        return Err(VerificationError::UnbalancedInteractions);
+    }
+
+    let trace_domains = tables
+        .iter()
+        .map(|table| pcs.natural_domain_for_degree(1 << table.degree_bits))
+        .collect_vec();
+    let trace_commit_domains = izip!(tables, &trace_domains)
+        .map(|(table, &trace_domain)| {
+            if is_zk {
+                pcs.natural_domain_for_degree(2 << table.degree_bits)
+            } else {
+                trace_domain
+            }
+        })
+        .collect_vec();
+    let quotient_domains = izip!(tables, &trace_domains)
+        .map(|(table, trace_domain)| {
+            trace_domain.create_disjoint_domain(
+                1 << (table.degree_bits + table.verifying_key.log_quotient_degree),
+            )
+        })
+        .collect_vec();
+    let quotient_chunks_domains = izip!(tables, &quotient_domains)
+        .map(|(table, domain)| domain.split_domains(1 << table.verifying_key.log_quotient_degree))
+        .collect_vec();
+
+    // Observe the instances, starting with each AIR itself so that transcripts of distinct AIRs
+    // diverge immediately.
+    for table in tables {
+        observe_fingerprint::<SC>(challenger, &table.verifying_key.fingerprint);
+        challenger.observe(Val::<SC>::from_canonical_usize(table.degree_bits));
+        if let Some(prep) = &table.verifying_key.preprocessed {
+            challenger.observe(prep.commitment.clone());
+        }
+        observe_instance::<SC>(challenger, table.instance);
+    }
+    challenger.observe(commitments.trace.clone());
+    for table in tables {
+        challenger.observe_slice(&table.instance.public_values);
+    }
+
+    let mut permutation_challenges = vec![];
+    if let Some(permutation_commit) = &commitments.permutation {
+        permutation_challenges = (0..NUM_PERMUTATION_CHALLENGES)
+            .map(|_| challenger.sample_ext_element())
+            .collect_vec();
+        challenger.observe(permutation_commit.clone());
+        if scope == InteractionScope::Global {
+            for &cumulative_sum in cumulative_sums.iter().flatten() {
+                challenger.observe_ext_element(cumulative_sum);
+            }
+        }
+    }
+
+    let alpha: SC::Challenge = challenger.sample_ext_element();
+    challenger.observe(commitments.quotient_chunks.clone());
+
+    if !check_zeta_witness(config, challenger, zeta_pow_witness) {
+        return Err(VerificationError::InvalidPowWitness);
+    }
+    let zeta: SC::Challenge = challenger.sample();
+
+    let window_points = izip!(tables, &trace_domains)
+        .map(|(table, domain)| window_points(domain, zeta, &table.verifying_key.row_offsets))
+        .collect_vec();
+    let mut trace_openings = izip!(&trace_commit_domains, &window_points, tables)
+        .map(|(&trace_commit_domain, points, table)| {
+            (
+                trace_commit_domain,
+                izip!(points.iter().copied(), table.opened_values.trace.clone()).collect_vec(),
+            )
+        })
+        .collect_vec();
+    if is_zk {
+        trace_openings.extend(
+            izip!(&quotient_domains, tables).map(|(&quotient_domain, table)| {
+                (
+                    quotient_domain,
+                    vec![(zeta, table.opened_values.quotient_randomizer.clone())],
+                )
+            }),
+        );
+    }
+    let quotient_openings = izip!(&quotient_chunks_domains, tables)
+        .flat_map(|(domains, table)| {
+            izip!(domains, &table.opened_values.quotient_chunks)
+                .map(|(&domain, values)| (domain, vec![(zeta, values.clone())]))
+        })
+        .collect_vec();
+    let mut rounds = vec![
+        (commitments.trace.clone(), trace_openings),
+        (commitments.quotient_chunks.clone(), quotient_openings),
+    ];
+    for (i, table) in tables.iter().enumerate() {
+        if preprocessed_owners[i] == Some(i) {
+            let prep = table.verifying_key.preprocessed.as_ref().unwrap();
+            rounds.push((
+                prep.commitment.clone(),
+                vec![(
+                    trace_domains[i],
+                    izip!(
+                        window_points[i].iter().copied(),
+                        table.opened_values.preprocessed.clone()
+                    )
+                    .collect_vec(),
+                )],
+            ));
+        }
+    }
+    if let Some(permutation_commit) = &commitments.permutation {
+        let permutation_openings = izip!(
+            &trace_domains,
+            &trace_commit_domains,
+            tables,
+            &has_interactions
+        )
+        .filter(|(_, _, _, &has_interactions)| has_interactions)
+        .map(|(trace_domain, &trace_commit_domain, table, _)| {
+            (
+                trace_commit_domain,
+                vec![
+                    (zeta, table.opened_values.permutation_local.clone()),
+                    (
+                        trace_domain.next_point(zeta).unwrap(),
+                        table.opened_values.permutation_next.clone(),
+                    ),
+                ],
+            )
+        })
+        .collect_vec();
+        rounds.push((permutation_commit.clone(), permutation_openings));
+    }
+
+    pcs.verify(rounds, opening_proof, challenger)
+        .map_err(VerificationError::InvalidOpeningArgument)?;
+
+    for (i, table) in tables.iter().enumerate() {
+        let opened_values = table.opened_values;
+        let trace_domain = &trace_domains[i];
+        let mut quotient = recompose_quotient_from_chunks::<SC>(
+            &quotient_chunks_domains[i],
+            &opened_values.quotient_chunks,
+            zeta,
+        );
+        // In zero-knowledge mode, the committed quotient is masked by a random polynomial, which is
+        // empty otherwise.
+        quotient -= opened_values
+            .quotient_randomizer
+            .iter()
+            .enumerate()
+            .map(|(e_i, &c)| SC::Challenge::monomial(e_i) * c)
+            .sum::<SC::Challenge>();
+
+        let row_offsets = &table.verifying_key.row_offsets;
+        let sels = trace_domain.selectors_at_point(zeta);
+        let is_transition_windows =
+            transition_window_selectors_at_point(trace_domain, zeta, window_size(row_offsets));
+        let row_selectors = row_selectors_at_point(
+            trace_domain,
+            zeta,
+            &<A as BaseAir<Val<SC>>>::row_selectors(table.air),
+        );
+        let periodic_values = periodic_values_at_point(
+            trace_domain,
+            zeta,
+            &<A as BaseAir<Val<SC>>>::periodic_columns(table.air),
+        );
+
+        let preprocessed_width = table
+            .verifying_key
+            .preprocessed
+            .as_ref()
+            .map_or(0, |prep| prep.width);
+        let preprocessed_values = preprocessed_owners[i].map_or(vec![], |owner| {
+            tables[owner].opened_values.preprocessed.concat()
+        });
+        let air_width = <A as BaseAir<Val<SC>>>::width(table.air);
+        let extension_width = <A as BaseAir<Val<SC>>>::extension_width(table.air);
+        let main_values = opened_values
+            .trace
+            .iter()
+            .flat_map(|row| row[..air_width].to_vec())
+            .collect_vec();
+        let main_ext_values = opened_values
+            .trace
+            .iter()
+            .flat_map(|row| unflatten_ext_openings::<Val<SC>, _>(&row[air_width..]))
+            .collect_vec();
+        let permutation_local =
+            unflatten_ext_openings::<Val<SC>, _>(&opened_values.permutation_local);
+        let permutation_next =
+            unflatten_ext_openings::<Val<SC>, _>(&opened_values.permutation_next);
+
+        let mut folder = VerifierConstraintFolder {
+            preprocessed: RowMajorMatrixView::new(&preprocessed_values, preprocessed_width),
+            main: RowMajorMatrixView::new(&main_values, air_width),
+            main_ext: RowMajorMatrixView::new(&main_ext_values, extension_width),
+            permutation: VerticalPair::new(
+                RowMajorMatrixView::new_row(&permutation_local),
+                RowMajorMatrixView::new_row(&permutation_next),
+            ),
+            permutation_challenges: &permutation_challenges,
+            public_values: &table.instance.public_values,
+            extension_public_values: &table.instance.extension_public_values,
+            is_first_row: sels.is_first_row,
+            is_last_row: sels.is_last_row,
+            is_transition_windows: &is_transition_windows,
+            row_selectors: &row_selectors,
+            periodic_values: &periodic_values,
+            alpha,
+            accumulator: SC::Challenge::ZERO,
+        };
+        table.air.eval(&mut folder);
+        if let Some(cumulative_sum) = cumulative_sums[i] {
+            eval_permutation_constraints(&mut folder, &sends[i], &receives[i], cumulative_sum);
+        }
+        let folded_constraints = folder.accumulator;
+
+        // Check that folded_constraints(zeta) / Z_H(zeta) = quotient(zeta) for this table.
+        if folded_constraints * sels.inv_zeroifier != quotient {
+            return Err(VerificationError::OodEvaluationMismatch);
+        }
+    }
+
+    Ok(())
+}
+
+/// For each table, the first table with the same preprocessed trace, which holds its openings; or
+/// `None` if it has no preprocessed trace. Tables share a preprocessed trace when they share a key.
+fn preprocessed_owners<'a, T: 'a>(
+    preprocessed: impl Iterator<Item = Option<&'a T>>,
+) -> Vec<Option<usize>> {
+    let preprocessed = preprocessed.collect_vec();
+    preprocessed
+        .iter()
+        .map(|prep| {
+            let prep = (*prep)?;
+            preprocessed
+                .iter()
+                .position(|other| other.is_some_and(|other| ptr::eq(other, prep)))
+        })
+        .collect()
+}
+
+/// Check that `extension_trace` holds the extension field columns of `air`, if it has any, for a
+/// main trace `trace`.
+pub(crate) fn check_extension_trace<F: Field, EF: ExtensionField<F>, A: BaseAir<F> + ?Sized>(
+    air: &A,
+    trace: &RowMajorMatrix<F>,
+    extension_trace: Option<&RowMajorMatrix<EF>>,
+) {
+    match extension_trace {
+        Some(extension_trace) => {
+            assert_eq!(
+                extension_trace.width(),
+                air.extension_width(),
+                "the extension trace width does not match the AIR"
+            );
+            assert_eq!(
+                extension_trace.height(),
+                trace.height(),
+                "the extension trace height must match the main trace height"
+            );
+        }
+        None => assert_eq!(
+            air.extension_width(),
+            0,
+            "the AIR has extension field columns, but no extension trace was given"
+        ),
+    }
+}
+
+/// Append the columns of `extension_trace`, flattened to base field columns, to those of `trace`.
+fn append_extension_columns<F: Field, EF: ExtensionField<F>>(
+    trace: RowMajorMatrix<F>,
+    extension_trace: RowMajorMatrix<EF>,
+) -> RowMajorMatrix<F> {
+    let extension_trace = FlatMatrixView::<F, EF, _>::new(extension_trace);
+    let width = trace.width() + extension_trace.width();
+    let values = trace
+        .row_slices()
+        .enumerate()
+        .flat_map(|(r, row)| row.iter().copied().chain(extension_trace.row(r)))
+        .collect();
+    RowMajorMatrix::new(values, width)
+}
@@ -1,18 +1,21 @@
-use alloc::vec;
 use alloc::vec::Vec;
 
 use itertools::Itertools;
-use p3_air::{Air, BaseAir};
-use p3_challenger::{CanObserve, CanSample, FieldChallenger};
-use p3_commit::{Pcs, PolynomialSpace};
-use p3_field::{Field, FieldAlgebra, FieldExtensionAlgebra};
-use p3_matrix::dense::RowMajorMatrixView;
-use p3_matrix::stack::VerticalPair;
+use p3_air::Air;
+use p3_commit::PolynomialSpace;
+use p3_field::{ExtensionField, Field, FieldExtensionAlgebra};
 use tracing::instrument;
 
-use crate::symbolic_builder::{get_log_quotient_degree, SymbolicAirBuilder};
-use crate::{PcsError, Proof, StarkGenericConfig, Val, VerifierConstraintFolder};
+use crate::symbolic_builder::SymbolicAirBuilder;
+use crate::{
+    setup_keys, verify_tables, Domain, Instance, InteractionScope, PcsError, Proof,
+    StarkGenericConfig, StarkVerifyingKey, Val, VerifierConstraintFolder, VerifierTable,
+};
 
+/// Verify a proof that was generated by `prove`.
+///
+/// The AIR's keys are computed on the fly; use `verify_with_key` to reuse a verifying key computed
+/// once by `setup_keys`.
 #[instrument(skip_all)]
 pub fn verify<SC, A>(
     config: &SC,
@@ -24,78 +27,93 @@ pub fn verify<SC, A>(
 where
     SC: StarkGenericConfig,
     A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+{
+    let (_, verifying_key) = setup_keys(config, air, public_values.len());
+    verify_with_key(
+        config,
+        &verifying_key,
+        air,
+        challenger,
+        proof,
+        public_values,
+    )
+}
+
+/// Verify a proof, given the verifying key of `air` from `setup_keys`.
+#[instrument(skip_all)]
+#[allow(clippy::ptr_arg)] // Takes a `Vec` like the other entry points.
+pub fn verify_with_key<SC, A>(
+    config: &SC,
+    verifying_key: &StarkVerifyingKey<SC>,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    proof: &Proof<SC>,
+    public_values: &Vec<Val<SC>>,
+) -> Result<(), VerificationError<PcsError<SC>>>
+where
+    SC: StarkGenericConfig,
+    A: for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+{
+    verify_with_instance(
+        config,
+        verifying_key,
+        air,
+        challenger,
+        proof,
+        &Instance::new(public_values.clone()),
+    )
+}
+
+/// Verify a proof generated by `prove_with_instance` for the public inputs `instance`, given the
+/// verifying key of `air` from `setup_keys`.
+#[instrument(skip_all)]
+pub fn verify_with_instance<SC, A>(
+    config: &SC,
+    verifying_key: &StarkVerifyingKey<SC>,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    proof: &Proof<SC>,
+    instance: &Instance<SC>,
+) -> Result<(), VerificationError<PcsError<SC>>>
+where
+    SC: StarkGenericConfig,
+    A: for<'a> Air<VerifierConstraintFolder<'a, SC>>,
 {
     let Proof {
         commitments,
+        zeta_pow_witness,
         opened_values,
         opening_proof,
         degree_bits,
     } = proof;
-
-    let degree = 1 << degree_bits;
-    let log_quotient_degree = get_log_quotient_degree::<Val<SC>, A>(air, 0, public_values.len());
-    let quotient_degree = 1 << log_quotient_degree;
-
-    let pcs = config.pcs();
-    let trace_domain = pcs.natural_domain_for_degree(degree);
-    let quotient_domain =
-        trace_domain.create_disjoint_domain(1 << (degree_bits + log_quotient_degree));
-    let quotient_chunks_domains = quotient_domain.split_domains(quotient_degree);
-
-    let air_width = <A as BaseAir<Val<SC>>>::width(air);
-    let valid_shape = opened_values.trace_local.len() == air_width
-        && opened_values.trace_next.len() == air_width
-        && opened_values.quotient_chunks.len() == quotient_degree
-        && opened_values
-            .quotient_chunks
-            .iter()
-            .all(|qc| qc.len() == <SC::Challenge as FieldExtensionAlgebra<Val<SC>>>::D);
-    if !valid_shape {
-        return Err(VerificationError::InvalidProofShape);
-    }
-
-    // Observe the instance.
-    challenger.observe(Val::<SC>::from_canonical_usize(proof.degree_bits));
-    // TODO: Might be best practice to include other instance data here in the transcript, like some
-    // encoding of the AIR. This protects against transcript collisions between distinct instances.
-    // Practically speaking though, the only related known attack is from failing to include public
-    // values. It's not clear if failing to include other instance data could enable a transcript
-    // collision, since most such changes would completely change the set of satisfying witnesses.
-
-    challenger.observe(commitments.trace.clone());
-    challenger.observe_slice(public_values);
-    let alpha: SC::Challenge = challenger.sample_ext_element();
-    challenger.observe(commitments.quotient_chunks.clone());
-
-    let zeta: SC::Challenge = challenger.sample();
-    let zeta_next = trace_domain.next_point(zeta).unwrap();
-
-    pcs.verify(
-        vec![
-            (
-                commitments.trace.clone(),
-                vec![(
-                    trace_domain,
-                    vec![
-                        (zeta, opened_values.trace_local.clone()),
-                        (zeta_next, opened_values.trace_next.clone()),
-                    ],
-                )],
-            ),
-            (
-                commitments.quotient_chunks.clone(),
-                quotient_chunks_domains
-                    .iter()
-                    .zip(&opened_values.quotient_chunks)
-                    .map(|(domain, values)| (*domain, vec![(zeta, values.clone())]))
-                    .collect_vec(),
-            ),
-        ],
-        opening_proof,
+    let table = VerifierTable {
+        air,
+        verifying_key,
+        instance,
+        degree_bits: *degree_bits,
+        opened_values,
+        cumulative_sum: None,
+    };
+    verify_tables(
+        config,
         challenger,
+        commitments,
+        *zeta_pow_witness,
+        opening_proof,
+        &[table],
+        InteractionScope::Table,
     )
-    .map_err(VerificationError::InvalidOpeningArgument)?;
+}
 
+/// Recompose the value of the quotient polynomial at `zeta` from the opened values of its chunks.
+pub fn recompose_quotient_from_chunks<SC>(
+    quotient_chunks_domains: &[Domain<SC>],
+    quotient_chunks: &[Vec<SC::Challenge>],
+    zeta: SC::Challenge,
+) -> SC::Challenge
+where
+    SC: StarkGenericConfig,
+{
     let zps = quotient_chunks_domains
         .iter()
         .enumerate()
@@ -112,8 +130,7 @@ where
         })
         .collect_vec();
 
-    let quotient = opened_values
-        .quotient_chunks
+    quotient_chunks
         .iter()
         .enumerate()
         .map(|(ch_i, ch)| {
@@ -122,34 +139,23 @@ where
                 .map(|(e_i, &c)| zps[ch_i] * SC::Challenge::monomial(e_i) * c)
                 .sum::<SC::Challenge>()
         })
-        .sum::<SC::Challenge>();
-
-    let sels = trace_domain.selectors_at_point(zeta);
-
-    let main = VerticalPair::new(
-        RowMajorMatrixView::new_row(&opened_values.trace_local),
-        RowMajorMatrixView::new_row(&opened_values.trace_next),
-    );
-
-    let mut folder = VerifierConstraintFolder {
-        main,
-        public_values,
-        is_first_row: sels.is_first_row,
-        is_last_row: sels.is_last_row,
-        is_transition: sels.is_transition,
-        alpha,
-        accumulator: SC::Challenge::ZERO,
-    };
-    air.eval(&mut folder);
-    let folded_constraints = folder.accumulator;
-
-    // Finally, check that
-    //     folded_constraints(zeta) / Z_H(zeta) = quotient(zeta)
-    if folded_constraints * sels.inv_zeroifier != quotient {
-        return Err(VerificationError::OodEvaluationMismatch);
-    }
+        .sum::<SC::Challenge>()
+}
 
-    Ok(())
+/// Reassemble extension field values from openings of extension field columns that were committed
+/// to as base field columns, as by `RowMajorMatrix::flatten_to_base`. This is the case for the
+/// permutation trace and for the extension field columns of the main trace.
+pub fn unflatten_ext_openings<F: Field, EF: ExtensionField<F>>(values: &[EF]) -> Vec<EF> {
+    values
+        .chunks_exact(<EF as FieldExtensionAlgebra<F>>::D)
+        .map(|coeffs| {
+            coeffs
+                .iter()
+                .enumerate()
+                .map(|(i, &coeff)| <EF as FieldExtensionAlgebra<F>>::monomial(i) * coeff)
+                .sum()
+        })
+        .collect()
 }
 
 #[derive(Debug)]
@@ -160,4 +166,9 @@ pub enum VerificationError<PcsErr> {
     /// Out-of-domain evaluation mismatch, i.e. `constraints(zeta)` did not match
     /// `quotient(zeta) Z_H(zeta)`.
     OodEvaluationMismatch,
+    /// The proof of work witness ground before `zeta` was sampled is invalid.
+    InvalidPowWitness,
+    /// The sends and receives of the tables' interactions do not balance, i.e. their cumulative
+    /// sums do not add up to zero.
+    UnbalancedInteractions,
 }
@@ -0,0 +1,232 @@
+use alloc::vec::Vec;
+
+use p3_air::RowSelector;
+use p3_commit::PolynomialSpace;
+use p3_field::{ExtensionField, Field};
+
+/// Check that `row_offsets` describes a valid evaluation window, i.e. that it is strictly
+/// increasing and starts at zero.
+pub fn check_row_offsets(row_offsets: &[usize]) {
+    assert_eq!(
+        row_offsets.first(),
+        Some(&0),
+        "row offsets must start at zero"
+    );
+    assert!(
+        row_offsets.windows(2).all(|pair| pair[0] < pair[1]),
+        "row offsets must be strictly increasing"
+    );
+}
+
+/// Check that each of `row_selectors` is well formed, i.e. that each period is a power of two
+/// and each offset lies within its period.
+pub fn check_row_selectors(row_selectors: &[RowSelector]) {
+    for selector in row_selectors {
+        if let &RowSelector::Periodic { period, offset } = selector {
+            assert!(
+                period.is_power_of_two() && offset < period,
+                "invalid row selector {selector:?}: the period must be a power of two greater than \
+                 the offset"
+            );
+        }
+    }
+}
+
+/// Check that each of `row_selectors` is defined on a trace of height `height`.
+pub(crate) fn assert_row_selectors_fit(row_selectors: &[RowSelector], height: usize) {
+    for selector in row_selectors {
+        assert!(
+            selector.is_valid_for_height(height),
+            "the row selector {selector:?} is not defined on a trace of height {height}"
+        );
+    }
+}
+
+/// Check that each of `periodic_columns` has a period which is a power of two.
+pub fn check_periodic_columns<F>(periodic_columns: &[Vec<F>]) {
+    for (index, column) in periodic_columns.iter().enumerate() {
+        assert!(
+            column.len().is_power_of_two(),
+            "periodic column {index} has a period of {} rows, which is not a power of two",
+            column.len()
+        );
+    }
+}
+
+/// Check that each of `periodic_columns` repeats a whole number of times on a trace of height
+/// `height`.
+pub(crate) fn assert_periodic_columns_fit<F>(periodic_columns: &[Vec<F>], height: usize) {
+    for (index, column) in periodic_columns.iter().enumerate() {
+        assert!(
+            column.len() <= height,
+            "periodic column {index} has a period of {} rows, longer than the trace of height \
+             {height}",
+            column.len()
+        );
+    }
+}
+
+/// Whether each of `periodic_columns` has a power of two period no longer than `height`.
+pub fn periodic_columns_fit<F>(periodic_columns: &[Vec<F>], height: usize) -> bool {
+    periodic_columns
+        .iter()
+        .all(|column| column.len().is_power_of_two() && column.len() <= height)
+}
+
+/// The value paired with `selector` in `row_selectors`, which holds the value of each of the
+/// AIR's declared row selectors.
+pub(crate) fn select_row<T: Copy>(row_selectors: &[(RowSelector, T)], selector: RowSelector) -> T {
+    row_selectors
+        .iter()
+        .find(|(declared, _)| *declared == selector)
+        .unwrap_or_else(|| {
+            panic!("the row selector {selector:?} is not declared in the AIR's row selectors")
+        })
+        .1
+}
+
+/// The values at `point` of each of `row_selectors` over `domain`.
+pub fn row_selectors_at_point<D, Ext>(
+    domain: &D,
+    point: Ext,
+    row_selectors: &[RowSelector],
+) -> Vec<(RowSelector, Ext)>
+where
+    D: PolynomialSpace,
+    Ext: ExtensionField<D::Val>,
+{
+    row_selectors
+        .iter()
+        .map(|&selector| {
+            let value = match selector {
+                RowSelector::Row(row) => domain.selector_at_row(row, point),
+                RowSelector::Periodic { period, offset } => {
+                    domain.periodic_selector(period, offset, point)
+                }
+            };
+            (selector, value)
+        })
+        .collect()
+}
+
+/// The values over `coset` of each of `row_selectors` over `domain`.
+pub fn row_selectors_on_coset<D: PolynomialSpace>(
+    domain: &D,
+    coset: D,
+    row_selectors: &[RowSelector],
+) -> Vec<Vec<D::Val>> {
+    row_selectors
+        .iter()
+        .map(|&selector| match selector {
+            RowSelector::Row(row) => domain.selector_at_row_on_coset(row, coset),
+            RowSelector::Periodic { period, offset } => {
+                domain.periodic_selector_on_coset(period, offset, coset)
+            }
+        })
+        .collect()
+}
+
+/// The values at `point` of each of `periodic_columns` over `domain`.
+pub fn periodic_values_at_point<D, Ext>(
+    domain: &D,
+    point: Ext,
+    periodic_columns: &[Vec<D::Val>],
+) -> Vec<Ext>
+where
+    D: PolynomialSpace,
+    Ext: ExtensionField<D::Val>,
+{
+    periodic_columns
+        .iter()
+        .map(|column| domain.periodic_column_at_point(column, point))
+        .collect()
+}
+
+/// The values over `coset` of each of `periodic_columns` over `domain`.
+pub fn periodic_columns_on_coset<D: PolynomialSpace>(
+    domain: &D,
+    coset: D,
+    periodic_columns: &[Vec<D::Val>],
+) -> Vec<Vec<D::Val>> {
+    periodic_columns
+        .iter()
+        .map(|column| domain.periodic_column_on_coset(column, coset))
+        .collect()
+}
+
+/// The number of consecutive rows spanned by a window with the given row offsets.
+///
+/// This is at least 2, as the transition selector of a two-row window is always available.
+pub fn window_size(row_offsets: &[usize]) -> usize {
+    (row_offsets.last().unwrap() + 1).max(2)
+}
+
+/// The points at which to open a trace over `domain` for a window with the given row offsets,
+/// i.e. `zeta` shifted by each offset.
+pub fn window_points<D, Ext>(domain: &D, zeta: Ext, row_offsets: &[usize]) -> Vec<Ext>
+where
+    D: PolynomialSpace,
+    Ext: ExtensionField<D::Val>,
+{
+    let mut point = zeta;
+    let mut offset = 0;
+    row_offsets
+        .iter()
+        .map(|&row_offset| {
+            while offset < row_offset {
+                point = domain.next_point(point).unwrap();
+                offset += 1;
+            }
+            point
+        })
+        .collect()
+}
+
+/// The transition selectors at `point` for windows of `2..=window_size` rows.
+///
+/// The selector for a window of `size` rows vanishes on the last `size - 1` rows of `domain`. It is
+/// the product of the two-row transition selector at `point` and at the `size - 2` points after it.
+pub fn transition_window_selectors_at_point<D, Ext>(
+    domain: &D,
+    point: Ext,
+    window_size: usize,
+) -> Vec<Ext>
+where
+    D: PolynomialSpace,
+    Ext: ExtensionField<D::Val>,
+{
+    let mut point = point;
+    let mut selector = Ext::ONE;
+    (2..=window_size)
+        .map(|_| {
+            selector *= domain.selectors_at_point(point).is_transition;
+            point = domain.next_point(point).unwrap();
+            selector
+        })
+        .collect()
+}
+
+/// The transition selectors over a coset for windows of `2..=window_size` rows, given the two-row
+/// transition selector `is_transition` over it.
+///
+/// `step` is the distance between the coset points corresponding to consecutive trace rows.
+pub fn transition_window_selectors_on_coset<F: Field>(
+    is_transition: &[F],
+    step: usize,
+    window_size: usize,
+) -> Vec<Vec<F>> {
+    let size = is_transition.len();
+    let mut selectors: Vec<Vec<F>> = Vec::with_capacity(window_size - 1);
+    selectors.push(is_transition.to_vec());
+    for shift in 1..window_size - 1 {
+        let selector = selectors
+            .last()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .map(|(i, &s)| s * is_transition[(i + shift * step) % size])
+            .collect();
+        selectors.push(selector);
+    }
+    selectors
+}
@@ -0,0 +1,85 @@
+use p3_air::{Air, AirBuilder, BaseAir, Interaction, VirtualPairCol};
+use p3_baby_bear::BabyBear;
+use p3_field::Field;
+use p3_matrix::Matrix;
+use p3_uni_stark::AirStats;
+
+/// An AIR whose last column is only used if it is sent on a bus.
+pub struct CubesAir {
+    send_last_column: bool,
+}
+
+impl<F: Field> BaseAir<F> for CubesAir {
+    fn width(&self) -> usize {
+        4
+    }
+
+    fn sends(&self) -> Vec<Interaction<F>> {
+        if self.send_last_column {
+            vec![Interaction::new(
+                vec![VirtualPairCol::single_main(3)],
+                VirtualPairCol::constant(F::ONE),
+                0,
+            )]
+        } else {
+            vec![]
+        }
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for CubesAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let next = main.row_slice(1);
+
+        builder.when_first_row().assert_zero(local[0]);
+        builder.assert_eq(local[1] * local[1] * local[1], local[2]);
+        builder
+            .when_transition()
+            .assert_eq(local[1] * local[1], next[0]);
+    }
+}
+
+const SOURCE: &str = include_str!("air_stats.rs");
+
+#[test]
+fn test_air_stats() {
+    let air = CubesAir {
+        send_last_column: false,
+    };
+    let stats = AirStats::new::<BabyBear, _>(&air, 0, 0, false);
+
+    assert_eq!(stats.num_constraints(), 3);
+    assert_eq!(stats.constraint_degrees, [2, 3, 2]);
+    assert_eq!(stats.max_degree_constraint, Some(1));
+    assert_eq!(stats.max_degree(), 3);
+    let line = stats.constraint_locations[1].line() as usize;
+    assert!(SOURCE
+        .lines()
+        .nth(line - 1)
+        .unwrap()
+        .contains("assert_eq(local[1] * local[1] * local[1], local[2])"));
+
+    // Two for the cube, whose square is shared with the transition constraint, and one for each
+    // selector.
+    assert_eq!(stats.num_multiplications, 4);
+    assert_eq!(stats.unused_columns, [3]);
+    assert!(stats.unused_extension_columns.is_empty());
+    assert_eq!(stats.quotient_degree(), 2);
+
+    let report = stats.to_string();
+    assert!(report.contains("max degree: 3 (constraint 1 at"));
+    assert!(report.contains("unused columns: [3]"));
+}
+
+#[test]
+fn test_air_stats_zk_and_interactions() {
+    let air = CubesAir {
+        send_last_column: true,
+    };
+    let stats = AirStats::new::<BabyBear, _>(&air, 0, 0, true);
+    assert!(stats.unused_columns.is_empty());
+    // In zero-knowledge mode the quotient has `2 * 3 - 1` chunks, rounded up to a power of two.
+    assert_eq!(stats.log_quotient_degree, 3);
+}
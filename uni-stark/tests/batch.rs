@@ -0,0 +1,521 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use p3_air::{
+    Air, AirBuilder, AirBuilderWithExtensionPublicValues, AirBuilderWithPublicValues, BaseAir,
+    ExtensionBuilder, ExtensionMainBuilder, Interaction, PairBuilder, VirtualPairCol,
+};
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{ExtensionField, Field, FieldAlgebra, FieldExtensionAlgebra};
+use p3_fri::{FriConfig, HidingFriPcs, TwoAdicFriPcs};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::{MerkleTreeHidingMmcs, MerkleTreeMmcs};
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{
+    prove_batch, prove_batch_with_instances, setup_keys, verify_batch, verify_batch_with_instances,
+    Instance, StarkConfig,
+};
+use rand::rngs::StdRng;
+use rand::{thread_rng, SeedableRng};
+
+/// Consecutive values `x`, starting from the public value, alongside their squares.
+pub struct CounterAir;
+
+impl<F> BaseAir<F> for CounterAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for CounterAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let start = builder.public_values()[0];
+        let local = main.row_slice(0);
+        let next = main.row_slice(1);
+
+        builder.when_first_row().assert_eq(local[0], start);
+        builder
+            .when_transition()
+            .assert_eq(local[0] + AB::Expr::ONE, next[0]);
+        builder.assert_eq(local[0] * local[0], local[1]);
+    }
+}
+
+fn counter_trace<F: Field>(start: usize, log_height: usize) -> RowMajorMatrix<F> {
+    let values = (start..start + (1 << log_height))
+        .flat_map(|i| {
+            let x = F::from_canonical_usize(i);
+            [x, x.square()]
+        })
+        .collect();
+    RowMajorMatrix::new(values, 2)
+}
+
+/// Checks that the second column is a permutation of the first, using a LogUp argument.
+pub struct PermutationAir;
+
+impl<F: Field> BaseAir<F> for PermutationAir {
+    fn width(&self) -> usize {
+        2
+    }
+
+    fn sends(&self) -> Vec<Interaction<F>> {
+        vec![Interaction::new(
+            vec![VirtualPairCol::single_main(0)],
+            VirtualPairCol::constant(F::ONE),
+            0,
+        )]
+    }
+
+    fn receives(&self) -> Vec<Interaction<F>> {
+        vec![Interaction::new(
+            vec![VirtualPairCol::single_main(1)],
+            VirtualPairCol::constant(F::ONE),
+            0,
+        )]
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for PermutationAir {
+    fn eval(&self, _builder: &mut AB) {}
+}
+
+fn permutation_trace<F: Field>(log_height: usize, multiplier: usize) -> RowMajorMatrix<F> {
+    let height = 1 << log_height;
+    let values = (0..height)
+        .flat_map(|i| {
+            [
+                F::from_canonical_usize(i),
+                F::from_canonical_usize((multiplier * i + 3) % height),
+            ]
+        })
+        .collect();
+    RowMajorMatrix::new(values, 2)
+}
+
+/// A lookup table read from a preprocessed column, shifted by the public value.
+pub struct ShiftedTableAir;
+
+const SHIFTED_TABLE_LOG_HEIGHT: usize = 4;
+
+impl ShiftedTableAir {
+    fn table_value<F: Field>(row: usize) -> F {
+        F::from_canonical_usize(3 * row * row + 1)
+    }
+
+    fn generate_trace<F: Field>(shift: usize) -> RowMajorMatrix<F> {
+        RowMajorMatrix::new_col(
+            (0..1 << SHIFTED_TABLE_LOG_HEIGHT)
+                .map(|row| Self::table_value::<F>(row) + F::from_canonical_usize(shift))
+                .collect(),
+        )
+    }
+}
+
+impl<F: Field> BaseAir<F> for ShiftedTableAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
+        Some(RowMajorMatrix::new_col(
+            (0..1 << SHIFTED_TABLE_LOG_HEIGHT)
+                .map(Self::table_value)
+                .collect(),
+        ))
+    }
+}
+
+impl<AB: PairBuilder + AirBuilderWithPublicValues> Air<AB> for ShiftedTableAir {
+    fn eval(&self, builder: &mut AB) {
+        let shift = builder.public_values()[0];
+        let x = builder.main().row_slice(0)[0];
+        let value = builder.preprocessed().row_slice(0)[0];
+        builder.assert_eq(x - value, shift);
+    }
+}
+
+/// The powers of the extension field public value `beta`, in an extension field column.
+pub struct PowersAir;
+
+impl PowersAir {
+    fn generate_traces<F: Field, EF: ExtensionField<F>>(
+        beta: EF,
+        log_height: usize,
+    ) -> (RowMajorMatrix<F>, RowMajorMatrix<EF>) {
+        let height = 1 << log_height;
+        let trace = RowMajorMatrix::new_col((0..height).map(F::from_canonical_usize).collect());
+        let extension_trace = RowMajorMatrix::new_col(beta.powers().take(height).collect());
+        (trace, extension_trace)
+    }
+}
+
+impl<F> BaseAir<F> for PowersAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn extension_width(&self) -> usize {
+        1
+    }
+
+    fn num_extension_public_values(&self) -> usize {
+        1
+    }
+}
+
+impl<AB: AirBuilderWithExtensionPublicValues + ExtensionMainBuilder> Air<AB> for PowersAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (x, x_next) = (main.row_slice(0)[0], main.row_slice(1)[0]);
+        builder.when_first_row().assert_zero(x);
+        builder
+            .when_transition()
+            .assert_eq(x + AB::Expr::ONE, x_next);
+
+        let beta: AB::ExprEF = builder.extension_public_values()[0].into();
+        let main_ext = builder.main_ext();
+        let acc: AB::ExprEF = main_ext.row_slice(0)[0].into();
+        let acc_next: AB::ExprEF = main_ext.row_slice(1)[0].into();
+        builder.when_first_row().assert_one_ext(acc.clone());
+        builder
+            .when_transition()
+            .assert_eq_ext(acc * beta, acc_next);
+    }
+}
+
+fn counter_instances(instances: &[(usize, usize)]) -> (Vec<RowMajorMatrix<Val>>, Vec<Vec<Val>>) {
+    instances
+        .iter()
+        .map(|&(start, log_height)| {
+            (
+                counter_trace(start, log_height),
+                vec![Val::from_canonical_usize(start)],
+            )
+        })
+        .unzip()
+}
+
+type Val = BabyBear;
+type Perm = Poseidon2BabyBear<16>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel<Val>;
+type ValMmcs =
+    MerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+type HidingValMmcs = MerkleTreeHidingMmcs<
+    <Val as Field>::Packing,
+    <Val as Field>::Packing,
+    MyHash,
+    MyCompress,
+    StdRng,
+    8,
+    4,
+>;
+type HidingChallengeMmcs = ExtensionMmcs<Val, Challenge, HidingValMmcs>;
+type HidingPcs = HidingFriPcs<Val, Dft, HidingValMmcs, HidingChallengeMmcs, StdRng>;
+type ZkConfig = StarkConfig<HidingPcs, Challenge, Challenger>;
+
+fn make_config_with(perm: &Perm, log_blowup: usize, num_queries: usize) -> MyConfig {
+    let val_mmcs = ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm.clone()));
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig::new(log_blowup, num_queries, 8, challenge_mmcs);
+    MyConfig::new(Pcs::new(Dft::default(), val_mmcs, fri_config))
+}
+
+fn make_zk_config(perm: &Perm, log_blowup: usize, num_queries: usize) -> ZkConfig {
+    ZkConfig::new(make_hiding_pcs(perm, log_blowup, num_queries))
+}
+
+fn make_hiding_pcs(perm: &Perm, log_blowup: usize, num_queries: usize) -> HidingPcs {
+    let val_mmcs = HidingValMmcs::new(
+        MyHash::new(perm.clone()),
+        MyCompress::new(perm.clone()),
+        StdRng::from_entropy(),
+    );
+    let challenge_mmcs = HidingChallengeMmcs::new(val_mmcs.clone());
+    HidingPcs::new(
+        Dft::default(),
+        val_mmcs,
+        FriConfig::new(log_blowup, num_queries, 8, challenge_mmcs),
+        4,
+        StdRng::from_entropy(),
+    )
+}
+
+#[test]
+fn test_batch() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config_with(&perm, 2, 28);
+    let (traces, public_values) = counter_instances(&[(0, 3), (10, 5), (100, 4)]);
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_batch(
+        &config,
+        &CounterAir,
+        &mut challenger,
+        traces,
+        &public_values,
+    );
+    assert_eq!(proof.degree_bits(), [3, 5, 4]);
+    assert_eq!(proof.opened_values().len(), 3);
+
+    let serialized_proof = postcard::to_allocvec(&proof).expect("unable to serialize proof");
+    let proof = postcard::from_bytes(&serialized_proof).expect("unable to deserialize proof");
+
+    let mut challenger = Challenger::new(perm);
+    verify_batch(
+        &config,
+        &CounterAir,
+        &mut challenger,
+        &proof,
+        &public_values,
+    )
+    .expect("verification failed");
+}
+
+#[test]
+fn test_batch_single_instance() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config_with(&perm, 2, 28);
+    let (traces, public_values) = counter_instances(&[(7, 4)]);
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_batch(
+        &config,
+        &CounterAir,
+        &mut challenger,
+        traces,
+        &public_values,
+    );
+    let mut challenger = Challenger::new(perm);
+    verify_batch(
+        &config,
+        &CounterAir,
+        &mut challenger,
+        &proof,
+        &public_values,
+    )
+    .expect("verification failed");
+}
+
+#[test]
+fn test_batch_permutation() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config_with(&perm, 2, 28);
+    let traces = vec![permutation_trace(4, 5), permutation_trace(3, 3)];
+    let public_values = vec![vec![], vec![]];
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_batch(
+        &config,
+        &PermutationAir,
+        &mut challenger,
+        traces,
+        &public_values,
+    );
+    let mut challenger = Challenger::new(perm);
+    verify_batch(
+        &config,
+        &PermutationAir,
+        &mut challenger,
+        &proof,
+        &public_values,
+    )
+    .expect("verification failed");
+}
+
+#[test]
+fn test_batch_zk() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_zk_config(&perm, 2, 28);
+    let (traces, public_values) = counter_instances(&[(3, 4), (5, 3)]);
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_batch(
+        &config,
+        &CounterAir,
+        &mut challenger,
+        traces,
+        &public_values,
+    );
+    let mut challenger = Challenger::new(perm);
+    verify_batch(
+        &config,
+        &CounterAir,
+        &mut challenger,
+        &proof,
+        &public_values,
+    )
+    .expect("verification failed");
+}
+
+#[test]
+fn test_batch_rejects_wrong_public_values() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config_with(&perm, 2, 28);
+    let (traces, mut public_values) = counter_instances(&[(0, 3), (10, 4)]);
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_batch(
+        &config,
+        &CounterAir,
+        &mut challenger,
+        traces,
+        &public_values,
+    );
+
+    public_values[1][0] += Val::ONE;
+    let mut challenger = Challenger::new(perm);
+    // The PCS panics, rather than returning an error, on an opening proof for another transcript.
+    let rejected = catch_unwind(AssertUnwindSafe(|| {
+        verify_batch(
+            &config,
+            &CounterAir,
+            &mut challenger,
+            &proof,
+            &public_values,
+        )
+        .is_err()
+    }))
+    .unwrap_or(true);
+    assert!(rejected);
+
+    // Dropping an instance changes the shape of the proof.
+    let mut challenger = Challenger::new(Perm::new_from_rng_128(&mut thread_rng()));
+    assert!(verify_batch(
+        &config,
+        &CounterAir,
+        &mut challenger,
+        &proof,
+        &public_values[..1]
+    )
+    .is_err());
+}
+
+#[test]
+fn test_batch_preprocessed() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config_with(&perm, 2, 28);
+    let (proving_key, verifying_key) = setup_keys(&config, &ShiftedTableAir, 1);
+    let shifts = [0, 5, 17];
+    let traces = shifts
+        .iter()
+        .map(|&shift| ShiftedTableAir::generate_trace(shift))
+        .collect();
+    let instances = shifts
+        .iter()
+        .map(|&shift| Instance::new(vec![Val::from_canonical_usize(shift)]))
+        .collect::<Vec<_>>();
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_batch_with_instances(
+        &config,
+        &proving_key,
+        &ShiftedTableAir,
+        &mut challenger,
+        traces,
+        None,
+        &instances,
+    );
+    assert_eq!(proof.degree_bits(), [SHIFTED_TABLE_LOG_HEIGHT; 3]);
+
+    let serialized_proof = postcard::to_allocvec(&proof).expect("unable to serialize proof");
+    let proof = postcard::from_bytes(&serialized_proof).expect("unable to deserialize proof");
+
+    let mut challenger = Challenger::new(perm.clone());
+    verify_batch_with_instances(
+        &config,
+        &verifying_key,
+        &ShiftedTableAir,
+        &mut challenger,
+        &proof,
+        &instances,
+    )
+    .expect("verification failed");
+
+    // The instances are bound to their own shifts, even though they share the preprocessed trace.
+    let mut swapped = instances.clone();
+    swapped.swap(1, 2);
+    let mut challenger = Challenger::new(perm);
+    let rejected = catch_unwind(AssertUnwindSafe(|| {
+        verify_batch_with_instances(
+            &config,
+            &verifying_key,
+            &ShiftedTableAir,
+            &mut challenger,
+            &proof,
+            &swapped,
+        )
+        .is_err()
+    }))
+    .unwrap_or(true);
+    assert!(rejected);
+}
+
+#[test]
+fn test_batch_extension_columns() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config_with(&perm, 2, 28);
+    let (proving_key, verifying_key) = setup_keys(&config, &PowersAir, 0);
+    let betas = [
+        Challenge::from_base_fn(|i| Val::from_canonical_usize(i + 2)),
+        Challenge::from_base_fn(|i| Val::from_canonical_usize(3 * i + 1)),
+    ];
+    let (traces, extension_traces) = powers_traces(&betas, &[3, 5]);
+    let instances = betas
+        .iter()
+        .map(|&beta| Instance::<MyConfig> {
+            extension_public_values: vec![beta],
+            ..Instance::new(vec![])
+        })
+        .collect::<Vec<_>>();
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_batch_with_instances(
+        &config,
+        &proving_key,
+        &PowersAir,
+        &mut challenger,
+        traces,
+        Some(extension_traces),
+        &instances,
+    );
+    // Each extension field column is opened as `D` base field columns.
+    assert!(proof
+        .opened_values()
+        .iter()
+        .all(|opened_values| opened_values.trace().iter().all(|row| row.len() == 1 + 4)));
+
+    let mut challenger = Challenger::new(perm);
+    verify_batch_with_instances(
+        &config,
+        &verifying_key,
+        &PowersAir,
+        &mut challenger,
+        &proof,
+        &instances,
+    )
+    .expect("verification failed");
+}
+
+fn powers_traces(
+    betas: &[Challenge],
+    log_heights: &[usize],
+) -> (Vec<RowMajorMatrix<Val>>, Vec<RowMajorMatrix<Challenge>>) {
+    betas
+        .iter()
+        .zip(log_heights)
+        .map(|(&beta, &log_height)| PowersAir::generate_traces(beta, log_height))
+        .unzip()
+}
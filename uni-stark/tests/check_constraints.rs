@@ -0,0 +1,142 @@
+use p3_air::{Air, AirBuilder, BaseAir, ExtensionMainBuilder};
+use p3_baby_bear::BabyBear;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{Field, FieldAlgebra};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_uni_stark::{check_constraints, check_constraints_with_extension, ConstraintFailure};
+
+/// Consecutive integers alongside their squares, starting from zero.
+pub struct SquaresAir;
+
+impl SquaresAir {
+    fn generate_trace<F: Field>(log_height: usize) -> RowMajorMatrix<F> {
+        let values = (0..1 << log_height)
+            .flat_map(|i| {
+                let x = F::from_canonical_usize(i);
+                [x, x.square()]
+            })
+            .collect();
+        RowMajorMatrix::new(values, 2)
+    }
+}
+
+impl<F> BaseAir<F> for SquaresAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for SquaresAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let next = main.row_slice(1);
+
+        builder.when_first_row().assert_zero(local[0]);
+        builder
+            .when_transition()
+            .assert_eq(local[0] + AB::Expr::ONE, next[0]);
+        builder.assert_eq(local[0] * local[0], local[1]);
+    }
+}
+
+/// A single extension field column, constant and equal to the base field column squared.
+pub struct ExtensionSquareAir;
+
+impl<F> BaseAir<F> for ExtensionSquareAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn extension_width(&self) -> usize {
+        1
+    }
+}
+
+impl<AB: ExtensionMainBuilder> Air<AB> for ExtensionSquareAir {
+    fn eval(&self, builder: &mut AB) {
+        let x = builder.main().row_slice(0)[0];
+        let y: AB::ExprEF = builder.main_ext().row_slice(0)[0].into();
+        let x_squared: AB::ExprEF = (x * x).into();
+        builder.assert_eq_ext(y, x_squared);
+    }
+}
+
+type Val = BabyBear;
+type Challenge = BinomialExtensionField<Val, 4>;
+
+const SOURCE: &str = include_str!("check_constraints.rs");
+
+/// The line of this file at which `failure` was asserted.
+fn source_line<F>(failure: &ConstraintFailure<F>) -> &'static str {
+    assert!(failure.location.file().ends_with("check_constraints.rs"));
+    SOURCE
+        .lines()
+        .nth(failure.location.line() as usize - 1)
+        .unwrap()
+}
+
+#[test]
+fn test_valid_trace() {
+    let trace = SquaresAir::generate_trace::<Val>(4);
+    assert_eq!(check_constraints(&SquaresAir, &trace, &[]), vec![]);
+}
+
+#[test]
+fn test_failures_are_reported() {
+    let mut trace = SquaresAir::generate_trace::<Val>(3);
+    // Break the square on row 5, and the first row's counter.
+    trace.values[2 * 5 + 1] += Val::ONE;
+    trace.values[0] = Val::TWO;
+
+    let failures = check_constraints(&SquaresAir, &trace, &[]);
+    let summary = failures
+        .iter()
+        .map(|failure| (failure.row, failure.constraint_index))
+        .collect::<Vec<_>>();
+    assert_eq!(summary, [(0, 0), (0, 1), (0, 2), (5, 2)]);
+
+    // The value is that of the constraint, including any selector.
+    assert_eq!(failures[0].value, Val::TWO);
+    assert_eq!(failures[3].value, -Val::ONE);
+
+    // Each failure points at the assertion in `eval`, even through a filtered builder.
+    assert!(source_line(&failures[0]).contains("assert_zero(local[0])"));
+    assert!(source_line(&failures[1]).contains(".assert_eq(local[0] + AB::Expr::ONE, next[0])"));
+    assert!(source_line(&failures[3]).contains("assert_eq(local[0] * local[0], local[1])"));
+    assert!(failures[3].to_string().contains("on row 5"));
+}
+
+#[test]
+fn test_extension_failures_are_reported() {
+    let height = 8;
+    let trace = RowMajorMatrix::new_col((0..height).map(Val::from_canonical_usize).collect());
+    let mut extension_trace = RowMajorMatrix::new_col(
+        (0..height)
+            .map(|i| Challenge::from_canonical_usize(i * i))
+            .collect(),
+    );
+    assert_eq!(
+        check_constraints_with_extension(
+            &ExtensionSquareAir,
+            &trace,
+            Some(&extension_trace),
+            &[],
+            &[]
+        ),
+        vec![]
+    );
+
+    extension_trace.values[3] += Challenge::ONE;
+    let failures = check_constraints_with_extension(
+        &ExtensionSquareAir,
+        &trace,
+        Some(&extension_trace),
+        &[],
+        &[],
+    );
+    assert_eq!(failures.len(), 1);
+    assert_eq!((failures[0].row, failures[0].value), (3, Challenge::ONE));
+    assert!(source_line(&failures[0]).contains("assert_eq_ext(y, x_squared)"));
+}
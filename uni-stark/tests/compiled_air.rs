@@ -0,0 +1,227 @@
+use p3_air::{
+    Air, AirBuilder, AirBuilderWithPublicValues, BaseAir, ExtensionBuilder, ExtensionMainBuilder,
+};
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{ExtensionField, Field, FieldAlgebra};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{
+    check_constraints_with_extension, prove_with_extension_trace, setup_keys, verify,
+    verify_with_key, CompiledAir, ConstraintFailure, StarkConfig,
+};
+use rand::thread_rng;
+
+/// A counter `x` starting from the public value, alongside `y = (x + 1)^2` and an extension field
+/// column `acc` of the products of `x + 1` over the previous rows.
+///
+/// The window skips a row, to check that `x` increases by 3 over three rows.
+pub struct ProductsAir;
+
+impl ProductsAir {
+    fn generate_traces<F: Field, EF: ExtensionField<F>>(
+        start: usize,
+        log_height: usize,
+    ) -> (RowMajorMatrix<F>, RowMajorMatrix<EF>) {
+        let mut acc = EF::ONE;
+        let mut values = vec![];
+        let mut extension_values = vec![];
+        for i in 0..1 << log_height {
+            let x_plus_one = F::from_canonical_usize(start + i + 1);
+            values.extend([x_plus_one - F::ONE, x_plus_one.square()]);
+            extension_values.push(acc);
+            acc *= x_plus_one;
+        }
+        (
+            RowMajorMatrix::new(values, 2),
+            RowMajorMatrix::new_col(extension_values),
+        )
+    }
+}
+
+impl<F> BaseAir<F> for ProductsAir {
+    fn width(&self) -> usize {
+        2
+    }
+
+    fn extension_width(&self) -> usize {
+        1
+    }
+
+    fn row_offsets(&self) -> Vec<usize> {
+        vec![0, 1, 3]
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues + ExtensionMainBuilder> Air<AB> for ProductsAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next, third) = (main.row_slice(0), main.row_slice(1), main.row_slice(2));
+        let (x, y) = (local[0], local[1]);
+        let start = builder.public_values()[0];
+
+        builder.when_first_row().assert_eq(x, start);
+        builder
+            .when_transition()
+            .assert_eq(x + AB::Expr::ONE, next[0]);
+        builder
+            .when_transition_window(4)
+            .assert_eq(x + AB::Expr::from_canonical_u32(3), third[0]);
+        // `x + 1` is recomputed for each constraint, rather than shared.
+        builder.assert_eq((x + AB::Expr::ONE) * (x + AB::Expr::ONE), y);
+        builder.assert_eq((AB::Expr::ONE + x) * (AB::Expr::ONE + x), y);
+
+        let main_ext = builder.main_ext();
+        let acc: AB::ExprEF = main_ext.row_slice(0)[0].into();
+        let acc_next: AB::ExprEF = main_ext.row_slice(1)[0].into();
+        builder.when_first_row().assert_one_ext(acc.clone());
+        builder
+            .when_transition()
+            .assert_eq_ext(acc * (x + AB::Expr::ONE), acc_next);
+    }
+}
+
+/// The rows, constraint indices and values of `failures`, whose locations differ between an AIR
+/// and its compiled form.
+fn summary(failures: &[ConstraintFailure<Challenge>]) -> Vec<(usize, usize, Challenge)> {
+    failures
+        .iter()
+        .map(|failure| (failure.row, failure.constraint_index, failure.value))
+        .collect()
+}
+
+type Val = BabyBear;
+type Perm = Poseidon2BabyBear<16>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel<Val>;
+type ValMmcs =
+    MerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+fn make_config(perm: &Perm) -> MyConfig {
+    let val_mmcs = ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm.clone()));
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig::new(1, 40, 8, challenge_mmcs);
+    MyConfig::new(Pcs::new(Dft::default(), val_mmcs, fri_config))
+}
+
+#[test]
+fn test_common_subexpressions() {
+    let compiled = CompiledAir::<Val>::new(&ProductsAir, 0, 1);
+    let constraints = compiled.constraints();
+    assert_eq!(constraints.len(), 7);
+    // Both ways of computing `(x + 1)^2 - y` are compiled to the same instructions.
+    assert_eq!(constraints[3], constraints[4]);
+    assert!(!constraints[3].is_ext());
+    assert!(constraints[5].is_ext() && constraints[6].is_ext());
+}
+
+#[test]
+fn test_compiled_constraints_match() {
+    let compiled = CompiledAir::<Val>::new(&ProductsAir, 0, 1);
+    let public_values = [Val::from_canonical_usize(5)];
+    let (mut trace, mut extension_trace) = ProductsAir::generate_traces::<Val, Challenge>(5, 4);
+    let original = |trace: &RowMajorMatrix<Val>, extension_trace: &RowMajorMatrix<Challenge>| {
+        summary(&check_constraints_with_extension(
+            &ProductsAir,
+            trace,
+            Some(extension_trace),
+            &public_values,
+            &[],
+        ))
+    };
+    let interpreted = |trace: &RowMajorMatrix<Val>, extension_trace: &RowMajorMatrix<Challenge>| {
+        summary(&check_constraints_with_extension(
+            &compiled,
+            trace,
+            Some(extension_trace),
+            &public_values,
+            &[],
+        ))
+    };
+
+    assert_eq!(interpreted(&trace, &extension_trace), vec![]);
+
+    trace.values[2 * 6] += Val::ONE;
+    extension_trace.values[9] += Challenge::TWO;
+    let failures = original(&trace, &extension_trace);
+    assert!(!failures.is_empty());
+    assert_eq!(interpreted(&trace, &extension_trace), failures);
+}
+
+fn do_test(log_height: usize) {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config(&perm);
+    let public_values = vec![Val::from_canonical_usize(7)];
+    let (trace, extension_trace) = ProductsAir::generate_traces(7, log_height);
+
+    let (mut proving_key, verifying_key) = setup_keys(&config, &ProductsAir, 1);
+    proving_key.compile_constraints();
+    assert!(proving_key.compiled_constraints().is_some());
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_with_extension_trace(
+        &config,
+        &proving_key,
+        &ProductsAir,
+        &mut challenger,
+        trace,
+        Some(extension_trace),
+        &public_values,
+    );
+
+    // The verifier evaluates the original constraints.
+    let mut challenger = Challenger::new(perm);
+    verify_with_key(
+        &config,
+        &verifying_key,
+        &ProductsAir,
+        &mut challenger,
+        &proof,
+        &public_values,
+    )
+    .expect("verification failed");
+}
+
+#[test]
+fn test_prove_with_compiled_constraints() {
+    do_test(3);
+    do_test(6);
+}
+
+#[test]
+fn test_prove_compiled_air() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config(&perm);
+    let public_values = vec![Val::from_canonical_usize(2)];
+    let (trace, extension_trace) = ProductsAir::generate_traces(2, 5);
+
+    // An AIR which only exists as its compiled constraints.
+    let air = CompiledAir::<Val>::new(&ProductsAir, 0, 1);
+
+    let (proving_key, _) = setup_keys(&config, &air, 1);
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_with_extension_trace(
+        &config,
+        &proving_key,
+        &air,
+        &mut challenger,
+        trace,
+        Some(extension_trace),
+        &public_values,
+    );
+
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &air, &mut challenger, &proof, &public_values).expect("verification failed");
+}
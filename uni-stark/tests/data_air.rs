@@ -0,0 +1,157 @@
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir, PairBuilder};
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{Field, FieldAlgebra};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{prove, setup_keys, verify, DataAir, StarkConfig};
+use rand::thread_rng;
+use serde_json::json;
+
+/// An iterated cube `x' = (x + c)^3` from a public input, where the round constant `c` of each row
+/// is read from a preprocessed column.
+pub struct CubeRoundsAir {
+    log_height: usize,
+}
+
+impl CubeRoundsAir {
+    fn round_constant<F: Field>(row: usize) -> F {
+        F::from_canonical_usize(3 * row + 1)
+    }
+
+    fn generate_trace<F: Field>(&self, input: F) -> RowMajorMatrix<F> {
+        let mut values = vec![input];
+        for row in 1..1 << self.log_height {
+            values.push((values[row - 1] + Self::round_constant::<F>(row - 1)).cube());
+        }
+        RowMajorMatrix::new_col(values)
+    }
+}
+
+impl<F: Field> BaseAir<F> for CubeRoundsAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
+        Some(RowMajorMatrix::new_col(
+            (0..1 << self.log_height)
+                .map(Self::round_constant)
+                .collect(),
+        ))
+    }
+}
+
+impl<AB: PairBuilder + AirBuilderWithPublicValues> Air<AB> for CubeRoundsAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let x = main.row_slice(0)[0];
+        let x_next = main.row_slice(1)[0];
+        let c = builder.preprocessed().row_slice(0)[0];
+        let input = builder.public_values()[0];
+
+        builder.when_first_row().assert_eq(x, input);
+        builder.when_transition().assert_eq((x + c).cube(), x_next);
+    }
+}
+
+/// The AIR, as emitted by a circuit compiler.
+fn cube_rounds_json(log_height: usize) -> String {
+    let air = DataAir::<Val>::from_air(&CubeRoundsAir { log_height }, 1);
+    serde_json::to_string(&air).expect("unable to serialize AIR")
+}
+
+type Val = BabyBear;
+type Perm = Poseidon2BabyBear<16>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel<Val>;
+type ValMmcs =
+    MerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+fn make_config_with(perm: &Perm, log_blowup: usize, num_queries: usize) -> MyConfig {
+    let val_mmcs = ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm.clone()));
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig::new(log_blowup, num_queries, 8, challenge_mmcs);
+    MyConfig::new(Pcs::new(Dft::default(), val_mmcs, fri_config))
+}
+
+#[test]
+fn test_prove_data_air() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config_with(&perm, 2, 28);
+    let air: DataAir<Val> =
+        serde_json::from_str(&cube_rounds_json(4)).expect("unable to deserialize AIR");
+    assert_eq!(air.num_public_values(), 1);
+    assert_eq!(air, DataAir::from_air(&CubeRoundsAir { log_height: 4 }, 1));
+
+    let input = Val::from_canonical_usize(5);
+    let trace = CubeRoundsAir { log_height: 4 }.generate_trace(input);
+    let public_values = vec![input];
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &public_values);
+
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &air, &mut challenger, &proof, &public_values).expect("verification failed");
+}
+
+#[test]
+fn test_data_air_fingerprint() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config_with(&perm, 2, 28);
+    let air = CubeRoundsAir { log_height: 4 };
+    let data_air = DataAir::<Val>::from_air(&air, 1);
+
+    let (_, verifying_key) = setup_keys(&config, &air, 1);
+    let (_, data_verifying_key) = setup_keys(&config, &data_air, 1);
+    assert_eq!(
+        verifying_key.fingerprint(),
+        data_verifying_key.fingerprint()
+    );
+
+    // So a proof for the AIR verifies against its data form.
+    let input = Val::from_canonical_usize(5);
+    let trace = air.generate_trace(input);
+    let public_values = vec![input];
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &public_values);
+
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &data_air, &mut challenger, &proof, &public_values)
+        .expect("verification failed");
+}
+#[test]
+fn test_malformed_data_air() {
+    let valid: serde_json::Value = serde_json::from_str(&cube_rounds_json(3)).unwrap();
+    let deserialize = |edit: &dyn Fn(&mut serde_json::Value)| {
+        let mut value = valid.clone();
+        edit(&mut value);
+        serde_json::from_value::<DataAir<Val>>(value).map_err(|err| err.to_string())
+    };
+    assert!(deserialize(&|_| {}).is_ok());
+
+    let err = deserialize(&|air| air["constraints"]["constraints"][1] = json!({ "Base": 100 }));
+    assert!(err.unwrap_err().contains("constraint 1"));
+
+    let err = deserialize(&|air| air["constraints"]["width"] = json!(0));
+    assert!(err.unwrap_err().contains("invalid operand"));
+
+    let err = deserialize(&|air| air["constraints"]["row_offsets"] = json!([1, 2]));
+    assert!(err.unwrap_err().contains("row offsets"));
+
+    let err = deserialize(&|air| air["preprocessed"] = json!(null));
+    assert!(err.unwrap_err().contains("preprocessed"));
+}
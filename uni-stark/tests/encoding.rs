@@ -0,0 +1,308 @@
+use std::marker::PhantomData;
+
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_challenger::{DuplexChallenger, HashChallenger, SerializingChallenger32};
+use p3_circle::CirclePcs;
+use p3_commit::{ExtensionMmcs, ProofDecodeError, PROOF_ENCODING_VERSION};
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{Field, FieldAlgebra, PrimeField32};
+use p3_fri::{FriConfig, HidingFriPcs, TwoAdicFriPcs};
+use p3_keccak::Keccak256Hash;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::{MerkleTreeHidingMmcs, MerkleTreeMmcs};
+use p3_mersenne_31::Mersenne31;
+use p3_symmetric::{
+    CompressionFunctionFromHasher, PaddingFreeSponge, SerializingHasher32, TruncatedPermutation,
+};
+use p3_uni_stark::{prove, verify, Proof, StarkConfig, StarkGenericConfig, Val};
+use rand::rngs::StdRng;
+use rand::{thread_rng, SeedableRng};
+
+/// A counter from the public value, alongside its double.
+pub struct DoublesAir;
+
+impl DoublesAir {
+    fn generate_trace<F: Field>(start: usize, log_height: usize) -> RowMajorMatrix<F> {
+        let values = (start..start + (1 << log_height))
+            .flat_map(|x| [F::from_canonical_usize(x), F::from_canonical_usize(2 * x)])
+            .collect();
+        RowMajorMatrix::new(values, 2)
+    }
+}
+
+impl<F> BaseAir<F> for DoublesAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for DoublesAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let next = main.row_slice(1);
+        let start = builder.public_values()[0];
+
+        builder.when_first_row().assert_eq(local[0], start);
+        builder
+            .when_transition()
+            .assert_eq(local[0] + AB::Expr::ONE, next[0]);
+        builder.assert_eq(local[0].into().double(), local[1]);
+    }
+}
+
+const START: usize = 5;
+const LOG_HEIGHT: usize = 5;
+
+/// Prove the doubles, and check that the proof survives a round trip through its encoding, which
+/// is canonical. Returns the encoding.
+fn round_trip<SC: StarkGenericConfig>(config: &SC, challenger: SC::Challenger) -> Vec<u8>
+where
+    SC::Challenger: Clone,
+{
+    let trace = DoublesAir::generate_trace(START, LOG_HEIGHT);
+    let public_values = vec![Val::<SC>::from_canonical_usize(START)];
+    let proof = prove(
+        config,
+        &DoublesAir,
+        &mut challenger.clone(),
+        trace,
+        &public_values,
+    );
+
+    let bytes = proof.to_bytes();
+    assert_eq!(bytes[0], PROOF_ENCODING_VERSION);
+    let decoded = Proof::<SC>::from_bytes(&bytes).unwrap();
+    assert_eq!(decoded.to_bytes(), bytes);
+    verify(
+        config,
+        &DoublesAir,
+        &mut challenger.clone(),
+        &decoded,
+        &public_values,
+    )
+    .expect("verification failed");
+
+    // The components cover the whole encoding.
+    let sizes = proof.size_by_component();
+    assert_eq!(
+        sizes.iter().map(|(_, size)| size).sum::<usize>(),
+        bytes.len()
+    );
+    bytes
+}
+
+/// The offset and size of `component` in the encoding of `proof`.
+fn locate<SC: StarkGenericConfig>(proof: &Proof<SC>, component: &str) -> (usize, usize) {
+    let sizes = proof.size_by_component();
+    let index = sizes
+        .iter()
+        .position(|(name, _)| name == component)
+        .unwrap();
+    let offset = sizes[..index].iter().map(|(_, size)| size).sum();
+    (offset, sizes[index].1)
+}
+
+type Perm = Poseidon2BabyBear<16>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type Challenge = BinomialExtensionField<BabyBear, 4>;
+type Challenger = DuplexChallenger<BabyBear, Perm, 16, 8>;
+type Dft = Radix2DitParallel<BabyBear>;
+type ValMmcs = MerkleTreeMmcs<
+    <BabyBear as Field>::Packing,
+    <BabyBear as Field>::Packing,
+    MyHash,
+    MyCompress,
+    8,
+>;
+type ChallengeMmcs = ExtensionMmcs<BabyBear, Challenge, ValMmcs>;
+type Pcs = TwoAdicFriPcs<BabyBear, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+type HidingValMmcs = MerkleTreeHidingMmcs<
+    <BabyBear as Field>::Packing,
+    <BabyBear as Field>::Packing,
+    MyHash,
+    MyCompress,
+    StdRng,
+    8,
+    4,
+>;
+type HidingChallengeMmcs = ExtensionMmcs<BabyBear, Challenge, HidingValMmcs>;
+type HidingPcs = HidingFriPcs<BabyBear, Dft, HidingValMmcs, HidingChallengeMmcs, StdRng>;
+type ZkConfig = StarkConfig<HidingPcs, Challenge, Challenger>;
+
+fn make_config_with(perm: &Perm, log_blowup: usize, num_queries: usize) -> MyConfig {
+    let val_mmcs = ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm.clone()));
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig::new(log_blowup, num_queries, 8, challenge_mmcs);
+    MyConfig::new(Pcs::new(Dft::default(), val_mmcs, fri_config))
+}
+
+fn make_zk_config(perm: &Perm, log_blowup: usize, num_queries: usize) -> ZkConfig {
+    ZkConfig::new(make_hiding_pcs(perm, log_blowup, num_queries))
+}
+
+fn make_hiding_pcs(perm: &Perm, log_blowup: usize, num_queries: usize) -> HidingPcs {
+    let val_mmcs = HidingValMmcs::new(
+        MyHash::new(perm.clone()),
+        MyCompress::new(perm.clone()),
+        StdRng::from_entropy(),
+    );
+    let challenge_mmcs = HidingChallengeMmcs::new(val_mmcs.clone());
+    HidingPcs::new(
+        Dft::default(),
+        val_mmcs,
+        FriConfig::new(log_blowup, num_queries, 8, challenge_mmcs),
+        4,
+        StdRng::from_entropy(),
+    )
+}
+
+#[test]
+fn test_encoding_two_adic() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config_with(&perm, 1, 20);
+    let bytes = round_trip(&config, Challenger::new(perm));
+    let proof = Proof::<MyConfig>::from_bytes(&bytes).unwrap();
+
+    let sizes = proof.size_by_component();
+    let names = sizes
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            "version",
+            "commitments.trace",
+            "commitments.permutation",
+            "commitments.quotient_chunks",
+            "zeta_pow_witness",
+            "opened_values.preprocessed",
+            "opened_values.trace",
+            "opened_values.permutation_local",
+            "opened_values.permutation_next",
+            "opened_values.quotient_chunks",
+            "opened_values.quotient_randomizer",
+            "opening_proof.commit_phase_commits",
+            "opening_proof.commit_pow_witnesses",
+            "opening_proof.query_proofs",
+            "opening_proof.commit_phase_proofs",
+            "opening_proof.final_poly",
+            "opening_proof.pow_witness",
+            "degree_bits",
+        ]
+    );
+    // A digest of 8 field elements, an absent commitment or witness, a constant final polynomial,
+    // prefixed with its length, and an integer take fixed sizes.
+    assert_eq!(locate(&proof, "commitments.trace").1, 8 * 4);
+    assert_eq!(locate(&proof, "commitments.permutation").1, 1);
+    assert_eq!(locate(&proof, "zeta_pow_witness").1, 1);
+    assert_eq!(locate(&proof, "opening_proof.final_poly").1, 4 + 4 * 4);
+    assert_eq!(locate(&proof, "degree_bits").1, 8);
+}
+
+#[test]
+fn test_decoding_errors() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config_with(&perm, 1, 20);
+    let bytes = round_trip(&config, Challenger::new(perm));
+    let proof = Proof::<MyConfig>::from_bytes(&bytes).unwrap();
+    let decode = |bytes: &[u8]| Proof::<MyConfig>::from_bytes(bytes).err();
+
+    let mut other_version = bytes.clone();
+    other_version[0] = PROOF_ENCODING_VERSION + 1;
+    assert_eq!(
+        decode(&other_version),
+        Some(ProofDecodeError::UnsupportedVersion(
+            PROOF_ENCODING_VERSION + 1
+        ))
+    );
+    assert_eq!(decode(&[]), Some(ProofDecodeError::UnexpectedEnd));
+    assert_eq!(
+        decode(&bytes[..bytes.len() - 1]),
+        Some(ProofDecodeError::UnexpectedEnd)
+    );
+
+    let mut trailing = bytes.clone();
+    trailing.extend([0, 0]);
+    assert_eq!(decode(&trailing), Some(ProofDecodeError::TrailingBytes(2)));
+
+    // The permutation commitment is an option, whose tag must be 0 or 1.
+    let (offset, _) = locate(&proof, "commitments.permutation");
+    let mut bad_tag = bytes.clone();
+    bad_tag[offset] = 2;
+    assert_eq!(decode(&bad_tag), Some(ProofDecodeError::InvalidTag(2)));
+
+    // The opened trace rows are prefixed with their number.
+    let (offset, _) = locate(&proof, "opened_values.trace");
+    let mut bad_length = bytes.clone();
+    bad_length[offset..offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert_eq!(
+        decode(&bad_length),
+        Some(ProofDecodeError::InvalidLength(u32::MAX as usize))
+    );
+
+    // Field elements must be canonical.
+    let (offset, _) = locate(&proof, "opening_proof.final_poly");
+    let mut non_canonical = bytes;
+    non_canonical[offset + 4..offset + 8].copy_from_slice(&BabyBear::ORDER_U32.to_le_bytes());
+    assert!(matches!(
+        decode(&non_canonical),
+        Some(ProofDecodeError::Invalid(_))
+    ));
+}
+
+#[test]
+fn test_encoding_hiding() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_zk_config(&perm, 1, 20);
+    let bytes = round_trip(&config, Challenger::new(perm));
+
+    // The opening proof is a tuple of the random codewords' openings and the FRI proof.
+    let proof = Proof::<ZkConfig>::from_bytes(&bytes).unwrap();
+    let sizes = proof.size_by_component();
+    assert!(sizes.iter().any(|(name, _)| name == "opening_proof.0"));
+    assert!(sizes.iter().any(|(name, _)| name == "opening_proof.1"));
+}
+
+#[test]
+fn test_encoding_circle() {
+    type Val = Mersenne31;
+    type Challenge = BinomialExtensionField<Val, 3>;
+    type ByteHash = Keccak256Hash;
+    type FieldHash = SerializingHasher32<ByteHash>;
+    type MyCompress = CompressionFunctionFromHasher<ByteHash, 2, 32>;
+    type ValMmcs = MerkleTreeMmcs<Val, u8, FieldHash, MyCompress, 32>;
+    type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+    type Challenger = SerializingChallenger32<Val, HashChallenger<u8, ByteHash, 32>>;
+    type Pcs = CirclePcs<Val, ValMmcs, ChallengeMmcs>;
+    type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+    let byte_hash = ByteHash {};
+    let val_mmcs = ValMmcs::new(FieldHash::new(byte_hash), MyCompress::new(byte_hash));
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let pcs = Pcs {
+        mmcs: val_mmcs,
+        fri_config: FriConfig::new(1, 20, 8, challenge_mmcs),
+        _phantom: PhantomData,
+    };
+    let config = MyConfig::new(pcs);
+    let bytes = round_trip(&config, Challenger::from_hasher(vec![], byte_hash));
+
+    let proof = Proof::<MyConfig>::from_bytes(&bytes).unwrap();
+    let sizes = proof.size_by_component();
+    // A byte digest takes its 32 bytes.
+    assert_eq!(locate(&proof, "commitments.trace").1, 32);
+    for component in [
+        "opening_proof.first_layer_commitment",
+        "opening_proof.lambdas",
+        "opening_proof.fri_proof",
+    ] {
+        assert!(sizes.iter().any(|(name, _)| name == component));
+    }
+}
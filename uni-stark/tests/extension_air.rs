@@ -0,0 +1,175 @@
+use p3_air::{Air, AirBuilder, BaseAir, ExtensionBuilder, ExtensionMainBuilder};
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{ExtensionField, Field, FieldAlgebra, FieldExtensionAlgebra};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{prove_with_extension_trace, prove_with_key, setup_keys, verify, StarkConfig};
+use rand::thread_rng;
+
+/// The powers of an extension field element `g`, alongside a base field row counter `x`.
+///
+/// The base field column is `x`, and the extension field columns are `g`, `acc = g^x` and
+/// `shifted = g + x`.
+pub struct PowersAir;
+
+impl PowersAir {
+    fn generate_traces<F: Field, EF: ExtensionField<F>>(
+        g: EF,
+        log_height: usize,
+    ) -> (RowMajorMatrix<F>, RowMajorMatrix<EF>) {
+        let height = 1 << log_height;
+        let trace = RowMajorMatrix::new_col((0..height).map(F::from_canonical_usize).collect());
+        let mut acc = EF::ONE;
+        let mut extension_values = Vec::with_capacity(3 * height);
+        for x in 0..height {
+            extension_values.extend([g, acc, g + F::from_canonical_usize(x)]);
+            acc *= g;
+        }
+        (trace, RowMajorMatrix::new(extension_values, 3))
+    }
+}
+
+impl<F> BaseAir<F> for PowersAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn extension_width(&self) -> usize {
+        3
+    }
+}
+
+impl<AB: ExtensionMainBuilder> Air<AB> for PowersAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let x = main.row_slice(0)[0];
+        let x_next = main.row_slice(1)[0];
+
+        let main_ext = builder.main_ext();
+        let (local, next) = (main_ext.row_slice(0), main_ext.row_slice(1));
+        let (g, acc, shifted): (AB::ExprEF, AB::ExprEF, AB::ExprEF) =
+            (local[0].into(), local[1].into(), local[2].into());
+        let (g_next, acc_next): (AB::ExprEF, AB::ExprEF) = (next[0].into(), next[1].into());
+
+        builder.when_first_row().assert_zero(x);
+        builder
+            .when_transition()
+            .assert_eq(x + AB::Expr::ONE, x_next);
+
+        builder.when_first_row().assert_one_ext(acc.clone());
+        builder.when_transition().assert_eq_ext(g_next, g.clone());
+        builder
+            .when_transition()
+            .assert_eq_ext(acc * g.clone(), acc_next);
+        builder.assert_eq_ext(g + x.into(), shifted);
+    }
+}
+
+fn generator() -> Challenge {
+    Challenge::from_base_fn(|i| Val::from_canonical_usize(i + 2))
+}
+
+fn do_test(log_height: usize) {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config(&perm);
+    let (trace, extension_trace) = PowersAir::generate_traces(generator(), log_height);
+
+    let (proving_key, _) = setup_keys(&config, &PowersAir, 0);
+    // One constraint per `assert_*_ext`, of degree 2.
+    assert_eq!(proving_key.constraint_count(), 6);
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_with_extension_trace(
+        &config,
+        &proving_key,
+        &PowersAir,
+        &mut challenger,
+        trace,
+        Some(extension_trace),
+        &vec![],
+    );
+    // Each extension field column is opened as `D` base field columns.
+    assert!(proof
+        .opened_values()
+        .trace()
+        .iter()
+        .all(|row| row.len() == 1 + 3 * 4));
+
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &PowersAir, &mut challenger, &proof, &vec![]).expect("verification failed");
+}
+
+type Val = BabyBear;
+type Perm = Poseidon2BabyBear<16>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel<Val>;
+type ValMmcs =
+    MerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+fn make_config(perm: &Perm) -> MyConfig {
+    let val_mmcs = ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm.clone()));
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig::new(1, 40, 8, challenge_mmcs);
+    MyConfig::new(Pcs::new(Dft::default(), val_mmcs, fri_config))
+}
+
+#[test]
+fn test_extension_columns() {
+    do_test(3);
+    do_test(6);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic]
+fn test_extension_columns_invalid_trace() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config(&perm);
+    let (trace, mut extension_trace) = PowersAir::generate_traces(generator(), 4);
+    // Break `acc' = acc * g` on the sixth row.
+    extension_trace.values[3 * 5 + 1] += Challenge::ONE;
+
+    let (proving_key, _) = setup_keys(&config, &PowersAir, 0);
+    let mut challenger = Challenger::new(perm);
+    prove_with_extension_trace(
+        &config,
+        &proving_key,
+        &PowersAir,
+        &mut challenger,
+        trace,
+        Some(extension_trace),
+        &vec![],
+    );
+}
+
+#[test]
+#[should_panic(expected = "no extension trace was given")]
+fn test_missing_extension_trace() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config(&perm);
+    let (trace, _) = PowersAir::generate_traces::<Val, Challenge>(generator(), 4);
+
+    let (proving_key, _) = setup_keys(&config, &PowersAir, 0);
+    let mut challenger = Challenger::new(perm);
+    prove_with_key(
+        &config,
+        &proving_key,
+        &PowersAir,
+        &mut challenger,
+        trace,
+        &vec![],
+    );
+}
@@ -0,0 +1,262 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use p3_air::{
+    Air, AirBuilder, AirBuilderWithExtensionPublicValues, AirBuilderWithPublicValues, BaseAir,
+    ExtensionBuilder, ExtensionMainBuilder,
+};
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{ExtensionField, Field, FieldAlgebra, FieldExtensionAlgebra};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{
+    prove_with_instance, setup_keys, verify_with_instance, CompiledAir, Instance, Proof,
+    StarkConfig, StarkVerifyingKey, VerificationError,
+};
+use rand::thread_rng;
+
+/// A counter `x` starting from the public value, alongside an extension field column `acc` of the
+/// powers of the extension field public value `beta`.
+pub struct PowersAir;
+
+impl PowersAir {
+    fn generate_traces<F: Field, EF: ExtensionField<F>>(
+        start: usize,
+        beta: EF,
+        log_height: usize,
+    ) -> (RowMajorMatrix<F>, RowMajorMatrix<EF>) {
+        let height = 1 << log_height;
+        let trace = RowMajorMatrix::new_col(
+            (start..start + height)
+                .map(F::from_canonical_usize)
+                .collect(),
+        );
+        let extension_trace = RowMajorMatrix::new_col(beta.powers().take(height).collect());
+        (trace, extension_trace)
+    }
+}
+
+impl<F> BaseAir<F> for PowersAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn extension_width(&self) -> usize {
+        1
+    }
+
+    fn num_extension_public_values(&self) -> usize {
+        1
+    }
+}
+
+impl<AB> Air<AB> for PowersAir
+where
+    AB: AirBuilderWithPublicValues + AirBuilderWithExtensionPublicValues + ExtensionMainBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (x, x_next) = (main.row_slice(0)[0], main.row_slice(1)[0]);
+        let start = builder.public_values()[0];
+        let beta: AB::ExprEF = builder.extension_public_values()[0].into();
+
+        builder.when_first_row().assert_eq(x, start);
+        builder
+            .when_transition()
+            .assert_eq(x + AB::Expr::ONE, x_next);
+
+        let main_ext = builder.main_ext();
+        let acc: AB::ExprEF = main_ext.row_slice(0)[0].into();
+        let acc_next: AB::ExprEF = main_ext.row_slice(1)[0].into();
+        builder.when_first_row().assert_one_ext(acc.clone());
+        builder
+            .when_transition()
+            .assert_eq_ext(acc * beta, acc_next);
+    }
+}
+
+fn make_instance() -> Instance<MyConfig> {
+    let beta = Challenge::from_base_slice(&[3, 1, 4, 1].map(Val::from_canonical_u32));
+    let mut instance = Instance::new(vec![Val::from_canonical_usize(5)]);
+    instance.extension_public_values = vec![beta];
+    instance.data = vec![
+        vec![Val::ONE, Val::TWO],
+        vec![],
+        vec![Val::from_canonical_u32(7)],
+    ];
+    instance
+}
+
+fn prove_instance(
+    perm: &Perm,
+    instance: &Instance<MyConfig>,
+) -> (MyConfig, StarkVerifyingKey<MyConfig>, Proof<MyConfig>) {
+    let config = make_config(perm);
+    let (trace, extension_trace) =
+        PowersAir::generate_traces(5, instance.extension_public_values[0], 5);
+    let (proving_key, verifying_key) = setup_keys(&config, &PowersAir, 1);
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_with_instance(
+        &config,
+        &proving_key,
+        &PowersAir,
+        &mut challenger,
+        trace,
+        Some(extension_trace),
+        instance,
+    );
+    (config, verifying_key, proof)
+}
+
+/// Whether verification of `proof` against `instance` fails, either with an error or, since the
+/// PCS panics on an opening proof for another transcript, a panic.
+fn rejects(
+    config: &MyConfig,
+    verifying_key: &StarkVerifyingKey<MyConfig>,
+    perm: &Perm,
+    proof: &Proof<MyConfig>,
+    instance: &Instance<MyConfig>,
+) -> bool {
+    let mut challenger = Challenger::new(perm.clone());
+    catch_unwind(AssertUnwindSafe(|| {
+        verify_with_instance(
+            config,
+            verifying_key,
+            &PowersAir,
+            &mut challenger,
+            proof,
+            instance,
+        )
+        .is_err()
+    }))
+    .unwrap_or(true)
+}
+
+type Val = BabyBear;
+type Perm = Poseidon2BabyBear<16>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel<Val>;
+type ValMmcs =
+    MerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+fn make_config(perm: &Perm) -> MyConfig {
+    let val_mmcs = ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm.clone()));
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig::new(1, 40, 8, challenge_mmcs);
+    MyConfig::new(Pcs::new(Dft::default(), val_mmcs, fri_config))
+}
+
+#[test]
+fn test_prove_with_instance() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let instance = make_instance();
+    let (config, verifying_key, proof) = prove_instance(&perm, &instance);
+    assert_eq!(verifying_key.num_extension_public_values(), 1);
+
+    let mut challenger = Challenger::new(perm);
+    verify_with_instance(
+        &config,
+        &verifying_key,
+        &PowersAir,
+        &mut challenger,
+        &proof,
+        &instance,
+    )
+    .expect("verification failed");
+}
+
+#[test]
+fn test_instance_data_is_bound() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let instance = make_instance();
+    let (config, verifying_key, proof) = prove_instance(&perm, &instance);
+
+    let mut changed = instance.clone();
+    changed.data[2][0] += Val::ONE;
+    assert!(rejects(&config, &verifying_key, &perm, &proof, &changed));
+
+    // Records are length-prefixed, so moving a value between them changes the transcript.
+    let mut regrouped = instance.clone();
+    regrouped.data[1] = vec![regrouped.data[0].pop().unwrap()];
+    assert!(rejects(&config, &verifying_key, &perm, &proof, &regrouped));
+
+    let mut dropped = instance;
+    dropped.data.clear();
+    assert!(rejects(&config, &verifying_key, &perm, &proof, &dropped));
+}
+
+#[test]
+fn test_extension_public_values_are_bound() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let instance = make_instance();
+    let (config, verifying_key, proof) = prove_instance(&perm, &instance);
+
+    let mut changed = instance.clone();
+    changed.extension_public_values[0] += Challenge::ONE;
+    assert!(rejects(&config, &verifying_key, &perm, &proof, &changed));
+
+    let mut missing = instance;
+    missing.extension_public_values.clear();
+    let mut challenger = Challenger::new(perm);
+    assert!(matches!(
+        verify_with_instance(
+            &config,
+            &verifying_key,
+            &PowersAir,
+            &mut challenger,
+            &proof,
+            &missing,
+        ),
+        Err(VerificationError::InvalidProofShape)
+    ));
+}
+
+#[test]
+fn test_compiled_extension_public_values() {
+    let compiled = CompiledAir::<Val>::new(&PowersAir, 0, 1);
+    assert_eq!(BaseAir::<Val>::num_extension_public_values(&compiled), 1);
+
+    let json = serde_json::to_string(&compiled).unwrap();
+    let parsed: CompiledAir<Val> = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.instructions(), compiled.instructions());
+
+    // The compiled AIR proves the same statement as the original.
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config(&perm);
+    let instance = make_instance();
+    let (trace, extension_trace) =
+        PowersAir::generate_traces(5, instance.extension_public_values[0], 4);
+    let (proving_key, verifying_key) = setup_keys(&config, &parsed, 1);
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_with_instance(
+        &config,
+        &proving_key,
+        &parsed,
+        &mut challenger,
+        trace,
+        Some(extension_trace),
+        &instance,
+    );
+    let mut challenger = Challenger::new(perm);
+    verify_with_instance(
+        &config,
+        &verifying_key,
+        &parsed,
+        &mut challenger,
+        &proof,
+        &instance,
+    )
+    .expect("verification failed");
+}
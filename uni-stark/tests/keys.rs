@@ -0,0 +1,185 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{Field, FieldAlgebra};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{prove, prove_with_key, setup_keys, verify, verify_with_key, StarkConfig};
+use rand::thread_rng;
+
+/// Consecutive integers alongside their squares, `y = x^2` and `x' = x + 1`, optionally pinning
+/// the first `x` to zero.
+pub struct SquaresAir {
+    pin_first_row: bool,
+}
+
+impl SquaresAir {
+    fn generate_trace<F: Field>(log_height: usize) -> RowMajorMatrix<F> {
+        let values = (0..1 << log_height)
+            .flat_map(|i| {
+                let x = F::from_canonical_usize(i);
+                [x, x.square()]
+            })
+            .collect();
+        RowMajorMatrix::new(values, 2)
+    }
+}
+
+impl<F> BaseAir<F> for SquaresAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for SquaresAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let next = main.row_slice(1);
+
+        builder.assert_eq(local[0] * local[0], local[1]);
+        builder
+            .when_transition()
+            .assert_eq(local[0] + AB::Expr::ONE, next[0]);
+        if self.pin_first_row {
+            builder.when_first_row().assert_zero(local[0]);
+        }
+    }
+}
+
+type Val = BabyBear;
+type Perm = Poseidon2BabyBear<16>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel<Val>;
+type ValMmcs =
+    MerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+fn make_config(perm: &Perm) -> MyConfig {
+    let val_mmcs = ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm.clone()));
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig::new(1, 40, 8, challenge_mmcs);
+    MyConfig::new(Pcs::new(Dft::default(), val_mmcs, fri_config))
+}
+
+#[test]
+fn test_keys_reused() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config(&perm);
+    let air = SquaresAir {
+        pin_first_row: true,
+    };
+
+    let (proving_key, verifying_key) = setup_keys(&config, &air, 0);
+    assert_eq!(verifying_key.log_quotient_degree(), 0);
+    assert_eq!(proving_key.constraint_count(), 3);
+
+    for log_height in [3, 5] {
+        let trace = SquaresAir::generate_trace(log_height);
+        let mut challenger = Challenger::new(perm.clone());
+        let proof = prove_with_key(&config, &proving_key, &air, &mut challenger, trace, &vec![]);
+        let mut challenger = Challenger::new(perm.clone());
+        verify_with_key(
+            &config,
+            &verifying_key,
+            &air,
+            &mut challenger,
+            &proof,
+            &vec![],
+        )
+        .expect("verification failed");
+
+        // Keys computed on the fly are the same.
+        let mut challenger = Challenger::new(perm.clone());
+        verify(&config, &air, &mut challenger, &proof, &vec![]).expect("verification failed");
+    }
+}
+
+#[test]
+fn test_fingerprint_is_deterministic() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config(&perm);
+
+    let (_, pinned) = setup_keys(
+        &config,
+        &SquaresAir {
+            pin_first_row: true,
+        },
+        0,
+    );
+    let (_, pinned_again) = setup_keys(
+        &config,
+        &SquaresAir {
+            pin_first_row: true,
+        },
+        0,
+    );
+    let (_, unpinned) = setup_keys(
+        &config,
+        &SquaresAir {
+            pin_first_row: false,
+        },
+        0,
+    );
+    let (_, with_public_value) = setup_keys(
+        &config,
+        &SquaresAir {
+            pin_first_row: true,
+        },
+        1,
+    );
+
+    assert_eq!(pinned.fingerprint(), pinned_again.fingerprint());
+    assert_ne!(pinned.fingerprint(), unpinned.fingerprint());
+    assert_ne!(pinned.fingerprint(), with_public_value.fingerprint());
+}
+
+#[test]
+fn test_proof_rejected_for_other_air() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config(&perm);
+    let unpinned = SquaresAir {
+        pin_first_row: false,
+    };
+    let pinned = SquaresAir {
+        pin_first_row: true,
+    };
+
+    // The trace satisfies both AIRs, but the proof is bound to the one it was generated for.
+    let trace = SquaresAir::generate_trace(4);
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &unpinned, &mut challenger, trace, &vec![]);
+
+    let mut challenger = Challenger::new(perm.clone());
+    verify(&config, &unpinned, &mut challenger, &proof, &vec![]).expect("verification failed");
+
+    let (_, verifying_key) = setup_keys(&config, &pinned, 0);
+    let mut challenger = Challenger::new(perm);
+    // The PCS panics, rather than returning an error, on an opening proof for another transcript.
+    let rejected = catch_unwind(AssertUnwindSafe(|| {
+        verify_with_key(
+            &config,
+            &verifying_key,
+            &pinned,
+            &mut challenger,
+            &proof,
+            &vec![],
+        )
+        .is_err()
+    }))
+    .unwrap_or(true);
+    assert!(rejected);
+}
@@ -0,0 +1,163 @@
+use p3_air::{Air, AirBuilder, BaseAir, Interaction, VirtualPairCol};
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{Field, FieldAlgebra};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{prove, prove_observed, setup_keys, verify, Instance, ProverError, StarkConfig};
+use rand::thread_rng;
+
+/// Range checks a column of values against a preprocessed table of `0..height`, using a LogUp
+/// lookup. Each row sends its value once and receives its table entry `multiplicity` times.
+///
+/// The main trace has columns `value`, `square` and `multiplicity`, with `square = value^2`.
+pub struct RangeCheckAir {
+    log_height: usize,
+}
+
+impl RangeCheckAir {
+    fn generate_trace<F: Field>(&self, values: &[u32]) -> RowMajorMatrix<F> {
+        let height = 1 << self.log_height;
+        assert_eq!(values.len(), height);
+        let mut multiplicities = vec![0u32; height];
+        for &value in values {
+            if let Some(multiplicity) = multiplicities.get_mut(value as usize) {
+                *multiplicity += 1;
+            }
+        }
+        let trace_values = values
+            .iter()
+            .zip(multiplicities)
+            .flat_map(|(&value, multiplicity)| {
+                let value = F::from_canonical_u32(value);
+                [value, value.square(), F::from_canonical_u32(multiplicity)]
+            })
+            .collect();
+        RowMajorMatrix::new(trace_values, 3)
+    }
+}
+
+impl<F: Field> BaseAir<F> for RangeCheckAir {
+    fn width(&self) -> usize {
+        3
+    }
+
+    fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
+        let height = 1 << self.log_height;
+        Some(RowMajorMatrix::new_col(
+            (0..height).map(F::from_canonical_usize).collect(),
+        ))
+    }
+
+    fn sends(&self) -> Vec<Interaction<F>> {
+        vec![Interaction::new(
+            vec![VirtualPairCol::single_main(0)],
+            VirtualPairCol::constant(F::ONE),
+            0,
+        )]
+    }
+
+    fn receives(&self) -> Vec<Interaction<F>> {
+        vec![Interaction::new(
+            vec![VirtualPairCol::single_preprocessed(0)],
+            VirtualPairCol::single_main(2),
+            0,
+        )]
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for RangeCheckAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        builder.assert_eq(local[0].into().square(), local[1]);
+    }
+}
+
+fn do_test(log_height: usize, values: &[u32]) {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config_with(&perm, 2, 28);
+    let air = RangeCheckAir { log_height };
+    let trace = air.generate_trace::<Val>(values);
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &vec![]);
+
+    let serialized_proof = postcard::to_allocvec(&proof).expect("unable to serialize proof");
+    let proof = postcard::from_bytes(&serialized_proof).expect("unable to deserialize proof");
+
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &air, &mut challenger, &proof, &vec![]).expect("verification failed");
+}
+
+type Val = BabyBear;
+type Perm = Poseidon2BabyBear<16>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel<Val>;
+type ValMmcs =
+    MerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+fn make_config_with(perm: &Perm, log_blowup: usize, num_queries: usize) -> MyConfig {
+    let val_mmcs = ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm.clone()));
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig::new(log_blowup, num_queries, 8, challenge_mmcs);
+    MyConfig::new(Pcs::new(Dft::default(), val_mmcs, fri_config))
+}
+
+#[test]
+fn test_range_check() {
+    let values = (0..64).map(|i| (i * 37 + 5) % 64).collect::<Vec<_>>();
+    do_test(6, &values);
+}
+
+#[test]
+fn test_range_check_repeated_values() {
+    let values = (0..32).map(|i| i % 3).collect::<Vec<_>>();
+    do_test(5, &values);
+}
+
+#[test]
+#[should_panic(expected = "do not balance")]
+fn test_range_check_out_of_range() {
+    let mut values = (0..32).collect::<Vec<_>>();
+    values[7] = 32;
+    do_test(5, &values);
+}
+
+#[test]
+fn test_range_check_out_of_range_error() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config_with(&perm, 2, 28);
+    let air = RangeCheckAir { log_height: 5 };
+    let mut values = (0..32).collect::<Vec<_>>();
+    values[7] = 32;
+    let trace = air.generate_trace::<Val>(&values);
+    let (proving_key, _) = setup_keys(&config, &air, 0);
+
+    // A witness whose interactions do not balance is an error, rather than a panic, for the
+    // entry points which return a `Result`.
+    let mut challenger = Challenger::new(perm);
+    let result = prove_observed(
+        &config,
+        &proving_key,
+        &air,
+        &mut challenger,
+        trace,
+        None,
+        &Instance::new(vec![]),
+        &mut (),
+    );
+    assert_eq!(result.err(), Some(ProverError::UnbalancedInteractions));
+}
@@ -214,12 +214,7 @@ fn do_test_bb_twoadic(log_blowup: usize, degree: u64, log_n: usize) -> Result<()
 
     type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
 
-    let fri_config = FriConfig {
-        log_blowup,
-        num_queries: 40,
-        proof_of_work_bits: 8,
-        mmcs: challenge_mmcs,
-    };
+    let fri_config = FriConfig::new(log_blowup, 40, 8, challenge_mmcs);
     type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
     let pcs = Pcs::new(dft, val_mmcs, fri_config);
 
@@ -274,12 +269,7 @@ fn do_test_m31_circle(log_blowup: usize, degree: u64, log_n: usize) -> Result<()
 
     type Challenger = SerializingChallenger32<Val, HashChallenger<u8, ByteHash, 32>>;
 
-    let fri_config = FriConfig {
-        log_blowup,
-        num_queries: 40,
-        proof_of_work_bits: 8,
-        mmcs: challenge_mmcs,
-    };
+    let fri_config = FriConfig::new(log_blowup, 40, 8, challenge_mmcs);
 
     type Pcs = CirclePcs<Val, ValMmcs, ChallengeMmcs>;
     let pcs = Pcs {
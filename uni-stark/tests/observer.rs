@@ -0,0 +1,227 @@
+use std::mem::discriminant;
+
+use p3_air::{Air, AirBuilder, BaseAir, Interaction, VirtualPairCol};
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::{CancellationToken, ExtensionMmcs, ProverObserver, ProverPhase};
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{Field, FieldAlgebra};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{prove_observed, setup_keys, verify, Instance, Proof, ProverError, StarkConfig};
+use rand::thread_rng;
+
+/// A counter, alongside a column which is a permutation of it.
+pub struct ShuffleAir;
+
+const LOG_HEIGHT: usize = 4;
+
+impl ShuffleAir {
+    fn generate_trace<F: Field>() -> RowMajorMatrix<F> {
+        let height = 1 << LOG_HEIGHT;
+        let values = (0..height)
+            .flat_map(|i| {
+                [
+                    F::from_canonical_usize(i),
+                    F::from_canonical_usize((5 * i + 3) % height),
+                ]
+            })
+            .collect();
+        RowMajorMatrix::new(values, 2)
+    }
+}
+
+impl<F: Field> BaseAir<F> for ShuffleAir {
+    fn width(&self) -> usize {
+        2
+    }
+
+    fn sends(&self) -> Vec<Interaction<F>> {
+        vec![Interaction::new(
+            vec![VirtualPairCol::single_main(0)],
+            VirtualPairCol::constant(F::ONE),
+            0,
+        )]
+    }
+
+    fn receives(&self) -> Vec<Interaction<F>> {
+        vec![Interaction::new(
+            vec![VirtualPairCol::single_main(1)],
+            VirtualPairCol::constant(F::ONE),
+            0,
+        )]
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for ShuffleAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let next = main.row_slice(1);
+        builder.when_first_row().assert_zero(local[0]);
+        builder
+            .when_transition()
+            .assert_eq(local[0] + AB::Expr::ONE, next[0]);
+    }
+}
+
+/// Records every event, and cancels once `cancel_after` has finished.
+#[derive(Default)]
+struct Recorder {
+    events: Vec<(bool, ProverPhase)>,
+    cancel_after: Option<ProverPhase>,
+    cancelled: bool,
+}
+
+impl ProverObserver for Recorder {
+    fn phase_started(&mut self, phase: ProverPhase) {
+        self.events.push((true, phase));
+    }
+
+    fn phase_finished(&mut self, phase: ProverPhase) {
+        self.events.push((false, phase));
+        self.cancelled |= self.cancel_after == Some(phase);
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+}
+
+impl Recorder {
+    fn started(&self) -> Vec<ProverPhase> {
+        self.events
+            .iter()
+            .filter(|(started, _)| *started)
+            .map(|&(_, phase)| phase)
+            .collect()
+    }
+}
+
+fn prove_shuffle(
+    config: &MyConfig,
+    perm: &Perm,
+    observer: &mut impl ProverObserver,
+) -> Result<Proof<MyConfig>, ProverError> {
+    let (proving_key, _) = setup_keys(config, &ShuffleAir, 0);
+    let mut challenger = Challenger::new(perm.clone());
+    prove_observed(
+        config,
+        &proving_key,
+        &ShuffleAir,
+        &mut challenger,
+        ShuffleAir::generate_trace(),
+        None,
+        &Instance::new(vec![]),
+        observer,
+    )
+}
+
+type Val = BabyBear;
+type Perm = Poseidon2BabyBear<16>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel<Val>;
+type ValMmcs =
+    MerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+fn make_config(perm: &Perm) -> MyConfig {
+    let val_mmcs = ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm.clone()));
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig::new(1, 40, 8, challenge_mmcs);
+    MyConfig::new(Pcs::new(Dft::default(), val_mmcs, fri_config))
+}
+
+#[test]
+fn test_observed_phases() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config(&perm);
+    let mut recorder = Recorder::default();
+    let proof = prove_shuffle(&config, &perm, &mut recorder).unwrap();
+
+    let started = recorder.started();
+    let expected = [
+        ProverPhase::TraceCommit {
+            height: 1 << LOG_HEIGHT,
+            width: 2,
+        },
+        ProverPhase::PermutationCommit {
+            height: 0,
+            width: 0,
+        },
+        ProverPhase::Quotient { size: 0 },
+        ProverPhase::QuotientCommit {
+            num_chunks: 0,
+            height: 1 << LOG_HEIGHT,
+        },
+        ProverPhase::Open { num_rounds: 3 },
+        ProverPhase::FriCommit { log_max_height: 0 },
+        ProverPhase::ProofOfWork { bits: 8 },
+        ProverPhase::FriQueries { num_queries: 40 },
+    ];
+    assert_eq!(
+        started.iter().map(discriminant).collect::<Vec<_>>(),
+        expected.iter().map(discriminant).collect::<Vec<_>>()
+    );
+    for phase in [0, 4, 6, 7] {
+        assert_eq!(started[phase], expected[phase]);
+    }
+
+    // Every phase finishes, with the FRI phases nested in the opening.
+    let mut open = vec![];
+    for &(is_start, phase) in &recorder.events {
+        if is_start {
+            open.push(phase);
+        } else {
+            assert_eq!(open.pop(), Some(phase));
+        }
+    }
+    assert!(open.is_empty());
+
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &ShuffleAir, &mut challenger, &proof, &vec![]).expect("verification failed");
+}
+
+#[test]
+fn test_cancel_between_phases() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config(&perm);
+    let mut recorder = Recorder {
+        cancel_after: Some(ProverPhase::TraceCommit {
+            height: 1 << LOG_HEIGHT,
+            width: 2,
+        }),
+        ..Default::default()
+    };
+    assert!(matches!(
+        prove_shuffle(&config, &perm, &mut recorder),
+        Err(ProverError::Cancelled)
+    ));
+    // The prover stopped before committing to the LogUp trace.
+    assert_eq!(recorder.events.len(), 2);
+}
+
+#[test]
+fn test_cancellation_token() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config(&perm);
+    let mut token = CancellationToken::new();
+    let handle = token.clone();
+    assert!(prove_shuffle(&config, &perm, &mut token).is_ok());
+
+    handle.cancel();
+    assert!(token.is_cancelled());
+    assert!(matches!(
+        prove_shuffle(&config, &perm, &mut token),
+        Err(ProverError::Cancelled)
+    ));
+}
@@ -0,0 +1,289 @@
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir, PeriodicAirBuilder};
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_challenger::{DuplexChallenger, HashChallenger, SerializingChallenger32};
+use p3_circle::CirclePcs;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{Field, FieldAlgebra};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_keccak::Keccak256Hash;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_mersenne_31::Mersenne31;
+use p3_symmetric::{
+    CompressionFunctionFromHasher, PaddingFreeSponge, SerializingHasher32, TruncatedPermutation,
+};
+use p3_uni_stark::{
+    check_constraints, get_symbolic_constraints, prove, verify, CompiledAir, StarkConfig,
+    StarkGenericConfig, Val, VerificationError,
+};
+use rand::thread_rng;
+
+/// A single column `x`, starting from the public value, which on each row is either incremented by
+/// the round constant or reset to it, according to a flag alternating between the two.
+///
+/// The round constants and flags are periodic columns, so only `x` is committed.
+pub struct RoundsAir {
+    constants: Vec<u32>,
+}
+
+/// Whether `x` is incremented rather than reset, on even and odd rows.
+const FLAGS: [bool; 2] = [true, false];
+
+impl RoundsAir {
+    fn generate_trace<F: Field>(&self, start: u32, log_height: usize) -> RowMajorMatrix<F> {
+        let height = 1 << log_height;
+        let mut x = F::from_canonical_u32(start);
+        let mut values = Vec::with_capacity(height);
+        for row in 0..height {
+            values.push(x);
+            let constant = F::from_canonical_u32(self.constants[row % self.constants.len()]);
+            x = if FLAGS[row % 2] {
+                x + constant
+            } else {
+                constant
+            };
+        }
+        RowMajorMatrix::new_col(values)
+    }
+}
+
+impl<F: Field> BaseAir<F> for RoundsAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn periodic_columns(&self) -> Vec<Vec<F>> {
+        vec![
+            self.constants
+                .iter()
+                .map(|&c| F::from_canonical_u32(c))
+                .collect(),
+            FLAGS.map(F::from_bool).to_vec(),
+        ]
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues + PeriodicAirBuilder> Air<AB> for RoundsAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (x, x_next) = (main.row_slice(0)[0], main.row_slice(1)[0]);
+        let start = builder.public_values()[0];
+        let constant: AB::Expr = builder.periodic_values()[0].into();
+        let flag: AB::Expr = builder.periodic_values()[1].into();
+
+        builder.when_first_row().assert_eq(x, start);
+        builder
+            .when_transition()
+            .assert_eq(x_next, flag * x + constant);
+    }
+}
+
+fn constants() -> Vec<u32> {
+    vec![3, 1, 4, 1, 5, 9, 2, 6]
+}
+
+fn do_test<SC: StarkGenericConfig>(
+    config: SC,
+    log_height: usize,
+    challenger: SC::Challenger,
+) -> Result<(), impl Debug>
+where
+    SC::Challenger: Clone,
+{
+    let air = RoundsAir {
+        constants: constants(),
+    };
+    let trace = air.generate_trace(7, log_height);
+    let public_values = vec![Val::<SC>::from_canonical_u32(7)];
+
+    let mut p_challenger = challenger.clone();
+    let proof = prove(&config, &air, &mut p_challenger, trace, &public_values);
+    // Only `x` is committed.
+    assert!(proof
+        .opened_values()
+        .trace()
+        .iter()
+        .all(|row| row.len() == 1));
+
+    let mut v_challenger = challenger;
+    verify(&config, &air, &mut v_challenger, &proof, &public_values)
+}
+
+type Perm = Poseidon2BabyBear<16>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type Challenge = BinomialExtensionField<BabyBear, 4>;
+type Challenger = DuplexChallenger<BabyBear, Perm, 16, 8>;
+type Dft = Radix2DitParallel<BabyBear>;
+type ValMmcs = MerkleTreeMmcs<
+    <BabyBear as Field>::Packing,
+    <BabyBear as Field>::Packing,
+    MyHash,
+    MyCompress,
+    8,
+>;
+type ChallengeMmcs = ExtensionMmcs<BabyBear, Challenge, ValMmcs>;
+type Pcs = TwoAdicFriPcs<BabyBear, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+fn make_config(perm: &Perm) -> MyConfig {
+    let val_mmcs = ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm.clone()));
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig::new(1, 40, 8, challenge_mmcs);
+    MyConfig::new(Pcs::new(Dft::default(), val_mmcs, fri_config))
+}
+
+#[test]
+fn test_periodic_columns_two_adic() -> Result<(), impl Debug> {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    do_test(make_config(&perm), 3, Challenger::new(perm.clone()))?;
+    do_test(make_config(&perm), 6, Challenger::new(perm))
+}
+
+#[test]
+fn test_periodic_columns_circle() -> Result<(), impl Debug> {
+    type Val = Mersenne31;
+    type Challenge = BinomialExtensionField<Val, 3>;
+
+    type ByteHash = Keccak256Hash;
+    type FieldHash = SerializingHasher32<ByteHash>;
+    let byte_hash = ByteHash {};
+    let field_hash = FieldHash::new(byte_hash);
+
+    type MyCompress = CompressionFunctionFromHasher<ByteHash, 2, 32>;
+    let compress = MyCompress::new(byte_hash);
+
+    type ValMmcs = MerkleTreeMmcs<Val, u8, FieldHash, MyCompress, 32>;
+    let val_mmcs = ValMmcs::new(field_hash, compress);
+
+    type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+
+    type Challenger = SerializingChallenger32<Val, HashChallenger<u8, ByteHash, 32>>;
+
+    let fri_config = FriConfig::new(1, 40, 8, challenge_mmcs);
+
+    type Pcs = CirclePcs<Val, ValMmcs, ChallengeMmcs>;
+    let pcs = Pcs {
+        mmcs: val_mmcs,
+        fri_config,
+        _phantom: PhantomData,
+    };
+
+    type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+    do_test(
+        MyConfig::new(pcs),
+        6,
+        Challenger::from_hasher(vec![], byte_hash),
+    )
+}
+
+#[test]
+fn test_periodic_columns_are_bound() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config(&perm);
+    let air = RoundsAir {
+        constants: constants(),
+    };
+    let trace = air.generate_trace::<BabyBear>(7, 5);
+    let public_values = vec![BabyBear::from_canonical_u32(7)];
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &public_values);
+
+    let mut changed = constants();
+    changed[5] += 1;
+    let other = RoundsAir { constants: changed };
+    let mut challenger = Challenger::new(perm);
+    let rejected = catch_unwind(AssertUnwindSafe(|| {
+        verify(&config, &other, &mut challenger, &proof, &public_values).is_err()
+    }))
+    .unwrap_or(true);
+    assert!(rejected);
+}
+
+#[test]
+fn test_period_longer_than_trace() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config(&perm);
+    let air = RoundsAir {
+        constants: vec![3, 1],
+    };
+    let trace = air.generate_trace::<BabyBear>(7, 2);
+    let public_values = vec![BabyBear::from_canonical_u32(7)];
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &public_values);
+
+    // A period of 8 rows does not fit in the 4 row trace.
+    let mut challenger = Challenger::new(perm);
+    let other = RoundsAir {
+        constants: constants(),
+    };
+    assert!(matches!(
+        verify(&config, &other, &mut challenger, &proof, &public_values),
+        Err(VerificationError::InvalidProofShape)
+    ));
+}
+
+#[test]
+fn test_check_constraints_with_periodic_columns() {
+    let air = RoundsAir {
+        constants: constants(),
+    };
+    let mut trace = air.generate_trace::<BabyBear>(7, 4);
+    let public_values = [BabyBear::from_canonical_u32(7)];
+    assert!(check_constraints(&air, &trace, &public_values).is_empty());
+
+    // Row 5 is row 4 plus its constant, but row 6 is reset to the constant of row 5, so changing
+    // row 5 only breaks the transition from row 4.
+    trace.values[5] += BabyBear::ONE;
+    let failures = check_constraints(&air, &trace, &public_values);
+    assert_eq!(
+        failures
+            .iter()
+            .map(|failure| failure.row)
+            .collect::<Vec<_>>(),
+        vec![4]
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_period_not_power_of_two() {
+    let air = RoundsAir {
+        constants: vec![3, 1, 4],
+    };
+    get_symbolic_constraints::<BabyBear, _>(&air, 0, 1);
+}
+
+#[test]
+fn test_compiled_periodic_columns() {
+    let air = RoundsAir {
+        constants: constants(),
+    };
+    let compiled = CompiledAir::<BabyBear>::new(&air, 0, 1);
+    assert_eq!(
+        BaseAir::<BabyBear>::periodic_columns(&compiled),
+        BaseAir::<BabyBear>::periodic_columns(&air)
+    );
+
+    let json = serde_json::to_string(&compiled).unwrap();
+    let parsed: CompiledAir<BabyBear> = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.instructions(), compiled.instructions());
+
+    // The compiled AIR proves the same statement as the original.
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config(&perm);
+    let trace = air.generate_trace::<BabyBear>(7, 4);
+    let public_values = vec![BabyBear::from_canonical_u32(7)];
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &parsed, &mut challenger, trace, &public_values);
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &parsed, &mut challenger, &proof, &public_values).expect("verification failed");
+}
@@ -0,0 +1,149 @@
+use p3_air::{Air, AirBuilder, BaseAir, PairBuilder};
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{Field, FieldAlgebra};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{
+    prove, prove_with_key, setup_keys, verify, verify_with_key, StarkConfig, VerificationError,
+};
+use rand::thread_rng;
+
+/// A MiMC-style iterated cube, `x' = (x + c)^3`, where the round constant `c` of each row is read
+/// from a preprocessed column rather than baked into the constraints.
+pub struct CubeRoundsAir {
+    log_height: usize,
+}
+
+impl CubeRoundsAir {
+    fn round_constant<F: Field>(row: usize) -> F {
+        F::from_canonical_usize(row * row + 7)
+    }
+
+    fn generate_trace<F: Field>(&self, input: F) -> RowMajorMatrix<F> {
+        let height = 1 << self.log_height;
+        let mut values = Vec::with_capacity(height);
+        let mut x = input;
+        for row in 0..height {
+            values.push(x);
+            x = (x + Self::round_constant::<F>(row)).cube();
+        }
+        RowMajorMatrix::new_col(values)
+    }
+}
+
+impl<F: Field> BaseAir<F> for CubeRoundsAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
+        let height = 1 << self.log_height;
+        Some(RowMajorMatrix::new_col(
+            (0..height).map(Self::round_constant).collect(),
+        ))
+    }
+}
+
+impl<AB: PairBuilder> Air<AB> for CubeRoundsAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let preprocessed = builder.preprocessed();
+
+        let x = main.row_slice(0)[0];
+        let x_next = main.row_slice(1)[0];
+        let c = preprocessed.row_slice(0)[0];
+
+        builder.when_transition().assert_eq((x + c).cube(), x_next);
+    }
+}
+
+type Val = BabyBear;
+type Perm = Poseidon2BabyBear<16>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel<Val>;
+type ValMmcs =
+    MerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+fn make_config_with(perm: &Perm, log_blowup: usize, num_queries: usize) -> MyConfig {
+    let val_mmcs = ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm.clone()));
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig::new(log_blowup, num_queries, 8, challenge_mmcs);
+    MyConfig::new(Pcs::new(Dft::default(), val_mmcs, fri_config))
+}
+
+#[test]
+fn test_preprocessed_setup_reused() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config_with(&perm, 2, 28);
+    let air = CubeRoundsAir { log_height: 6 };
+
+    let (proving_key, verifying_key) = setup_keys(&config, &air, 0);
+    assert_eq!(proving_key.preprocessed().unwrap().width(), 1);
+    assert_eq!(verifying_key.preprocessed().unwrap().degree_bits(), 6);
+
+    for input in [0, 1, 12345] {
+        let trace = air.generate_trace(Val::from_canonical_u32(input));
+        let mut challenger = Challenger::new(perm.clone());
+        let proof = prove_with_key(&config, &proving_key, &air, &mut challenger, trace, &vec![]);
+        let mut challenger = Challenger::new(perm.clone());
+        verify_with_key(
+            &config,
+            &verifying_key,
+            &air,
+            &mut challenger,
+            &proof,
+            &vec![],
+        )
+        .expect("verification failed");
+    }
+}
+
+#[test]
+fn test_preprocessed_setup_on_the_fly() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config_with(&perm, 2, 28);
+    let air = CubeRoundsAir { log_height: 5 };
+
+    let trace = air.generate_trace(Val::from_canonical_u32(3));
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &vec![]);
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &air, &mut challenger, &proof, &vec![]).expect("verification failed");
+}
+
+#[test]
+fn test_preprocessed_verifier_key_mismatch() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config_with(&perm, 2, 28);
+    let air = CubeRoundsAir { log_height: 5 };
+    let other_air = CubeRoundsAir { log_height: 6 };
+
+    let trace = air.generate_trace(Val::from_canonical_u32(3));
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &vec![]);
+
+    let (_, other_verifying_key) = setup_keys(&config, &other_air, 0);
+    let mut challenger = Challenger::new(perm);
+    let result = verify_with_key(
+        &config,
+        &other_verifying_key,
+        &air,
+        &mut challenger,
+        &proof,
+        &vec![],
+    );
+    assert!(matches!(result, Err(VerificationError::InvalidProofShape)));
+}
@@ -0,0 +1,230 @@
+use std::marker::PhantomData;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_challenger::{DuplexChallenger, HashChallenger, SerializingChallenger32};
+use p3_circle::CirclePcs;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{Field, FieldAlgebra};
+use p3_fri::{FriConfig, HidingFriPcs, TwoAdicFriPcs};
+use p3_keccak::Keccak256Hash;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::{MerkleTreeHidingMmcs, MerkleTreeMmcs};
+use p3_mersenne_31::Mersenne31;
+use p3_symmetric::{
+    CompressionFunctionFromHasher, PaddingFreeSponge, SerializingHasher32, TruncatedPermutation,
+};
+use p3_uni_stark::{prove, verify, Proof, StarkConfig, StarkGenericConfig, Val};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// A counter `x` from the public value, alongside its square `y`.
+pub struct SquaresAir;
+
+impl SquaresAir {
+    fn generate_trace<F: Field>(start: usize, log_height: usize) -> RowMajorMatrix<F> {
+        let values = (start..start + (1 << log_height))
+            .flat_map(|x| [F::from_canonical_usize(x), F::from_canonical_usize(x * x)])
+            .collect();
+        RowMajorMatrix::new(values, 2)
+    }
+}
+
+impl<F> BaseAir<F> for SquaresAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for SquaresAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let next = main.row_slice(1);
+        let start = builder.public_values()[0];
+
+        builder.when_first_row().assert_eq(local[0], start);
+        builder
+            .when_transition()
+            .assert_eq(local[0] + AB::Expr::ONE, next[0]);
+        builder.assert_eq(local[0] * local[0], local[1]);
+    }
+}
+
+const START: usize = 3;
+const LOG_HEIGHT: usize = 4;
+const NUM_MUTANTS: usize = 300;
+
+/// Change `bytes` in place by flipping a bit, inserting or removing bytes, or truncating them.
+fn mutate(bytes: &mut Vec<u8>, rng: &mut StdRng) {
+    match rng.gen_range(0..6) {
+        // Flipping one of the low seven bits keeps the structure of postcard's varints, so these
+        // mutants are the most likely to deserialize.
+        0..=2 => {
+            let i = rng.gen_range(0..bytes.len());
+            bytes[i] ^= 1 << rng.gen_range(0..7);
+        }
+        3 => {
+            let i = rng.gen_range(0..=bytes.len());
+            bytes.insert(i, rng.gen());
+        }
+        4 => {
+            let i = rng.gen_range(0..bytes.len());
+            let len = rng.gen_range(1..=8.min(bytes.len() - i));
+            bytes.drain(i..i + len);
+        }
+        _ => bytes.truncate(rng.gen_range(0..bytes.len())),
+    }
+}
+
+/// Prove the squares, then check that the verifier rejects, without panicking, every mutation of
+/// the serialized proof which still deserializes to a different proof.
+fn fuzz_proof<SC: StarkGenericConfig>(config: SC, challenger: SC::Challenger, seed: u64)
+where
+    SC::Challenger: Clone,
+{
+    let trace = SquaresAir::generate_trace(START, LOG_HEIGHT);
+    let public_values = vec![Val::<SC>::from_canonical_usize(START)];
+    let proof = prove(
+        &config,
+        &SquaresAir,
+        &mut challenger.clone(),
+        trace,
+        &public_values,
+    );
+    let bytes = postcard::to_allocvec(&proof).unwrap();
+    verify(
+        &config,
+        &SquaresAir,
+        &mut challenger.clone(),
+        &proof,
+        &public_values,
+    )
+    .expect("verification failed");
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut num_verified = 0;
+    for _ in 0..NUM_MUTANTS {
+        let mut mutant = bytes.clone();
+        for _ in 0..rng.gen_range(1..=3) {
+            if mutant.is_empty() {
+                break;
+            }
+            mutate(&mut mutant, &mut rng);
+        }
+        let Ok(mutant) = postcard::from_bytes::<Proof<SC>>(&mutant) else {
+            continue;
+        };
+        // A mutation of padding or trailing bytes leaves the proof as it was.
+        if postcard::to_allocvec(&mutant).unwrap() == bytes {
+            continue;
+        }
+
+        num_verified += 1;
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            verify(
+                &config,
+                &SquaresAir,
+                &mut challenger.clone(),
+                &mutant,
+                &public_values,
+            )
+            .is_err()
+        }));
+        assert!(matches!(result, Ok(true)), "mutated proof was not rejected");
+    }
+    // Many mutants fail to deserialize, but enough remain to exercise the verifier.
+    assert!(
+        num_verified >= NUM_MUTANTS / 5,
+        "only {num_verified} mutants"
+    );
+}
+
+type Perm = Poseidon2BabyBear<16>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type Challenge = BinomialExtensionField<BabyBear, 4>;
+type Challenger = DuplexChallenger<BabyBear, Perm, 16, 8>;
+type Dft = Radix2DitParallel<BabyBear>;
+type ValMmcs = MerkleTreeMmcs<
+    <BabyBear as Field>::Packing,
+    <BabyBear as Field>::Packing,
+    MyHash,
+    MyCompress,
+    8,
+>;
+type ChallengeMmcs = ExtensionMmcs<BabyBear, Challenge, ValMmcs>;
+type Pcs = TwoAdicFriPcs<BabyBear, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+type HidingValMmcs = MerkleTreeHidingMmcs<
+    <BabyBear as Field>::Packing,
+    <BabyBear as Field>::Packing,
+    MyHash,
+    MyCompress,
+    StdRng,
+    8,
+    4,
+>;
+type HidingChallengeMmcs = ExtensionMmcs<BabyBear, Challenge, HidingValMmcs>;
+type HidingPcs = HidingFriPcs<BabyBear, Dft, HidingValMmcs, HidingChallengeMmcs, StdRng>;
+type ZkConfig = StarkConfig<HidingPcs, Challenge, Challenger>;
+
+#[test]
+fn test_fuzz_two_adic_proof() {
+    let perm = Perm::new_from_rng_128(&mut StdRng::seed_from_u64(0));
+    let val_mmcs = ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm.clone()));
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let pcs = Pcs::new(
+        Dft::default(),
+        val_mmcs,
+        FriConfig::new(1, 10, 1, challenge_mmcs),
+    );
+    fuzz_proof(MyConfig::new(pcs), Challenger::new(perm), 1);
+}
+
+#[test]
+fn test_fuzz_hiding_proof() {
+    let perm = Perm::new_from_rng_128(&mut StdRng::seed_from_u64(0));
+    let val_mmcs = HidingValMmcs::new(
+        MyHash::new(perm.clone()),
+        MyCompress::new(perm.clone()),
+        StdRng::seed_from_u64(1),
+    );
+    let challenge_mmcs = HidingChallengeMmcs::new(val_mmcs.clone());
+    let pcs = HidingPcs::new(
+        Dft::default(),
+        val_mmcs,
+        FriConfig::new(1, 10, 1, challenge_mmcs),
+        4,
+        StdRng::seed_from_u64(2),
+    );
+    fuzz_proof(ZkConfig::new(pcs), Challenger::new(perm), 2);
+}
+
+#[test]
+fn test_fuzz_circle_proof() {
+    type Val = Mersenne31;
+    type Challenge = BinomialExtensionField<Val, 3>;
+    type ByteHash = Keccak256Hash;
+    type FieldHash = SerializingHasher32<ByteHash>;
+    type MyCompress = CompressionFunctionFromHasher<ByteHash, 2, 32>;
+    type ValMmcs = MerkleTreeMmcs<Val, u8, FieldHash, MyCompress, 32>;
+    type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+    type Challenger = SerializingChallenger32<Val, HashChallenger<u8, ByteHash, 32>>;
+    type Pcs = CirclePcs<Val, ValMmcs, ChallengeMmcs>;
+
+    let byte_hash = ByteHash {};
+    let val_mmcs = ValMmcs::new(FieldHash::new(byte_hash), MyCompress::new(byte_hash));
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let pcs = Pcs {
+        mmcs: val_mmcs,
+        fri_config: FriConfig::new(1, 10, 1, challenge_mmcs),
+        _phantom: PhantomData,
+    };
+    let config = StarkConfig::<Pcs, Challenge, Challenger>::new(pcs);
+    fuzz_proof(config, Challenger::from_hasher(vec![], byte_hash), 3);
+}
@@ -0,0 +1,153 @@
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::Field;
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{prove, verify, Proof, StarkConfig, VerificationError};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+/// A column which doubles from one row to the next.
+pub struct DoublingAir;
+
+impl<F> BaseAir<F> for DoublingAir {
+    fn width(&self) -> usize {
+        1
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for DoublingAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let next = main.row_slice(1);
+        builder.when_first_row().assert_one(local[0]);
+        builder
+            .when_transition()
+            .assert_eq(local[0] + local[0], next[0]);
+    }
+}
+
+const LOG_HEIGHT: usize = 5;
+
+fn generate_trace<F: Field>() -> RowMajorMatrix<F> {
+    let values = F::TWO.powers().take(1 << LOG_HEIGHT).collect();
+    RowMajorMatrix::new(values, 1)
+}
+
+fn make_config(perm: &Perm, commit_proof_of_work_bits: usize, zeta_bits: usize) -> MyConfig {
+    let val_mmcs = ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm.clone()));
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig::new(1, 10, 4, challenge_mmcs)
+        .with_commit_proof_of_work_bits(commit_proof_of_work_bits);
+    let pcs = Pcs::new(Dft::default(), val_mmcs, fri_config);
+    MyConfig::new(pcs).with_zeta_proof_of_work_bits(zeta_bits)
+}
+
+/// The offset and size of `component` in the encoding of `proof`.
+fn locate(proof: &Proof<MyConfig>, component: &str) -> (usize, usize) {
+    let sizes = proof.size_by_component();
+    let index = sizes
+        .iter()
+        .position(|(name, _)| name == component)
+        .unwrap();
+    let offset = sizes[..index].iter().map(|(_, size)| size).sum();
+    (offset, sizes[index].1)
+}
+
+type Val = BabyBear;
+type Perm = Poseidon2BabyBear<16>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel<Val>;
+type ValMmcs =
+    MerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+#[test]
+fn test_grinding() {
+    let perm = Perm::new_from_rng_128(&mut StdRng::seed_from_u64(0));
+    let config = make_config(&perm, 4, 8);
+    let proof = prove(
+        &config,
+        &DoublingAir,
+        &mut Challenger::new(perm.clone()),
+        generate_trace(),
+        &vec![],
+    );
+    verify(
+        &config,
+        &DoublingAir,
+        &mut Challenger::new(perm.clone()),
+        &proof,
+        &vec![],
+    )
+    .expect("verification failed");
+
+    // A present witness is tagged, and there is one commit phase witness per folding round.
+    assert_eq!(locate(&proof, "zeta_pow_witness").1, 1 + 4);
+    assert_eq!(
+        locate(&proof, "opening_proof.commit_pow_witnesses").1,
+        4 + LOG_HEIGHT * 4
+    );
+
+    // A verifier which does not expect the witness rejects the proof outright.
+    let result = verify(
+        &make_config(&perm, 4, 0),
+        &DoublingAir,
+        &mut Challenger::new(perm.clone()),
+        &proof,
+        &vec![],
+    );
+    assert!(matches!(result, Err(VerificationError::InvalidProofShape)));
+
+    // Tampering with the witness ground before `zeta` is caught before `zeta` is sampled.
+    let mut bytes = proof.to_bytes();
+    let (offset, _) = locate(&proof, "zeta_pow_witness");
+    bytes[offset + 1] ^= 1;
+    let tampered = Proof::<MyConfig>::from_bytes(&bytes).unwrap();
+    let result = verify(
+        &config,
+        &DoublingAir,
+        &mut Challenger::new(perm),
+        &tampered,
+        &vec![],
+    );
+    assert!(matches!(result, Err(VerificationError::InvalidPowWitness)));
+}
+
+#[test]
+fn test_no_grinding() {
+    let perm = Perm::new_from_rng_128(&mut StdRng::seed_from_u64(1));
+    let config = make_config(&perm, 0, 0);
+    let proof = prove(
+        &config,
+        &DoublingAir,
+        &mut Challenger::new(perm.clone()),
+        generate_trace(),
+        &vec![],
+    );
+    verify(
+        &config,
+        &DoublingAir,
+        &mut Challenger::new(perm),
+        &proof,
+        &vec![],
+    )
+    .expect("verification failed");
+
+    // Without grinding, the witnesses are absent.
+    assert_eq!(locate(&proof, "zeta_pow_witness").1, 1);
+    assert_eq!(locate(&proof, "opening_proof.commit_pow_witnesses").1, 4);
+}
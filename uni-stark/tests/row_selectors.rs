@@ -0,0 +1,229 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir, RowSelector};
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{Field, FieldAlgebra};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{
+    check_constraints, get_symbolic_constraints, prove, verify, CompiledAir, StarkConfig,
+    VerificationError,
+};
+use rand::thread_rng;
+
+/// A counter `x` from zero, whose value on row `row` is the public value, alongside a column `y`
+/// which is zero on every row congruent to `OFFSET` modulo `PERIOD`.
+pub struct BoundaryAir {
+    row: usize,
+}
+
+const PERIOD: usize = 4;
+const OFFSET: usize = 1;
+
+impl BoundaryAir {
+    fn generate_trace<F: Field>(log_height: usize) -> RowMajorMatrix<F> {
+        let height = 1 << log_height;
+        let values = (0..height)
+            .flat_map(|i| {
+                let y = if i % PERIOD == OFFSET { 0 } else { 3 * i + 1 };
+                [F::from_canonical_usize(i), F::from_canonical_usize(y)]
+            })
+            .collect();
+        RowMajorMatrix::new(values, 2)
+    }
+}
+
+impl<F> BaseAir<F> for BoundaryAir {
+    fn width(&self) -> usize {
+        2
+    }
+
+    fn row_selectors(&self) -> Vec<RowSelector> {
+        vec![
+            RowSelector::Row(self.row),
+            RowSelector::Periodic {
+                period: PERIOD,
+                offset: OFFSET,
+            },
+        ]
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for BoundaryAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let next = main.row_slice(1);
+        let value = builder.public_values()[0];
+
+        builder.when_first_row().assert_zero(local[0]);
+        builder
+            .when_transition()
+            .assert_eq(local[0] + AB::Expr::ONE, next[0]);
+        builder.when_row(self.row).assert_eq(local[0], value);
+        builder
+            .when_periodic_row(PERIOD, OFFSET)
+            .assert_zero(local[1]);
+    }
+}
+
+/// An AIR which asserts a constraint at a row without declaring its selector.
+pub struct UndeclaredAir;
+
+impl<F> BaseAir<F> for UndeclaredAir {
+    fn width(&self) -> usize {
+        1
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for UndeclaredAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0)[0];
+        builder.when_row(3).assert_zero(local);
+    }
+}
+
+fn do_test(row: usize, log_height: usize) {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config(&perm);
+    let air = BoundaryAir { row };
+    let trace = BoundaryAir::generate_trace::<Val>(log_height);
+    let public_values = vec![Val::from_canonical_usize(row)];
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &public_values);
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &air, &mut challenger, &proof, &public_values).expect("verification failed");
+}
+
+type Val = BabyBear;
+type Perm = Poseidon2BabyBear<16>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel<Val>;
+type ValMmcs =
+    MerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+fn make_config(perm: &Perm) -> MyConfig {
+    let val_mmcs = ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm.clone()));
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig::new(1, 40, 8, challenge_mmcs);
+    MyConfig::new(Pcs::new(Dft::default(), val_mmcs, fri_config))
+}
+
+#[test]
+fn test_row_selectors() {
+    do_test(17, 5);
+    do_test(17, 7);
+    do_test(0, 3);
+}
+
+#[test]
+fn test_wrong_public_value_is_rejected() {
+    let air = BoundaryAir { row: 17 };
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config(&perm);
+    let trace = BoundaryAir::generate_trace::<Val>(5);
+    let public_values = vec![Val::from_canonical_usize(17)];
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &public_values);
+
+    let wrong = vec![Val::from_canonical_usize(18)];
+    let mut challenger = Challenger::new(perm);
+    let rejected = catch_unwind(AssertUnwindSafe(|| {
+        verify(&config, &air, &mut challenger, &proof, &wrong).is_err()
+    }))
+    .unwrap_or(true);
+    assert!(rejected);
+}
+
+#[test]
+fn test_row_selector_outside_trace() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config(&perm);
+    let trace = BoundaryAir::generate_trace::<Val>(4);
+    let public_values = vec![Val::from_canonical_usize(7)];
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(
+        &config,
+        &BoundaryAir { row: 7 },
+        &mut challenger,
+        trace,
+        &public_values,
+    );
+
+    // Row 17 is past the end of the 16 row trace.
+    let mut challenger = Challenger::new(perm);
+    assert!(matches!(
+        verify(
+            &config,
+            &BoundaryAir { row: 17 },
+            &mut challenger,
+            &proof,
+            &public_values
+        ),
+        Err(VerificationError::InvalidProofShape)
+    ));
+}
+
+#[test]
+fn test_check_constraints_at_rows() {
+    let air = BoundaryAir { row: 17 };
+    let mut trace = BoundaryAir::generate_trace::<Val>(5);
+    let public_values = [Val::from_canonical_usize(17)];
+    assert!(check_constraints(&air, &trace, &public_values).is_empty());
+
+    let failures = check_constraints(&air, &trace, &[Val::from_canonical_usize(16)]);
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].row, 17);
+
+    // Row 9 is congruent to the offset, row 10 is not.
+    trace.values[2 * 9 + 1] = Val::ONE;
+    trace.values[2 * 10 + 1] = Val::ONE;
+    let failures = check_constraints(&air, &trace, &public_values);
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].row, 9);
+}
+
+#[test]
+#[should_panic]
+fn test_undeclared_row_selector() {
+    get_symbolic_constraints::<Val, _>(&UndeclaredAir, 0, 0);
+}
+
+#[test]
+fn test_compiled_row_selectors() {
+    let air = BoundaryAir { row: 17 };
+    let compiled = CompiledAir::<Val>::new(&air, 0, 1);
+    assert_eq!(
+        BaseAir::<Val>::row_selectors(&compiled),
+        BaseAir::<Val>::row_selectors(&air)
+    );
+
+    let json = serde_json::to_string(&compiled).unwrap();
+    let parsed: CompiledAir<Val> = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.instructions(), compiled.instructions());
+
+    // The compiled AIR proves the same statement as the original.
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config(&perm);
+    let trace = BoundaryAir::generate_trace::<Val>(5);
+    let public_values = vec![Val::from_canonical_usize(17)];
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &parsed, &mut challenger, trace, &public_values);
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &parsed, &mut challenger, &proof, &public_values).expect("verification failed");
+}
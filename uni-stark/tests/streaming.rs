@@ -0,0 +1,256 @@
+use std::cell::RefCell;
+use std::ops::Range;
+
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{Field, FieldAlgebra};
+use p3_fri::{FriConfig, HidingFriPcs, TwoAdicFriPcs};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::{MerkleTreeHidingMmcs, MerkleTreeMmcs};
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{
+    prove_streaming, prove_with_instance, setup_keys, verify_with_instance, Instance, StarkConfig,
+    StarkGenericConfig, Val,
+};
+use rand::rngs::StdRng;
+use rand::{thread_rng, SeedableRng};
+
+/// `WIDTH` counters, where counter `j` starts at `j` and steps by `j + 1`.
+pub struct CountersAir;
+
+const WIDTH: usize = 7;
+
+impl CountersAir {
+    /// The columns of the trace in `columns`, computed without the others.
+    fn generate_columns<F: Field>(columns: Range<usize>, height: usize) -> RowMajorMatrix<F> {
+        let width = columns.len();
+        let values = (0..height)
+            .flat_map(|row| {
+                columns
+                    .clone()
+                    .map(move |j| F::from_canonical_usize(j + row * (j + 1)))
+            })
+            .collect();
+        RowMajorMatrix::new(values, width)
+    }
+}
+
+impl<F> BaseAir<F> for CountersAir {
+    fn width(&self) -> usize {
+        WIDTH
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for CountersAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let next = main.row_slice(1);
+        for j in 0..WIDTH {
+            builder
+                .when_first_row()
+                .assert_eq(local[j], AB::Expr::from_canonical_usize(j));
+            builder
+                .when_transition()
+                .assert_eq(local[j] + AB::Expr::from_canonical_usize(j + 1), next[j]);
+        }
+    }
+}
+
+const LOG_HEIGHT: usize = 6;
+
+/// With a blowup of 2, an LDE column takes `2 * 64 * 4` bytes, and the budget covers two such
+/// columns, each held twice over.
+const CHUNK_BUDGET: usize = 2 * 2 * (2 << LOG_HEIGHT) * 4;
+
+fn make_config(perm: &Perm, chunk_budget: Option<usize>) -> MyConfig {
+    let val_mmcs = ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm.clone()));
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let mut pcs = Pcs::new(
+        Dft::default(),
+        val_mmcs,
+        FriConfig::new(1, 40, 8, challenge_mmcs),
+    );
+    if let Some(bytes) = chunk_budget {
+        pcs = pcs.with_chunk_budget(bytes);
+    }
+    MyConfig::new(pcs)
+}
+
+fn make_zk_config(perm: &Perm) -> ZkConfig {
+    ZkConfig::new(make_hiding_pcs(perm, 1, 40).with_chunk_budget(CHUNK_BUDGET))
+}
+
+/// Prove the counters with `prove_streaming`, returning the proof and the ranges of columns which
+/// were requested.
+fn prove_counters<SC: StarkGenericConfig>(
+    config: &SC,
+    challenger: &mut SC::Challenger,
+) -> (p3_uni_stark::Proof<SC>, Vec<Range<usize>>) {
+    let (proving_key, _) = setup_keys(config, &CountersAir, 0);
+    let requested = RefCell::new(vec![]);
+    let proof = prove_streaming(
+        config,
+        &proving_key,
+        &CountersAir,
+        challenger,
+        1 << LOG_HEIGHT,
+        |columns| {
+            requested.borrow_mut().push(columns.clone());
+            CountersAir::generate_columns::<Val<SC>>(columns, 1 << LOG_HEIGHT)
+        },
+        &Instance::new(vec![]),
+        &mut (),
+    )
+    .unwrap();
+    (proof, requested.into_inner())
+}
+
+type Perm = Poseidon2BabyBear<16>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type Challenge = BinomialExtensionField<BabyBear, 4>;
+type Challenger = DuplexChallenger<BabyBear, Perm, 16, 8>;
+type Dft = Radix2DitParallel<BabyBear>;
+type ValMmcs = MerkleTreeMmcs<
+    <BabyBear as Field>::Packing,
+    <BabyBear as Field>::Packing,
+    MyHash,
+    MyCompress,
+    8,
+>;
+type ChallengeMmcs = ExtensionMmcs<BabyBear, Challenge, ValMmcs>;
+type Pcs = TwoAdicFriPcs<BabyBear, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+type HidingValMmcs = MerkleTreeHidingMmcs<
+    <BabyBear as Field>::Packing,
+    <BabyBear as Field>::Packing,
+    MyHash,
+    MyCompress,
+    StdRng,
+    8,
+    4,
+>;
+type HidingChallengeMmcs = ExtensionMmcs<BabyBear, Challenge, HidingValMmcs>;
+type HidingPcs = HidingFriPcs<BabyBear, Dft, HidingValMmcs, HidingChallengeMmcs, StdRng>;
+type ZkConfig = StarkConfig<HidingPcs, Challenge, Challenger>;
+
+fn make_hiding_pcs(perm: &Perm, log_blowup: usize, num_queries: usize) -> HidingPcs {
+    let val_mmcs = HidingValMmcs::new(
+        MyHash::new(perm.clone()),
+        MyCompress::new(perm.clone()),
+        StdRng::from_entropy(),
+    );
+    let challenge_mmcs = HidingChallengeMmcs::new(val_mmcs.clone());
+    HidingPcs::new(
+        Dft::default(),
+        val_mmcs,
+        FriConfig::new(log_blowup, num_queries, 8, challenge_mmcs),
+        4,
+        StdRng::from_entropy(),
+    )
+}
+
+#[test]
+fn test_streaming_matches_prove() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let instance = Instance::new(vec![]);
+
+    let config = make_config(&perm, Some(CHUNK_BUDGET));
+    let mut challenger = Challenger::new(perm.clone());
+    let (proof, requested) = prove_counters(&config, &mut challenger);
+    assert_eq!(requested, vec![0..2, 2..4, 4..6, 6..7]);
+
+    let (proving_key, verifying_key) = setup_keys(&config, &CountersAir, 0);
+    let mut challenger = Challenger::new(perm.clone());
+    verify_with_instance(
+        &config,
+        &verifying_key,
+        &CountersAir,
+        &mut challenger,
+        &proof,
+        &instance,
+    )
+    .expect("verification failed");
+
+    // Committing a chunk at a time gives the same commitment, and so the same proof, as committing
+    // to the whole trace.
+    let whole_config = make_config(&perm, None);
+    let mut challenger = Challenger::new(perm);
+    let whole_proof = prove_with_instance(
+        &whole_config,
+        &proving_key,
+        &CountersAir,
+        &mut challenger,
+        CountersAir::generate_columns(0..WIDTH, 1 << LOG_HEIGHT),
+        None,
+        &instance,
+    );
+    assert_eq!(
+        postcard::to_allocvec(&proof).unwrap(),
+        postcard::to_allocvec(&whole_proof).unwrap()
+    );
+}
+
+#[test]
+fn test_streaming_lde_in_file() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let val_mmcs = ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm.clone()));
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let pcs = Pcs::new(
+        Dft::default(),
+        val_mmcs,
+        FriConfig::new(1, 40, 8, challenge_mmcs),
+    )
+    .with_chunk_budget(CHUNK_BUDGET)
+    .with_lde_dir(std::env::temp_dir());
+    let config = MyConfig::new(pcs);
+    let mut challenger = Challenger::new(perm.clone());
+    let (proof, _) = prove_counters(&config, &mut challenger);
+
+    // Keeping the committed LDE in a file does not change the proof.
+    let memory_config = make_config(&perm, Some(CHUNK_BUDGET));
+    let mut challenger = Challenger::new(perm);
+    let (memory_proof, _) = prove_counters(&memory_config, &mut challenger);
+    assert_eq!(
+        postcard::to_allocvec(&proof).unwrap(),
+        postcard::to_allocvec(&memory_proof).unwrap()
+    );
+}
+
+#[test]
+fn test_streaming_without_budget() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config(&perm, None);
+    let mut challenger = Challenger::new(perm);
+    let (_, requested) = prove_counters(&config, &mut challenger);
+    assert_eq!(requested, vec![0..WIDTH]);
+}
+
+#[test]
+fn test_streaming_zk() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_zk_config(&perm);
+    let mut challenger = Challenger::new(perm.clone());
+    let (proof, requested) = prove_counters(&config, &mut challenger);
+    // The randomized trace is twice as tall, so the chunks are half as wide. The PCS generates the
+    // random codewords it appends without requesting any columns.
+    assert_eq!(requested, (0..WIDTH).map(|j| j..j + 1).collect::<Vec<_>>());
+
+    let (_, verifying_key) = setup_keys(&config, &CountersAir, 0);
+    let mut challenger = Challenger::new(perm);
+    verify_with_instance(
+        &config,
+        &verifying_key,
+        &CountersAir,
+        &mut challenger,
+        &proof,
+        &Instance::new(vec![]),
+    )
+    .expect("verification failed");
+}
@@ -0,0 +1,218 @@
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{Field, FieldAlgebra};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::MerkleTreeMmcs;
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{
+    prove, setup_keys, verify, AirStats, DebugConstraintBuilder, ProverConstraintFolder,
+    StarkConfig, SymbolicAirBuilder, VerifierConstraintFolder,
+};
+use rand::thread_rng;
+
+/// A single column sequence `x`, in which `x[i + lag] = x[i] + x[i + 1]`, starting from `1, 1`.
+///
+/// With `lag = 2` this is the Fibonacci sequence, over a window of three consecutive rows. With a
+/// larger lag, the window skips the rows in between.
+pub struct LaggedSumAir {
+    lag: usize,
+}
+
+impl LaggedSumAir {
+    fn generate_trace<F: Field>(&self, log_height: usize) -> RowMajorMatrix<F> {
+        let height = 1 << log_height;
+        let mut values = vec![F::ONE; self.lag];
+        for i in self.lag..height {
+            values.push(values[i - self.lag] + values[i - self.lag + 1]);
+        }
+        RowMajorMatrix::new_col(values)
+    }
+}
+
+impl<F> BaseAir<F> for LaggedSumAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn row_offsets(&self) -> Vec<usize> {
+        vec![0, 1, self.lag]
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for LaggedSumAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0)[0];
+        let next = main.row_slice(1)[0];
+        let lagged = main.row_slice(2)[0];
+
+        builder.when_first_row().assert_one(local);
+        builder.when_first_row().assert_one(next);
+        builder
+            .when_transition_window(self.lag + 1)
+            .assert_eq(local + next, lagged);
+    }
+}
+
+/// A single column sequence `x`, in which `x[i + lag] = x[i]^(degree - 1) x[i + 1]`, starting
+/// from `2, 3`.
+///
+/// The transition selector of the window has degree `lag`, which the quotient must make room for on
+/// top of the constraint's own degree once it reaches it.
+pub struct LaggedProductAir {
+    lag: usize,
+    degree: usize,
+}
+
+impl LaggedProductAir {
+    fn generate_trace<F: Field>(&self, log_height: usize) -> RowMajorMatrix<F> {
+        let height = 1 << log_height;
+        let mut values = vec![F::TWO, F::from_canonical_u8(3)];
+        values.resize(self.lag, F::ONE);
+        for i in self.lag..height {
+            values.push(
+                values[i - self.lag].exp_u64(self.degree as u64 - 1) * values[i - self.lag + 1],
+            );
+        }
+        RowMajorMatrix::new_col(values)
+    }
+}
+
+impl<F> BaseAir<F> for LaggedProductAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn row_offsets(&self) -> Vec<usize> {
+        vec![0, 1, self.lag]
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for LaggedProductAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local: AB::Expr = main.row_slice(0)[0].into();
+        let next = main.row_slice(1)[0];
+        let lagged = main.row_slice(2)[0];
+
+        builder
+            .when_first_row()
+            .assert_eq(local.clone(), AB::Expr::TWO);
+        builder
+            .when_transition_window(self.lag + 1)
+            .assert_eq(local.exp_u64(self.degree as u64 - 1) * next, lagged);
+    }
+}
+
+fn do_test<A>(air: A, trace: RowMajorMatrix<Val>)
+where
+    A: BaseAir<Val>
+        + Air<SymbolicAirBuilder<Val>>
+        + for<'a> Air<ProverConstraintFolder<'a, MyConfig>>
+        + for<'a> Air<VerifierConstraintFolder<'a, MyConfig>>
+        + for<'a> Air<DebugConstraintBuilder<'a, Val, Challenge>>,
+{
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config_with(&perm, 2, 40);
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &vec![]);
+    assert_eq!(proof.opened_values().trace().len(), air.row_offsets().len());
+
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &air, &mut challenger, &proof, &vec![]).expect("verification failed");
+}
+
+fn do_test_sum(lag: usize, log_height: usize) {
+    let air = LaggedSumAir { lag };
+    let trace = air.generate_trace(log_height);
+    do_test(air, trace);
+}
+
+fn do_test_product(lag: usize, degree: usize, log_height: usize) {
+    let air = LaggedProductAir { lag, degree };
+    let trace = air.generate_trace(log_height);
+    do_test(air, trace);
+}
+
+type Val = BabyBear;
+type Perm = Poseidon2BabyBear<16>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel<Val>;
+type ValMmcs =
+    MerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+fn make_config_with(perm: &Perm, log_blowup: usize, num_queries: usize) -> MyConfig {
+    let val_mmcs = ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm.clone()));
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig::new(log_blowup, num_queries, 8, challenge_mmcs);
+    MyConfig::new(Pcs::new(Dft::default(), val_mmcs, fri_config))
+}
+
+#[test]
+fn test_consecutive_window() {
+    do_test_sum(2, 3);
+    do_test_sum(2, 6);
+}
+
+#[test]
+fn test_sparse_window() {
+    do_test_sum(4, 3);
+    do_test_sum(4, 6);
+}
+
+#[test]
+fn test_nonlinear_window() {
+    // The window's selector has degree `lag`, and reaches the degree of the constraint.
+    do_test_product(2, 2, 3);
+    do_test_product(2, 2, 6);
+    do_test_product(4, 3, 3);
+    do_test_product(4, 3, 6);
+    // The selector's degree is below that of the constraint.
+    do_test_product(2, 3, 5);
+}
+
+#[test]
+fn test_nonlinear_window_degree() {
+    let degrees = |lag, degree| {
+        AirStats::new::<Val, _>(&LaggedProductAir { lag, degree }, 0, 0, false).constraint_degrees
+    };
+    assert_eq!(degrees(2, 2), [2, 3]);
+    assert_eq!(degrees(4, 3), [2, 4]);
+    assert_eq!(degrees(2, 3), [2, 3]);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic]
+fn test_sparse_window_invalid_trace() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config_with(&perm, 2, 40);
+    let air = LaggedSumAir { lag: 4 };
+    let mut trace = air.generate_trace::<Val>(4);
+    // Break `x[9] = x[5] + x[6]`, which only a window of 5 rows reaches.
+    trace.values[9] += Val::ONE;
+
+    let mut challenger = Challenger::new(perm);
+    prove(&config, &air, &mut challenger, trace, &vec![]);
+}
+
+#[test]
+fn test_row_offsets_in_verifying_key() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_config_with(&perm, 2, 40);
+    let (_, verifying_key) = setup_keys(&config, &LaggedSumAir { lag: 4 }, 0);
+    assert_eq!(verifying_key.row_offsets(), [0, 1, 4]);
+}
@@ -0,0 +1,239 @@
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir, Interaction, VirtualPairCol};
+use p3_baby_bear::{BabyBear, Poseidon2BabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{Field, FieldAlgebra, FieldExtensionAlgebra};
+use p3_fri::{FriConfig, HidingFriPcs, TwoAdicFriPcs};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::{MerkleTreeHidingMmcs, MerkleTreeMmcs};
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{prove, verify, StarkConfig};
+use rand::rngs::StdRng;
+use rand::{thread_rng, SeedableRng};
+
+/// A column which is constant and equal to the public value `c`, alongside a column holding `c^2`.
+///
+/// The trace polynomials are constants, so without zero knowledge their openings are exactly `c`
+/// and `c^2`, and every constraint vanishes identically, making the quotient zero.
+pub struct ConstantAir;
+
+impl<F> BaseAir<F> for ConstantAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for ConstantAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let c = builder.public_values()[0];
+        let local = main.row_slice(0);
+        let next = main.row_slice(1);
+
+        builder.when_first_row().assert_eq(local[0], c);
+        builder.when_transition().assert_eq(local[0], next[0]);
+        builder.assert_eq(local[0].into().square(), local[1]);
+    }
+}
+
+/// Checks that the second column is a permutation of the first, using a LogUp argument.
+pub struct PermutationAir;
+
+impl<F: Field> BaseAir<F> for PermutationAir {
+    fn width(&self) -> usize {
+        2
+    }
+
+    fn sends(&self) -> Vec<Interaction<F>> {
+        vec![Interaction::new(
+            vec![VirtualPairCol::single_main(0)],
+            VirtualPairCol::constant(F::ONE),
+            0,
+        )]
+    }
+
+    fn receives(&self) -> Vec<Interaction<F>> {
+        vec![Interaction::new(
+            vec![VirtualPairCol::single_main(1)],
+            VirtualPairCol::constant(F::ONE),
+            0,
+        )]
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for PermutationAir {
+    fn eval(&self, _builder: &mut AB) {}
+}
+
+fn constant_trace<F: Field>(c: F, height: usize) -> RowMajorMatrix<F> {
+    RowMajorMatrix::new([c, c.square()].repeat(height), 2)
+}
+
+type Val = BabyBear;
+type Perm = Poseidon2BabyBear<16>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel<Val>;
+type ValMmcs =
+    MerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+type HidingValMmcs = MerkleTreeHidingMmcs<
+    <Val as Field>::Packing,
+    <Val as Field>::Packing,
+    MyHash,
+    MyCompress,
+    StdRng,
+    8,
+    4,
+>;
+type HidingChallengeMmcs = ExtensionMmcs<Val, Challenge, HidingValMmcs>;
+type HidingPcs = HidingFriPcs<Val, Dft, HidingValMmcs, HidingChallengeMmcs, StdRng>;
+type ZkConfig = StarkConfig<HidingPcs, Challenge, Challenger>;
+
+fn make_config_with(perm: &Perm, log_blowup: usize, num_queries: usize) -> MyConfig {
+    let val_mmcs = ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm.clone()));
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig::new(log_blowup, num_queries, 8, challenge_mmcs);
+    MyConfig::new(Pcs::new(Dft::default(), val_mmcs, fri_config))
+}
+
+fn make_zk_config(perm: &Perm, log_blowup: usize, num_queries: usize) -> ZkConfig {
+    ZkConfig::new(make_hiding_pcs(perm, log_blowup, num_queries))
+}
+
+fn make_hiding_pcs(perm: &Perm, log_blowup: usize, num_queries: usize) -> HidingPcs {
+    let val_mmcs = HidingValMmcs::new(
+        MyHash::new(perm.clone()),
+        MyCompress::new(perm.clone()),
+        StdRng::from_entropy(),
+    );
+    let challenge_mmcs = HidingChallengeMmcs::new(val_mmcs.clone());
+    HidingPcs::new(
+        Dft::default(),
+        val_mmcs,
+        FriConfig::new(log_blowup, num_queries, 8, challenge_mmcs),
+        4,
+        StdRng::from_entropy(),
+    )
+}
+
+#[test]
+fn test_zk_prove_verify() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_zk_config(&perm, 2, 28);
+    let c = Val::from_canonical_u32(7);
+    let public_values = vec![c];
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(
+        &config,
+        &ConstantAir,
+        &mut challenger,
+        constant_trace(c, 1 << 5),
+        &public_values,
+    );
+
+    let serialized_proof = postcard::to_allocvec(&proof).expect("unable to serialize proof");
+    let proof = postcard::from_bytes(&serialized_proof).expect("unable to deserialize proof");
+
+    let mut challenger = Challenger::new(perm);
+    verify(
+        &config,
+        &ConstantAir,
+        &mut challenger,
+        &proof,
+        &public_values,
+    )
+    .expect("verification failed");
+}
+
+#[test]
+fn test_zk_permutation() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let config = make_zk_config(&perm, 2, 28);
+    let height = 1 << 4;
+    let values = (0..height)
+        .flat_map(|i| {
+            [
+                Val::from_canonical_usize(i),
+                Val::from_canonical_usize((5 * i + 3) % height),
+            ]
+        })
+        .collect();
+    let trace = RowMajorMatrix::new(values, 2);
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &PermutationAir, &mut challenger, trace, &vec![]);
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &PermutationAir, &mut challenger, &proof, &vec![])
+        .expect("verification failed");
+}
+
+#[test]
+fn test_zk_openings_are_masked() {
+    let perm = Perm::new_from_rng_128(&mut thread_rng());
+    let c = Val::from_canonical_u32(7);
+    let public_values = vec![c];
+    let expected_local = [Challenge::from_base(c), Challenge::from_base(c.square())];
+
+    // Without zero knowledge, the openings reveal the trace and the quotient is zero.
+    let config = make_config_with(&perm, 2, 28);
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(
+        &config,
+        &ConstantAir,
+        &mut challenger,
+        constant_trace(c, 1 << 5),
+        &public_values,
+    );
+    let opened_values = proof.opened_values();
+    for row in opened_values.trace() {
+        assert_eq!(row, &expected_local);
+    }
+    assert!(opened_values
+        .quotient_chunks()
+        .iter()
+        .flatten()
+        .all(|&v| v == Challenge::ZERO));
+
+    // With zero knowledge, they are masked by random polynomials.
+    let config = make_zk_config(&perm, 2, 28);
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(
+        &config,
+        &ConstantAir,
+        &mut challenger,
+        constant_trace(c, 1 << 5),
+        &public_values,
+    );
+    let opened_values = proof.opened_values();
+    for (opened, expected) in opened_values
+        .trace()
+        .iter()
+        .flatten()
+        .zip(expected_local.iter().cycle())
+    {
+        assert_ne!(opened, expected);
+    }
+    assert!(opened_values
+        .quotient_chunks()
+        .iter()
+        .all(|chunk| chunk.iter().any(|&v| v != Challenge::ZERO)));
+
+    let mut challenger = Challenger::new(perm);
+    verify(
+        &config,
+        &ConstantAir,
+        &mut challenger,
+        &proof,
+        &public_values,
+    )
+    .expect("verification failed");
+}